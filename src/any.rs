@@ -1,6 +1,8 @@
 //! Helper module which defines the `Any` trait to to allow dynamic value handling.
 
+use crate::datetime::{DateTime, Duration};
 use crate::engine::{Array, Map};
+use crate::fn_native::FnPtr;
 use crate::parser::INT;
 
 #[cfg(not(feature = "no_float"))]
@@ -196,6 +198,9 @@ impl Dynamic {
 
             #[cfg(not(feature = "no_std"))]
             Union::Variant(value) if value.is::<Instant>() => "timestamp",
+            Union::Variant(value) if value.is::<FnPtr>() => "Fn",
+            Union::Variant(value) if value.is::<DateTime>() => "datetime",
+            Union::Variant(value) if value.is::<Duration>() => "duration",
             Union::Variant(value) => (***value).type_name(),
         }
     }
@@ -213,6 +218,15 @@ impl fmt::Display for Dynamic {
             Union::Float(value) => write!(f, "{}", value),
             Union::Array(value) => write!(f, "{:?}", value),
             Union::Map(value) => write!(f, "#{:?}", value),
+            Union::Variant(value) if value.is::<FnPtr>() => {
+                write!(f, "{}", value.downcast_ref::<FnPtr>().unwrap())
+            }
+            Union::Variant(value) if value.is::<DateTime>() => {
+                write!(f, "{}", value.downcast_ref::<DateTime>().unwrap())
+            }
+            Union::Variant(value) if value.is::<Duration>() => {
+                write!(f, "{}", value.downcast_ref::<Duration>().unwrap())
+            }
             Union::Variant(_) => write!(f, "?"),
         }
     }
@@ -230,6 +244,15 @@ impl fmt::Debug for Dynamic {
             Union::Float(value) => write!(f, "{:?}", value),
             Union::Array(value) => write!(f, "{:?}", value),
             Union::Map(value) => write!(f, "#{:?}", value),
+            Union::Variant(value) if value.is::<FnPtr>() => {
+                write!(f, "{}", value.downcast_ref::<FnPtr>().unwrap())
+            }
+            Union::Variant(value) if value.is::<DateTime>() => {
+                write!(f, "{:?}", value.downcast_ref::<DateTime>().unwrap())
+            }
+            Union::Variant(value) if value.is::<Duration>() => {
+                write!(f, "{:?}", value.downcast_ref::<Duration>().unwrap())
+            }
             Union::Variant(_) => write!(f, "<dynamic>"),
         }
     }
@@ -527,6 +550,11 @@ impl<T: Variant + Clone> From<Vec<T>> for Dynamic {
         )))
     }
 }
+impl From<Map> for Dynamic {
+    fn from(value: Map) -> Self {
+        Self(Union::Map(Box::new(value)))
+    }
+}
 impl<T: Variant + Clone> From<HashMap<String, T>> for Dynamic {
     fn from(value: HashMap<String, T>) -> Self {
         Self(Union::Map(Box::new(
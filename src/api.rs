@@ -1,20 +1,28 @@
 //! Module that defines the extern API of `Engine`.
 
 use crate::any::{Dynamic, Variant};
-use crate::engine::{make_getter, make_setter, Engine, Map, State};
+use crate::engine::{
+    calc_fn_spec, make_getter, make_setter, Engine, FnCallArgs, Map, State, FUNC_INDEXER_GET,
+    FUNC_INDEXER_SET, FUNC_TO_STRING, KEYWORD_DEBUG, KEYWORD_PRINT,
+};
 use crate::error::ParseError;
 use crate::fn_call::FuncArgs;
+use crate::fn_native::NativeCallContext;
 use crate::fn_register::RegisterFn;
+use crate::json;
 use crate::optimize::{optimize_into_ast, OptimizationLevel};
 use crate::parser::{parse, parse_global_expr, AST};
 use crate::result::EvalAltResult;
 use crate::scope::Scope;
-use crate::token::{lex, Position};
+use crate::syntax::EvalContext;
+use crate::token::{is_identifier, is_symbol, lex, Position, Token, SYMBOL_OPERATORS};
 
 use crate::stdlib::{
     any::{type_name, TypeId},
     boxed::Box,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display},
+    format,
     string::{String, ToString},
     vec::Vec,
 };
@@ -42,6 +50,29 @@ pub trait ObjectSetCallback<T, U>: Fn(&mut T, U) + 'static {}
 #[cfg(not(feature = "sync"))]
 impl<F: Fn(&mut T, U) + 'static, T, U> ObjectSetCallback<T, U> for F {}
 
+#[cfg(feature = "sync")]
+pub trait ObjectIndexerGetCallback<T, X, U>: Fn(&mut T, X) -> U + Send + Sync + 'static {}
+#[cfg(feature = "sync")]
+impl<F: Fn(&mut T, X) -> U + Send + Sync + 'static, T, X, U> ObjectIndexerGetCallback<T, X, U>
+    for F
+{
+}
+
+#[cfg(not(feature = "sync"))]
+pub trait ObjectIndexerGetCallback<T, X, U>: Fn(&mut T, X) -> U + 'static {}
+#[cfg(not(feature = "sync"))]
+impl<F: Fn(&mut T, X) -> U + 'static, T, X, U> ObjectIndexerGetCallback<T, X, U> for F {}
+
+#[cfg(feature = "sync")]
+pub trait ObjectIndexerSetCallback<T, X, U>: Fn(&mut T, X, U) + Send + Sync + 'static {}
+#[cfg(feature = "sync")]
+impl<F: Fn(&mut T, X, U) + Send + Sync + 'static, T, X, U> ObjectIndexerSetCallback<T, X, U> for F {}
+
+#[cfg(not(feature = "sync"))]
+pub trait ObjectIndexerSetCallback<T, X, U>: Fn(&mut T, X, U) + 'static {}
+#[cfg(not(feature = "sync"))]
+impl<F: Fn(&mut T, X, U) + 'static, T, X, U> ObjectIndexerSetCallback<T, X, U> for F {}
+
 #[cfg(feature = "sync")]
 pub trait IteratorCallback:
     Fn(Dynamic) -> Box<dyn Iterator<Item = Dynamic>> + Send + Sync + 'static
@@ -58,11 +89,71 @@ pub trait IteratorCallback: Fn(Dynamic) -> Box<dyn Iterator<Item = Dynamic>> + '
 #[cfg(not(feature = "sync"))]
 impl<F: Fn(Dynamic) -> Box<dyn Iterator<Item = Dynamic>> + 'static> IteratorCallback for F {}
 
+#[cfg(feature = "sync")]
+pub trait RawFnCallback:
+    Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>
+    + Send
+    + Sync
+    + 'static
+{
+}
+#[cfg(feature = "sync")]
+impl<
+        F: Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>
+            + Send
+            + Sync
+            + 'static,
+    > RawFnCallback for F
+{
+}
+
+#[cfg(not(feature = "sync"))]
+pub trait RawFnCallback:
+    Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>> + 'static
+{
+}
+#[cfg(not(feature = "sync"))]
+impl<
+        F: Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>> + 'static,
+    > RawFnCallback for F
+{
+}
+
+#[cfg(feature = "sync")]
+pub trait OnVarCallback:
+    Fn(&str, usize, &EvalContext) -> Result<Option<Dynamic>, Box<EvalAltResult>> + Send + Sync + 'static
+{
+}
+#[cfg(feature = "sync")]
+impl<
+        F: Fn(&str, usize, &EvalContext) -> Result<Option<Dynamic>, Box<EvalAltResult>>
+            + Send
+            + Sync
+            + 'static,
+    > OnVarCallback for F
+{
+}
+
+#[cfg(not(feature = "sync"))]
+pub trait OnVarCallback:
+    Fn(&str, usize, &EvalContext) -> Result<Option<Dynamic>, Box<EvalAltResult>> + 'static
+{
+}
+#[cfg(not(feature = "sync"))]
+impl<F: Fn(&str, usize, &EvalContext) -> Result<Option<Dynamic>, Box<EvalAltResult>> + 'static>
+    OnVarCallback for F
+{
+}
+
 /// Engine public API
 impl Engine {
     /// Register a custom type for use with the `Engine`.
     /// The type must implement `Clone`.
     ///
+    /// Values of the type cannot be printed or concatenated to strings until their formatting is
+    /// registered: use `register_display` to wire up `print`, `to_string` and `+`, and
+    /// `register_debug` to wire up `debug`.
+    ///
     /// # Example
     ///
     /// ```
@@ -151,6 +242,98 @@ impl Engine {
             .insert(type_name::<T>().to_string(), name.to_string());
     }
 
+    /// Register the `Display` implementation of a custom type with the `Engine`,
+    /// so that values of the type can be used with `print`, `to_string` and
+    /// string concatenation with `+`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::fmt;
+    ///
+    /// #[derive(Clone)]
+    /// struct TestStruct {
+    ///     field: i64
+    /// }
+    ///
+    /// impl TestStruct {
+    ///     fn new() -> Self { TestStruct { field: 42 } }
+    /// }
+    ///
+    /// impl fmt::Display for TestStruct {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         write!(f, "<{}>", self.field)
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Register the custom type and its `Display` implementation.
+    /// engine.register_type::<TestStruct>();
+    /// engine.register_display::<TestStruct>();
+    ///
+    /// engine.register_fn("new_ts", TestStruct::new);
+    ///
+    /// assert_eq!(
+    ///     engine.eval::<String>(r#"let x = new_ts(); "value: " + x"#)?,
+    ///     "value: <42>"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    pub fn register_display<T: Variant + Clone + Display>(&mut self) {
+        self.register_fn(KEYWORD_PRINT, |x: &mut T| x.to_string());
+        self.register_fn(FUNC_TO_STRING, |x: &mut T| x.to_string());
+        self.register_fn("+", |s: String, x: T| format!("{}{}", s, x));
+        self.register_fn("+", |x: T, s: String| format!("{}{}", x, s));
+    }
+
+    /// Register the `Debug` implementation of a custom type with the `Engine`,
+    /// so that values of the type can be used with `debug`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Debug, Clone)]
+    /// struct TestStruct {
+    ///     field: i64
+    /// }
+    ///
+    /// impl TestStruct {
+    ///     fn new() -> Self { TestStruct { field: 42 } }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    /// use std::sync::{Arc, RwLock};
+    ///
+    /// let mut engine = Engine::new();
+    /// let log = Arc::new(RwLock::new(String::new()));
+    ///
+    /// let logger = log.clone();
+    /// engine.on_debug(move |s| logger.write().unwrap().push_str(s));
+    ///
+    /// // Register the custom type and its `Debug` implementation.
+    /// engine.register_type::<TestStruct>();
+    /// engine.register_debug::<TestStruct>();
+    ///
+    /// engine.register_fn("new_ts", TestStruct::new);
+    ///
+    /// engine.consume("debug(new_ts())")?;
+    ///
+    /// assert_eq!(*log.read().unwrap(), "TestStruct { field: 42 }");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    pub fn register_debug<T: Variant + Clone + Debug>(&mut self) {
+        self.register_fn(KEYWORD_DEBUG, |x: &mut T| format!("{:?}", x));
+    }
+
     /// Register an iterator adapter for a type with the `Engine`.
     /// This is an advanced feature.
     pub fn register_iterator<T: Variant + Clone, F: IteratorCallback>(&mut self, f: F) {
@@ -299,6 +482,300 @@ impl Engine {
         self.register_set(name, set_fn);
     }
 
+    /// Register an indexer getter function for a registered type with the `Engine`,
+    /// so that `obj[index]` can be read.
+    ///
+    /// Indexers can be registered for different index types (e.g. integers and strings) on the same type.
+    /// Arrays, object maps and strings cannot have indexers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Clone)]
+    /// struct TestStruct {
+    ///     fields: Vec<i64>
+    /// }
+    ///
+    /// impl TestStruct {
+    ///     fn new() -> Self                            { TestStruct { fields: vec![1, 2, 3, 4, 5] } }
+    ///
+    ///     // Even a getter must start with `&mut self` and not `&self`.
+    ///     fn get_field(&mut self, index: i64) -> i64  { self.fields[index as usize] }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Register the custom type.
+    /// engine.register_type::<TestStruct>();
+    ///
+    /// engine.register_fn("new_ts", TestStruct::new);
+    ///
+    /// // Register an indexer.
+    /// engine.register_indexer_get(TestStruct::get_field);
+    ///
+    /// assert_eq!(engine.eval::<i64>("let a = new_ts(); a[2]")?, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    pub fn register_indexer_get<T, X, U, F>(&mut self, callback: F)
+    where
+        T: Variant + Clone,
+        X: Variant + Clone,
+        U: Variant + Clone,
+        F: ObjectIndexerGetCallback<T, X, U>,
+    {
+        self.register_fn(FUNC_INDEXER_GET, callback);
+    }
+
+    /// Register an indexer setter function for a registered type with the `Engine`,
+    /// so that `obj[index] = value` can be assigned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Clone)]
+    /// struct TestStruct {
+    ///     fields: Vec<i64>
+    /// }
+    ///
+    /// impl TestStruct {
+    ///     fn new() -> Self                                        { TestStruct { fields: vec![1, 2, 3, 4, 5] } }
+    ///     fn set_field(&mut self, index: i64, value: i64)         { self.fields[index as usize] = value; }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Register the custom type.
+    /// engine.register_type::<TestStruct>();
+    ///
+    /// engine.register_fn("new_ts", TestStruct::new);
+    ///
+    /// // Register an indexer.
+    /// engine.register_indexer_set(TestStruct::set_field);
+    ///
+    /// assert_eq!(
+    ///     engine.eval::<TestStruct>("let a = new_ts(); a[2] = 42; a")?.fields[2],
+    ///     42
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    pub fn register_indexer_set<T, X, U, F>(&mut self, callback: F)
+    where
+        T: Variant + Clone,
+        X: Variant + Clone,
+        U: Variant + Clone,
+        F: ObjectIndexerSetCallback<T, X, U>,
+    {
+        self.register_fn(FUNC_INDEXER_SET, callback);
+    }
+
+    /// Shorthand for registering both indexer getter and setter functions
+    /// for a registered type with the `Engine`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// #[derive(Clone)]
+    /// struct TestStruct {
+    ///     fields: Vec<i64>
+    /// }
+    ///
+    /// impl TestStruct {
+    ///     fn new() -> Self                                        { TestStruct { fields: vec![1, 2, 3, 4, 5] } }
+    ///     fn get_field(&mut self, index: i64) -> i64              { self.fields[index as usize] }
+    ///     fn set_field(&mut self, index: i64, value: i64)         { self.fields[index as usize] = value; }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Register the custom type.
+    /// engine.register_type::<TestStruct>();
+    ///
+    /// engine.register_fn("new_ts", TestStruct::new);
+    ///
+    /// // Register an indexer.
+    /// engine.register_indexer_get_set(TestStruct::get_field, TestStruct::set_field);
+    ///
+    /// assert_eq!(engine.eval::<i64>("let a = new_ts(); a[2] = 42; a[2]")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    pub fn register_indexer_get_set<T, X, U, G, S>(&mut self, get_fn: G, set_fn: S)
+    where
+        T: Variant + Clone,
+        X: Variant + Clone,
+        U: Variant + Clone,
+        G: ObjectIndexerGetCallback<T, X, U>,
+        S: ObjectIndexerSetCallback<T, X, U>,
+    {
+        self.register_indexer_get(get_fn);
+        self.register_indexer_set(set_fn);
+    }
+
+    /// Register a function taking its arguments as a slice of `Dynamic` values.
+    ///
+    /// Unlike `register_fn`, the number of parameters is not limited and their types are given as
+    /// a list of `TypeId`'s, so functions can be generated at runtime, e.g. from metadata.
+    /// A parameter of type `Dynamic` (i.e. `TypeId::of::<Dynamic>()`) accepts an argument of any type,
+    /// although an overload with a matching concrete type is preferred.
+    ///
+    /// The function receives a `NativeCallContext` and the arguments, which are guaranteed to match
+    /// the parameter types in number and type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use std::any::TypeId;
+    /// use rhai::{Dynamic, Engine};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Add an integer to a value of any type, converting the result to a string
+    /// engine.register_raw_fn(
+    ///     "add_describe",
+    ///     &[TypeId::of::<Dynamic>(), TypeId::of::<i64>()],
+    ///     |_, args| {
+    ///         let y = args[1].clone().cast::<i64>();
+    ///         Ok(format!("{} + {}", args[0], y).into())
+    ///     },
+    /// );
+    ///
+    /// assert_eq!(engine.eval::<String>(r#"add_describe("x", 42)"#)?, "x + 42");
+    /// assert_eq!(engine.eval::<String>("add_describe(true, 1)")?, "true + 1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_raw_fn<F: RawFnCallback>(&mut self, name: &str, arg_types: &[TypeId], func: F) {
+        let hash = calc_fn_spec(name, arg_types.iter().cloned());
+
+        if arg_types.contains(&TypeId::of::<Dynamic>()) && !self.functions.contains_key(&hash) {
+            self.dynamic_fns
+                .entry((name.into(), arg_types.len()))
+                .or_default()
+                .push(arg_types.to_vec());
+        }
+
+        self.functions.insert(hash, Box::new(func));
+    }
+
+    /// Register a custom binary operator with the `Engine`.
+    ///
+    /// The operator can either be a sequence of symbol characters (e.g. `**` or `=~`) or a word
+    /// (e.g. `contains`).  Once registered, `a op b` is parsed into a call to the function named
+    /// after the operator, i.e. `op(a, b)`, so that function must be registered separately.
+    ///
+    /// The `precedence` decides how tightly the operator binds, compared to built-in operators:
+    ///
+    /// | Precedence | Built-in operators              |
+    /// | :--------: | ------------------------------- |
+    /// |     30     | `??`                            |
+    /// |     40     | `\|\|`, `^`, `\|`            |
+    /// |     50     | `&&`, `&`                       |
+    /// |     60     | `==`, `!=`, `<`, `<=`, `>`, `>=` |
+    /// |     70     | `in`                            |
+    /// |     80     | `+`, `-`                        |
+    /// |     90     | `*`, `/`, `~`                   |
+    /// |    100     | `<<`, `>>`                      |
+    /// |    110     | `%`                             |
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the precedence is zero, if the operator is a keyword,
+    /// a built-in operator or the start of one, if it contains characters that cannot be part of
+    /// an operator, or if it would change the meaning of valid scripts (e.g. `!!` in `!!x`).
+    ///
+    /// A word operator is only recognized after an operand, so it can still be used as
+    /// a function or method name (e.g. `contains(a, b)` or `a.contains(b)`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterFn};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Register '**' with the same precedence as '*'
+    /// engine.register_custom_operator("**", 90).unwrap();
+    /// engine.register_fn("**", |x: i64, y: i64| x.pow(y as u32));
+    ///
+    /// assert_eq!(engine.eval::<i64>("1 + 2 ** 3 * 5")?, 41);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_custom_operator(&mut self, symbol: &str, precedence: u8) -> Result<(), String> {
+        if precedence == 0 {
+            return Err(format!(
+                "Precedence of custom operator '{}' cannot be zero",
+                symbol
+            ));
+        }
+
+        let scripts = [symbol];
+        let no_custom_operators = HashMap::new();
+        let no_disabled_symbols = HashSet::new();
+        let tokens: Vec<_> = lex(&scripts, &no_custom_operators, &no_disabled_symbols)
+            .take_while(|(token, _)| *token != Token::EOF)
+            .collect();
+
+        if symbol.is_empty() {
+            return Err("Custom operator cannot be empty".into());
+        } else if is_identifier(symbol) {
+            // A word operator must not be a keyword
+            match tokens.as_slice() {
+                [(Token::Identifier(_), _)] => (),
+                _ => {
+                    return Err(format!(
+                        "'{}' is a keyword or not a valid identifier",
+                        symbol
+                    ))
+                }
+            }
+        } else if !symbol.chars().all(is_symbol)
+            || symbol.starts_with("//")
+            || symbol.starts_with("/*")
+        {
+            return Err(format!("'{}' is not a valid operator", symbol));
+        } else if SYMBOL_OPERATORS.iter().any(|op| op.starts_with(symbol)) {
+            // A symbol operator must not be a built-in operator or the start of one
+            return Err(format!(
+                "'{}' is a built-in operator or the start of one",
+                symbol
+            ));
+        } else if tokens[1..].iter().any(|(token, _)| {
+            matches!(
+                token,
+                Token::Plus | Token::UnaryPlus | Token::Minus | Token::UnaryMinus | Token::Bang
+            )
+        }) {
+            // A symbol operator must not take over valid scripts, such as '!!x' or 'a <- b'
+            return Err(format!(
+                "'{}' conflicts with built-in unary operators",
+                symbol
+            ));
+        }
+
+        self.custom_operators.insert(symbol.into(), precedence);
+        Ok(())
+    }
+
     /// Compile a string into an `AST`, which can be used later for evaluation.
     ///
     /// # Example
@@ -371,7 +848,7 @@ impl Engine {
         optimization_level: OptimizationLevel,
     ) -> Result<AST, Box<ParseError>> {
         let scripts = [script];
-        let stream = lex(&scripts);
+        let stream = lex(&scripts, &self.custom_operators, &self.disabled_symbols);
         parse(&mut stream.peekable(), self, scope, optimization_level)
     }
 
@@ -456,7 +933,9 @@ impl Engine {
     /// Parse a JSON string into a map.
     ///
     /// Set `has_null` to `true` in order to map `null` values to `()`.
-    /// Setting it to `false` will cause a _variable not found_ error during parsing.
+    /// Setting it to `false` will cause a syntax error when a `null` value is encountered.
+    ///
+    /// The JSON text must be an object. Use `parse_json_value` to parse any JSON value.
     ///
     /// # Example
     ///
@@ -478,24 +957,108 @@ impl Engine {
     /// ```
     #[cfg(not(feature = "no_object"))]
     pub fn parse_json(&self, json: &str, has_null: bool) -> Result<Map, Box<EvalAltResult>> {
-        let mut scope = Scope::new();
-
-        // Trims the JSON string and add a '#' in front
-        let scripts = ["#", json.trim()];
-        let stream = lex(&scripts);
-        let ast = parse_global_expr(
-            &mut stream.peekable(),
-            self,
-            &scope,
-            OptimizationLevel::None,
-        )?;
-
-        // Handle null - map to ()
-        if has_null {
-            scope.push_constant("null", ());
-        }
+        let value = json::parse_json(json, has_null)?;
+        let type_name = self.map_type_name(value.type_name());
 
-        self.eval_ast_with_scope(&mut scope, &ast)
+        value.try_cast::<Map>().ok_or_else(|| {
+            Box::new(EvalAltResult::ErrorMismatchDataType(
+                "map".into(),
+                type_name.into(),
+                Position::none(),
+            ))
+        })
+    }
+
+    /// Parse a JSON string containing any JSON value into a `Dynamic`.
+    ///
+    /// Objects become object maps, arrays become arrays and `null` becomes `()`.
+    /// Integers become `INT`, other numbers become `FLOAT`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// assert_eq!(engine.parse_json_value("42")?.cast::<i64>(), 42);
+    ///
+    /// # #[cfg(not(feature = "no_index"))]
+    /// # #[cfg(not(feature = "no_object"))]
+    /// # {
+    /// use rhai::Array;
+    ///
+    /// let value = engine.parse_json_value(r#"[1, "two", null, {"a": [true]}]"#)?;
+    ///
+    /// let array = value.cast::<Array>();
+    /// assert_eq!(array.len(), 4);
+    /// assert_eq!(array[0].as_int().unwrap(), 1);
+    /// assert_eq!(array[1].as_str().unwrap(), "two");
+    /// assert!(array[2].is::<()>());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_json_value(&self, json: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(json::parse_json(json, true)?)
+    }
+
+    /// Serialize a `Dynamic` into a compact JSON string.
+    ///
+    /// `()` becomes `null` and characters become strings. Custom types and non-finite
+    /// floating-point numbers cannot be represented in JSON and cause an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Dynamic, Engine};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// # #[cfg(not(feature = "no_index"))]
+    /// # {
+    /// let value = engine.eval::<Dynamic>(r#"[1, "say \"hi\"", (), true]"#)?;
+    ///
+    /// assert_eq!(engine.to_json(&value)?, r#"[1,"say \"hi\"",null,true]"#);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_json(&self, value: &Dynamic) -> Result<String, Box<EvalAltResult>> {
+        json::to_json(value, None, |name| self.map_type_name(name))
+    }
+
+    /// Serialize a `Dynamic` into a pretty-printed JSON string,
+    /// with each nesting level indented by `indent` spaces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Dynamic, Engine};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// # #[cfg(not(feature = "no_index"))]
+    /// # {
+    /// let value = engine.eval::<Dynamic>("[1, [2, 3], []]")?;
+    ///
+    /// assert_eq!(
+    ///     engine.to_json_pretty(&value, 2)?,
+    ///     "[\n  1,\n  [\n    2,\n    3\n  ],\n  []\n]"
+    /// );
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_json_pretty(
+        &self,
+        value: &Dynamic,
+        indent: usize,
+    ) -> Result<String, Box<EvalAltResult>> {
+        json::to_json(value, Some(indent), |name| self.map_type_name(name))
     }
 
     /// Compile a string containing an expression into an `AST`,
@@ -567,7 +1130,7 @@ impl Engine {
         script: &str,
     ) -> Result<AST, Box<ParseError>> {
         let scripts = [script];
-        let stream = lex(&scripts);
+        let stream = lex(&scripts, &self.custom_operators, &self.disabled_symbols);
 
         parse_global_expr(&mut stream.peekable(), self, scope, self.optimization_level)
     }
@@ -716,7 +1279,7 @@ impl Engine {
         script: &str,
     ) -> Result<T, Box<EvalAltResult>> {
         let scripts = [script];
-        let stream = lex(&scripts);
+        let stream = lex(&scripts, &self.custom_operators, &self.disabled_symbols);
         // Since the AST will be thrown away afterwards, don't bother to optimize it
         let ast = parse_global_expr(&mut stream.peekable(), self, scope, OptimizationLevel::None)?;
         self.eval_ast_with_scope(scope, &ast)
@@ -840,7 +1403,7 @@ impl Engine {
         script: &str,
     ) -> Result<(), Box<EvalAltResult>> {
         let scripts = [script];
-        let stream = lex(&scripts);
+        let stream = lex(&scripts, &self.custom_operators, &self.disabled_symbols);
 
         // Since the AST will be thrown away afterwards, don't bother to optimize it
         let ast = parse(&mut stream.peekable(), self, scope, OptimizationLevel::None)?;
@@ -920,15 +1483,24 @@ impl Engine {
         args: A,
     ) -> Result<T, Box<EvalAltResult>> {
         let mut arg_values = args.into_vec();
+        let types_list: Vec<_> = arg_values
+            .iter()
+            .map(|arg| self.map_type_name(arg.type_name()))
+            .collect();
         let mut args: Vec<_> = arg_values.iter_mut().collect();
         let fn_lib = ast.1.as_ref();
         let pos = Position::none();
 
         let fn_def = fn_lib
-            .get_function(name, args.len())
-            .ok_or_else(|| Box::new(EvalAltResult::ErrorFunctionNotFound(name.to_string(), pos)))?;
+            .get_function(name, args.len(), |i| types_list[i])
+            .ok_or_else(|| {
+                Box::new(EvalAltResult::ErrorFunctionNotFound(
+                    format!("{} ({})", name, types_list.join(", ")),
+                    pos,
+                ))
+            })?;
 
-        let result = self.call_fn_from_lib(Some(scope), fn_lib, fn_def, &mut args, pos, 0)?;
+        let result = self.call_fn_from_lib(Some(scope), fn_lib, fn_def, &mut args, None, pos, 0)?;
 
         let return_type = self.map_type_name(result.type_name());
 
@@ -960,8 +1532,9 @@ impl Engine {
     ) -> AST {
         let fn_lib = ast
             .1
-            .iter()
-            .map(|(_, fn_def)| fn_def.as_ref().clone())
+            .values()
+            .flatten()
+            .map(|fn_def| fn_def.as_ref().clone())
             .collect();
         optimize_into_ast(self, scope, ast.0, fn_lib, optimization_level)
     }
@@ -1079,4 +1652,36 @@ impl Engine {
     pub fn on_debug(&mut self, callback: impl Fn(&str) + 'static) {
         self.debug = Box::new(callback);
     }
+
+    /// Register a callback that resolves variables on demand, so that host-provided values
+    /// need not be pushed into a `Scope` before every run.
+    ///
+    /// The callback is consulted whenever a script reads a variable that is not found in the `Scope`,
+    /// and is passed the variable name, its offset from the end of the `Scope` as calculated during
+    /// parsing (zero if not known, i.e. the variable is not declared in the script), and the current
+    /// `EvalContext`.  Returning `Ok(None)` means that the variable is not found.
+    ///
+    /// Variables provided by the callback are read-only.  Under strict variables mode, the names of
+    /// the variables provided by the callback must be declared via `declare_external_var`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.on_var(|name, index, _| match name {
+    ///     "MYSTIC_NUMBER" if index == 0 => Ok(Some(42_i64.into())),
+    ///     _ => Ok(None),
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<i64>("MYSTIC_NUMBER")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_var<F: OnVarCallback>(&mut self, callback: F) {
+        self.var_resolver = Some(Box::new(callback));
+    }
 }
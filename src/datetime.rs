@@ -0,0 +1,994 @@
+//! Module defining the date/time types: `DateTime` (a point in time in UTC) and `Duration`.
+
+use crate::stdlib::{
+    fmt, format,
+    iter::Peekable,
+    ops::Neg,
+    str::Chars,
+    string::{String, ToString},
+};
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
+
+#[cfg(not(feature = "no_std"))]
+use crate::stdlib::time::{SystemTime, UNIX_EPOCH};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Days from the Unix epoch to 0000-01-01, the earliest supported date.
+const MIN_DAYS: i64 = -719_528;
+/// Days from the Unix epoch to 9999-12-31, the latest supported date.
+const MAX_DAYS: i64 = 2_932_896;
+
+/// Largest magnitude of a `Duration`, in nanoseconds.
+const MAX_DURATION: i128 = i64::MAX as i128 * NANOS_PER_SEC;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days since the Unix epoch of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date (year, month, day) in the proleptic Gregorian calendar of a number of days since the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Format the fractional part of a second with 3, 6 or 9 digits, or nothing if it is zero.
+fn format_fraction(nanos: u32) -> String {
+    if nanos == 0 {
+        return "".to_string();
+    }
+
+    let digits = format!("{:09}", nanos);
+
+    if digits.ends_with("000000") {
+        format!(".{}", &digits[..3])
+    } else if digits.ends_with("000") {
+        format!(".{}", &digits[..6])
+    } else {
+        format!(".{}", digits)
+    }
+}
+
+/// A point in time in UTC, with nanosecond precision, between the years 0 and 9999.
+///
+/// Dates follow the proleptic Gregorian calendar.  Leap seconds are not supported.
+///
+/// # Example
+///
+/// ```
+/// use rhai::{DateTime, Duration};
+///
+/// let date = DateTime::from_date_time(2026, 10, 17, 12, 0, 0).unwrap();
+/// assert_eq!(date.weekday(), 6);
+///
+/// let later = date.checked_add(Duration::from_seconds(86_400)).unwrap();
+/// assert_eq!(later.to_string(), "2026-10-18T12:00:00Z");
+/// assert_eq!(later.format("%d %B %Y").unwrap(), "18 October 2026");
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DateTime {
+    /// Nanoseconds since the Unix epoch.
+    nanos: i128,
+}
+
+impl DateTime {
+    /// The Unix epoch, 1970-01-01T00:00:00Z.
+    pub const UNIX_EPOCH: Self = Self { nanos: 0 };
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        let min = MIN_DAYS as i128 * SECS_PER_DAY as i128 * NANOS_PER_SEC;
+        let max = (MAX_DAYS + 1) as i128 * SECS_PER_DAY as i128 * NANOS_PER_SEC;
+
+        if nanos < min || nanos >= max {
+            None
+        } else {
+            Some(Self { nanos })
+        }
+    }
+
+    /// Build a date/time from its components, applying an offset from UTC in seconds.
+    pub(crate) fn from_parts(
+        (year, month, day): (i128, i128, i128),
+        (hour, minute, second): (i128, i128, i128),
+        nanosecond: u32,
+        offset: i128,
+    ) -> Result<Self, String> {
+        if !(0..=9999).contains(&year)
+            || !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year as i64, month as i64) as i128
+        {
+            return Err(format!("invalid date {:04}-{:02}-{:02}", year, month, day));
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(format!(
+                "invalid time {:02}:{:02}:{:02}",
+                hour, minute, second
+            ));
+        }
+
+        let days = days_from_civil(year as i64, month as i64, day as i64) as i128;
+        let seconds = days * SECS_PER_DAY as i128 + hour * 3600 + minute * 60 + second - offset;
+
+        Self::from_nanos(seconds * NANOS_PER_SEC + nanosecond as i128)
+            .ok_or_else(|| "date/time out of range".to_string())
+    }
+
+    /// Get the current date/time from the system clock.
+    ///
+    /// Not available under the `no_std` feature.
+    #[cfg(not(feature = "no_std"))]
+    pub fn now() -> Self {
+        let nanos = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as i128,
+            Err(err) => -(err.duration().as_nanos() as i128),
+        };
+
+        Self::from_nanos(nanos).unwrap_or(Self::UNIX_EPOCH)
+    }
+
+    /// Create a date/time at midnight of a date.
+    ///
+    /// Returns `None` if the date is invalid or the year is not between 0 and 9999.
+    pub fn from_date(year: i64, month: u32, day: u32) -> Option<Self> {
+        Self::from_date_time(year, month, day, 0, 0, 0)
+    }
+
+    /// Create a date/time from a date and a time of day.
+    ///
+    /// Returns `None` if the date or time is invalid or the year is not between 0 and 9999.
+    pub fn from_date_time(
+        year: i64,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        let date = (year as i128, month as i128, day as i128);
+        let time = (hour as i128, minute as i128, second as i128);
+        Self::from_parts(date, time, 0, 0).ok()
+    }
+
+    /// Create a date/time from the number of seconds since the Unix epoch, plus nanoseconds.
+    ///
+    /// Returns `None` if `nanosecond` is not less than one billion or the date/time is out of range.
+    pub fn from_unix_time(seconds: i64, nanosecond: u32) -> Option<Self> {
+        if nanosecond as i128 >= NANOS_PER_SEC {
+            return None;
+        }
+        Self::from_nanos(seconds as i128 * NANOS_PER_SEC + nanosecond as i128)
+    }
+
+    /// Number of whole seconds since the Unix epoch (negative before 1970).
+    pub fn unix_time(&self) -> i64 {
+        self.nanos.div_euclid(NANOS_PER_SEC) as i64
+    }
+
+    fn date(&self) -> (i64, i64, i64) {
+        civil_from_days(self.unix_time().div_euclid(SECS_PER_DAY))
+    }
+
+    fn seconds_of_day(&self) -> i64 {
+        self.unix_time().rem_euclid(SECS_PER_DAY)
+    }
+
+    /// The year, between 0 and 9999.
+    pub fn year(&self) -> i64 {
+        self.date().0
+    }
+
+    /// The month, between 1 and 12.
+    pub fn month(&self) -> u32 {
+        self.date().1 as u32
+    }
+
+    /// The day of the month, between 1 and 31.
+    pub fn day(&self) -> u32 {
+        self.date().2 as u32
+    }
+
+    /// The hour, between 0 and 23.
+    pub fn hour(&self) -> u32 {
+        (self.seconds_of_day() / 3600) as u32
+    }
+
+    /// The minute, between 0 and 59.
+    pub fn minute(&self) -> u32 {
+        (self.seconds_of_day() / 60 % 60) as u32
+    }
+
+    /// The second, between 0 and 59.
+    pub fn second(&self) -> u32 {
+        (self.seconds_of_day() % 60) as u32
+    }
+
+    /// The fraction of the second in nanoseconds.
+    pub fn nanosecond(&self) -> u32 {
+        self.nanos.rem_euclid(NANOS_PER_SEC) as u32
+    }
+
+    /// The day of the week, from 1 (Monday) to 7 (Sunday) as in ISO 8601.
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        ((self.unix_time().div_euclid(SECS_PER_DAY) + 3).rem_euclid(7) + 1) as u32
+    }
+
+    /// The day of the year, between 1 and 366.
+    pub fn day_of_year(&self) -> u32 {
+        let days = self.unix_time().div_euclid(SECS_PER_DAY);
+        (days - days_from_civil(self.year(), 1, 1) + 1) as u32
+    }
+
+    /// Add a duration, returning `None` if the result is out of range.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        Self::from_nanos(self.nanos + duration.nanos)
+    }
+
+    /// Subtract a duration, returning `None` if the result is out of range.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        Self::from_nanos(self.nanos - duration.nanos)
+    }
+
+    /// The duration from an earlier date/time to this one (negative if `earlier` is actually later).
+    pub fn duration_since(&self, earlier: DateTime) -> Duration {
+        Duration {
+            nanos: self.nanos - earlier.nanos,
+        }
+    }
+
+    /// Parse a date/time in ISO 8601 format, e.g. `2026-10-17`, `2026-10-17T12:00:00Z` or
+    /// `2026-10-17T14:00:00.5+02:00`.
+    ///
+    /// The time and the offset from UTC are optional and default to midnight and UTC.
+    pub fn parse_iso8601(s: &str) -> Result<Self, String> {
+        let mut input = Cursor::new(s.trim());
+
+        let year = input.number(4, 4)?;
+        input.expect('-')?;
+        let month = input.number(2, 2)?;
+        input.expect('-')?;
+        let day = input.number(2, 2)?;
+
+        let mut time = (0, 0, 0);
+        let mut nanosecond = 0;
+        let mut offset = 0;
+
+        if input.eat('T') || input.eat('t') || input.eat(' ') {
+            time.0 = input.number(2, 2)?;
+            input.expect(':')?;
+            time.1 = input.number(2, 2)?;
+
+            if input.eat(':') {
+                time.2 = input.number(2, 2)?;
+
+                if input.eat('.') || input.eat(',') {
+                    nanosecond = input.fraction(1, 9)?;
+                }
+            }
+
+            if !input.is_end() {
+                offset = input.offset()?;
+            }
+        }
+
+        input.end()?;
+
+        Self::from_parts((year, month, day), time, nanosecond, offset)
+    }
+
+    /// Parse a date/time according to a `strftime`-style format string.
+    ///
+    /// See [`format`](#method.format) for the supported specifiers.  A year (or a Unix timestamp
+    /// via `%s`) is required; the other components default to the start of the year.
+    pub fn parse(s: &str, fmt: &str) -> Result<Self, String> {
+        let mut parsed = Parsed::default();
+        let mut input = Cursor::new(s);
+
+        parsed.parse(&mut input, fmt)?;
+        input.end()?;
+        parsed.resolve()
+    }
+
+    /// Format the date/time according to a `strftime`-style format string.
+    ///
+    /// | Specifier     | Description                                     | Example      |
+    /// | ------------- | ----------------------------------------------- | ------------ |
+    /// | `%Y`          | year (4 digits)                                 | `2026`       |
+    /// | `%y`          | year within the century (2 digits)              | `26`         |
+    /// | `%m`          | month (2 digits)                                | `10`         |
+    /// | `%b`, `%h`    | abbreviated month name                          | `Oct`        |
+    /// | `%B`          | full month name                                 | `October`    |
+    /// | `%d`          | day of the month (2 digits)                     | `07`         |
+    /// | `%e`          | day of the month (space-padded)                 | ` 7`         |
+    /// | `%j`          | day of the year (3 digits)                      | `280`        |
+    /// | `%a`          | abbreviated weekday name                        | `Wed`        |
+    /// | `%A`          | full weekday name                               | `Wednesday`  |
+    /// | `%u`          | day of the week, from 1 (Monday) to 7 (Sunday)  | `3`          |
+    /// | `%w`          | day of the week, from 0 (Sunday) to 6 (Saturday)| `3`          |
+    /// | `%H`          | hour (2 digits)                                 | `15`         |
+    /// | `%I`          | hour on a 12-hour clock (2 digits)              | `03`         |
+    /// | `%p`          | `AM` or `PM`                                    | `PM`         |
+    /// | `%M`          | minute (2 digits)                               | `04`         |
+    /// | `%S`          | second (2 digits)                               | `05`         |
+    /// | `%f`          | nanoseconds (9 digits)                          | `120000000`  |
+    /// | `%3f`, `%6f`, `%9f` | milli-, micro- or nanoseconds             | `120`        |
+    /// | `%s`          | seconds since the Unix epoch                    | `1791385445` |
+    /// | `%z`, `%:z`   | offset from UTC, always zero                    | `+0000`      |
+    /// | `%Z`          | time zone name, always `UTC`                    | `UTC`        |
+    /// | `%F`          | same as `%Y-%m-%d`                              |              |
+    /// | `%T`          | same as `%H:%M:%S`                              |              |
+    /// | `%R`          | same as `%H:%M`                                 |              |
+    /// | `%D`          | same as `%m/%d/%y`                              |              |
+    /// | `%n`, `%t`, `%%` | new-line, tab, `%`                           |              |
+    pub fn format(&self, fmt: &str) -> Result<String, String> {
+        let mut result = String::new();
+        self.format_to(&mut result, fmt)?;
+        Ok(result)
+    }
+
+    fn format_to(&self, result: &mut String, fmt: &str) -> Result<(), String> {
+        let (year, month, day) = self.date();
+        let mut chars = fmt.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                result.push(ch);
+                continue;
+            }
+
+            let text = match read_spec(&mut chars)? {
+                (None, ch) if expand_spec(ch).is_some() => {
+                    self.format_to(result, expand_spec(ch).unwrap())?;
+                    continue;
+                }
+                (None, 'Y') => format!("{:04}", year),
+                (None, 'y') => format!("{:02}", year % 100),
+                (None, 'm') => format!("{:02}", month),
+                (None, 'b') | (None, 'h') => MONTH_NAMES[month as usize - 1][..3].to_string(),
+                (None, 'B') => MONTH_NAMES[month as usize - 1].to_string(),
+                (None, 'd') => format!("{:02}", day),
+                (None, 'e') => format!("{:>2}", day),
+                (None, 'j') => format!("{:03}", self.day_of_year()),
+                (None, 'a') => WEEKDAY_NAMES[self.weekday() as usize - 1][..3].to_string(),
+                (None, 'A') => WEEKDAY_NAMES[self.weekday() as usize - 1].to_string(),
+                (None, 'u') => self.weekday().to_string(),
+                (None, 'w') => (self.weekday() % 7).to_string(),
+                (None, 'H') => format!("{:02}", self.hour()),
+                (None, 'I') => format!("{:02}", (self.hour() + 11) % 12 + 1),
+                (None, 'p') if self.hour() < 12 => "AM".to_string(),
+                (None, 'p') => "PM".to_string(),
+                (None, 'M') => format!("{:02}", self.minute()),
+                (None, 'S') => format!("{:02}", self.second()),
+                (None, 'f') | (Some('9'), 'f') => format!("{:09}", self.nanosecond()),
+                (Some('3'), 'f') => format!("{:03}", self.nanosecond() / 1_000_000),
+                (Some('6'), 'f') => format!("{:06}", self.nanosecond() / 1_000),
+                (None, 's') => self.unix_time().to_string(),
+                (None, 'z') => "+0000".to_string(),
+                (Some(':'), 'z') => "+00:00".to_string(),
+                (None, 'Z') => "UTC".to_string(),
+                (None, 'n') => "\n".to_string(),
+                (None, 't') => "\t".to_string(),
+                (None, '%') => "%".to_string(),
+                spec => return Err(unknown_spec(spec)),
+            };
+
+            result.push_str(&text);
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for DateTime {
+    /// Format the date/time in ISO 8601 format, e.g. `2026-10-17T12:00:00Z`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.date();
+
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}Z",
+            year,
+            month,
+            day,
+            self.hour(),
+            self.minute(),
+            self.second(),
+            format_fraction(self.nanosecond())
+        )
+    }
+}
+
+impl fmt::Debug for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A signed span of time with nanosecond precision, up to `i64::MAX` seconds in either direction.
+///
+/// # Example
+///
+/// ```
+/// use rhai::Duration;
+///
+/// let duration = Duration::parse_iso8601("P1DT2H30M").unwrap();
+/// assert_eq!(duration.as_seconds(), 95_400);
+/// assert_eq!((-duration).to_string(), "-P1DT2H30M");
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Duration {
+    /// Length of the duration in nanoseconds.
+    nanos: i128,
+}
+
+impl Duration {
+    /// The zero duration.
+    pub const ZERO: Self = Self { nanos: 0 };
+
+    /// Create a duration from a number of seconds.
+    pub fn from_seconds(seconds: i64) -> Self {
+        Self {
+            nanos: seconds as i128 * NANOS_PER_SEC,
+        }
+    }
+
+    /// Create a duration from a number of nanoseconds, returning `None` if it is out of range.
+    pub fn from_nanoseconds(nanoseconds: i128) -> Option<Self> {
+        if !(-MAX_DURATION..=MAX_DURATION).contains(&nanoseconds) {
+            None
+        } else {
+            Some(Self { nanos: nanoseconds })
+        }
+    }
+
+    /// Create a duration from a fractional number of seconds, rounded to the nearest nanosecond.
+    ///
+    /// Returns `None` if the number is not finite or is out of range.
+    #[cfg(not(feature = "no_float"))]
+    pub fn from_seconds_float(seconds: FLOAT) -> Option<Self> {
+        let nanos = seconds * NANOS_PER_SEC as FLOAT;
+
+        if !nanos.is_finite() || !(-MAX_DURATION as FLOAT..=MAX_DURATION as FLOAT).contains(&nanos)
+        {
+            return None;
+        }
+
+        let nanos = if nanos < 0.0 {
+            nanos - 0.5
+        } else {
+            nanos + 0.5
+        };
+        Self::from_nanoseconds(nanos as i128)
+    }
+
+    /// Length of the duration in whole seconds, truncated towards zero.
+    pub fn as_seconds(&self) -> i64 {
+        (self.nanos / NANOS_PER_SEC) as i64
+    }
+
+    /// Length of the duration in fractional seconds.
+    #[cfg(not(feature = "no_float"))]
+    pub fn as_seconds_float(&self) -> FLOAT {
+        self.nanos as FLOAT / NANOS_PER_SEC as FLOAT
+    }
+
+    /// Length of the duration in nanoseconds.
+    pub fn as_nanoseconds(&self) -> i128 {
+        self.nanos
+    }
+
+    /// The absolute value of the duration.
+    pub fn abs(&self) -> Self {
+        Self {
+            nanos: self.nanos.abs(),
+        }
+    }
+
+    /// Add two durations, returning `None` if the result is out of range.
+    pub fn checked_add(&self, other: Self) -> Option<Self> {
+        Self::from_nanoseconds(self.nanos + other.nanos)
+    }
+
+    /// Subtract a duration, returning `None` if the result is out of range.
+    pub fn checked_sub(&self, other: Self) -> Option<Self> {
+        Self::from_nanoseconds(self.nanos - other.nanos)
+    }
+
+    /// Multiply the duration by a number, returning `None` if the result is out of range.
+    pub fn checked_mul(&self, factor: i128) -> Option<Self> {
+        Self::from_nanoseconds(self.nanos.checked_mul(factor)?)
+    }
+
+    /// Divide the duration by a number, returning `None` if the number is zero.
+    pub fn checked_div(&self, divisor: i128) -> Option<Self> {
+        Self::from_nanoseconds(self.nanos.checked_div(divisor)?)
+    }
+
+    /// Parse a duration in ISO 8601 format, e.g. `PT1H30M`, `P2W`, `P1DT0.5S` or `-PT10S`.
+    ///
+    /// Years and months are not supported because their lengths vary.
+    pub fn parse_iso8601(s: &str) -> Result<Self, String> {
+        let mut input = Cursor::new(s.trim());
+
+        let negative = if input.eat('-') {
+            true
+        } else {
+            input.eat('+');
+            false
+        };
+
+        input.expect('P')?;
+
+        let mut nanos: i128 = 0;
+        let mut in_time = false;
+        let mut empty = true;
+
+        while !input.is_end() {
+            if !in_time && input.eat('T') {
+                in_time = true;
+                empty = true;
+                continue;
+            }
+
+            let value = input.number(1, 18)?;
+
+            let fraction = if in_time && (input.eat('.') || input.eat(',')) {
+                Some(input.fraction(1, 9)?)
+            } else {
+                None
+            };
+
+            let seconds = match (in_time, fraction, input.peek()) {
+                (false, None, Some('W')) => 7 * SECS_PER_DAY,
+                (false, None, Some('D')) => SECS_PER_DAY,
+                (true, None, Some('H')) => 3600,
+                (true, None, Some('M')) => 60,
+                (true, _, Some('S')) => 1,
+                _ => return Err(input.error()),
+            };
+
+            input.next();
+            nanos = nanos
+                .checked_add(
+                    value * seconds as i128 * NANOS_PER_SEC + fraction.unwrap_or(0) as i128,
+                )
+                .ok_or_else(|| "duration out of range".to_string())?;
+            empty = false;
+        }
+
+        if empty {
+            return Err(input.error());
+        }
+
+        Self::from_nanoseconds(if negative { -nanos } else { nanos })
+            .ok_or_else(|| "duration out of range".to_string())
+    }
+}
+
+impl Neg for Duration {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { nanos: -self.nanos }
+    }
+}
+
+impl fmt::Display for Duration {
+    /// Format the duration in ISO 8601 format, e.g. `PT1H30M` or `-P1DT0.5S`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.nanos < 0 {
+            write!(f, "-")?;
+        }
+
+        let nanos = self.nanos.abs();
+        let seconds = (nanos / NANOS_PER_SEC) as i64;
+        let fraction = (nanos % NANOS_PER_SEC) as u32;
+
+        let days = seconds / SECS_PER_DAY;
+        let hours = seconds % SECS_PER_DAY / 3600;
+        let minutes = seconds % 3600 / 60;
+        let seconds = seconds % 60;
+
+        write!(f, "P")?;
+
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if days == 0 || hours > 0 || minutes > 0 || seconds > 0 || fraction > 0 {
+            write!(f, "T")?;
+        }
+        if hours > 0 {
+            write!(f, "{}H", hours)?;
+        }
+        if minutes > 0 {
+            write!(f, "{}M", minutes)?;
+        }
+        if seconds > 0 || fraction > 0 || (days == 0 && hours == 0 && minutes == 0) {
+            write!(f, "{}{}S", seconds, format_fraction(fraction))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Read the specifier following a `%` in a format string, e.g. `Y`, `:z` or `3f`.
+fn read_spec(chars: &mut Peekable<Chars>) -> Result<(Option<char>, char), String> {
+    let flag = match chars.peek() {
+        Some(&ch) if ch == ':' || ch == '3' || ch == '6' || ch == '9' => {
+            chars.next();
+            Some(ch)
+        }
+        _ => None,
+    };
+
+    match chars.next() {
+        Some(ch) => Ok((flag, ch)),
+        None => Err("incomplete specifier at the end of the format string".into()),
+    }
+}
+
+/// Expand a specifier which is a shorthand for a longer format.
+fn expand_spec(ch: char) -> Option<&'static str> {
+    match ch {
+        'F' => Some("%Y-%m-%d"),
+        'T' => Some("%H:%M:%S"),
+        'R' => Some("%H:%M"),
+        'D' => Some("%m/%d/%y"),
+        _ => None,
+    }
+}
+
+fn unknown_spec((flag, ch): (Option<char>, char)) -> String {
+    match flag {
+        Some(flag) => format!("unknown specifier '%{}{}'", flag, ch),
+        None => format!("unknown specifier '%{}'", ch),
+    }
+}
+
+/// A simple cursor over the characters of a string being parsed.
+struct Cursor<'a> {
+    /// The rest of the input.
+    rest: &'a str,
+    /// Number of characters read so far.
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            rest: input,
+            pos: 0,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.rest.chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.rest = &self.rest[ch.len_utf8()..];
+        self.pos += 1;
+        Some(ch)
+    }
+
+    fn is_end(&mut self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Error for an unexpected character (or the end of the input) at the current position.
+    fn error(&mut self) -> String {
+        match self.peek() {
+            Some(ch) => format!("unexpected character '{}' at position {}", ch, self.pos),
+            None => "unexpected end of input".into(),
+        }
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        if self.is_end() {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn eat(&mut self, ch: char) -> bool {
+        if self.peek() == Some(ch) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), String> {
+        if self.eat(ch) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(ch) if ch.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    /// Read a string of between `min` and `max` decimal digits.
+    fn digits(&mut self, min: usize, max: usize) -> Result<String, String> {
+        let mut digits = String::new();
+
+        while digits.len() < max {
+            match self.peek() {
+                Some(ch) if ch.is_ascii_digit() => {
+                    digits.push(ch);
+                    self.next();
+                }
+                _ => break,
+            }
+        }
+
+        if digits.len() < min {
+            Err(self.error())
+        } else {
+            Ok(digits)
+        }
+    }
+
+    /// Read a number with between `min` and `max` decimal digits.
+    fn number(&mut self, min: usize, max: usize) -> Result<i128, String> {
+        self.digits(min, max)?
+            .parse()
+            .map_err(|_| "number too large".to_string())
+    }
+
+    /// Read the fractional part of a second, with between `min` and `max` digits, as nanoseconds.
+    fn fraction(&mut self, min: usize, max: usize) -> Result<u32, String> {
+        let digits = self.digits(min, max)?;
+        Ok(format!("{:0<9}", digits).parse().unwrap())
+    }
+
+    /// Read one of a list of names, ignoring case, optionally abbreviated to three letters.
+    fn name(&mut self, names: &[&str], abbreviated: bool) -> Result<usize, String> {
+        let rest = self.rest;
+        let starts_with = |prefix: &str| matches!(rest.get(..prefix.len()), Some(s) if s.eq_ignore_ascii_case(prefix));
+
+        let found = names.iter().enumerate().find_map(|(index, name)| {
+            if starts_with(name) {
+                Some((index, name.len()))
+            } else if abbreviated && starts_with(&name[..3]) {
+                Some((index, 3))
+            } else {
+                None
+            }
+        });
+
+        match found {
+            Some((index, len)) => {
+                (0..len).for_each(|_| {
+                    self.next();
+                });
+                Ok(index)
+            }
+            None => Err(self.error()),
+        }
+    }
+
+    /// Read an offset from UTC - `Z`, `+hh`, `+hhmm` or `+hh:mm` - in seconds.
+    fn offset(&mut self) -> Result<i128, String> {
+        if self.eat('Z') || self.eat('z') {
+            return Ok(0);
+        }
+
+        let sign = if self.eat('+') {
+            1
+        } else if self.eat('-') {
+            -1
+        } else {
+            return Err(self.error());
+        };
+
+        let hours = self.number(2, 2)?;
+        let minutes = if self.eat(':') {
+            self.number(2, 2)?
+        } else {
+            self.number(0, 2).unwrap_or(0)
+        };
+
+        if hours > 23 || minutes > 59 {
+            return Err("invalid offset from UTC".into());
+        }
+
+        Ok(sign * (hours * 3600 + minutes * 60))
+    }
+}
+
+/// Components of a date/time collected while parsing with a format string.
+#[derive(Default)]
+struct Parsed {
+    year: Option<i128>,
+    month: Option<i128>,
+    day: Option<i128>,
+    day_of_year: Option<i128>,
+    hour: Option<i128>,
+    hour12: Option<i128>,
+    pm: bool,
+    minute: i128,
+    second: i128,
+    nanosecond: u32,
+    unix_time: Option<i128>,
+    offset: i128,
+}
+
+impl Parsed {
+    fn parse(&mut self, input: &mut Cursor, fmt: &str) -> Result<(), String> {
+        let mut chars = fmt.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            if ch.is_whitespace() {
+                input.skip_whitespace();
+                continue;
+            }
+            if ch != '%' {
+                input.expect(ch)?;
+                continue;
+            }
+
+            match read_spec(&mut chars)? {
+                (None, ch) if expand_spec(ch).is_some() => {
+                    self.parse(input, expand_spec(ch).unwrap())?
+                }
+                (None, 'Y') => self.year = Some(input.number(1, 4)?),
+                (None, 'y') => {
+                    let year = input.number(2, 2)?;
+                    self.year = Some(if year < 69 { 2000 + year } else { 1900 + year });
+                }
+                (None, 'm') => self.month = Some(input.number(1, 2)?),
+                (None, 'b') | (None, 'h') | (None, 'B') => {
+                    self.month = Some(input.name(&MONTH_NAMES, true)? as i128 + 1)
+                }
+                (None, 'd') => self.day = Some(input.number(1, 2)?),
+                (None, 'e') => {
+                    input.skip_whitespace();
+                    self.day = Some(input.number(1, 2)?);
+                }
+                (None, 'j') => self.day_of_year = Some(input.number(1, 3)?),
+                (None, 'a') | (None, 'A') => {
+                    input.name(&WEEKDAY_NAMES, true)?;
+                }
+                (None, 'u') | (None, 'w') => {
+                    input.number(1, 1)?;
+                }
+                (None, 'H') => self.hour = Some(input.number(1, 2)?),
+                (None, 'I') => self.hour12 = Some(input.number(1, 2)?),
+                (None, 'p') => self.pm = input.name(&["AM", "PM"], false)? == 1,
+                (None, 'M') => self.minute = input.number(1, 2)?,
+                (None, 'S') => self.second = input.number(1, 2)?,
+                (None, 'f') => self.nanosecond = input.fraction(1, 9)?,
+                (Some(digits @ '3'), 'f')
+                | (Some(digits @ '6'), 'f')
+                | (Some(digits @ '9'), 'f') => {
+                    let digits = digits.to_digit(10).unwrap() as usize;
+                    self.nanosecond = input.fraction(digits, digits)?;
+                }
+                (None, 's') => {
+                    let sign = if input.eat('-') { -1 } else { 1 };
+                    self.unix_time = Some(sign * input.number(1, 18)?);
+                }
+                (None, 'z') | (Some(':'), 'z') => self.offset = input.offset()?,
+                (None, 'Z') => {
+                    input.name(&["UTC", "GMT", "Z"], false)?;
+                }
+                (None, 'n') | (None, 't') => input.skip_whitespace(),
+                (None, '%') => input.expect('%')?,
+                spec => return Err(unknown_spec(spec)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&self) -> Result<DateTime, String> {
+        if let Some(seconds) = self.unix_time {
+            return DateTime::from_nanos(seconds * NANOS_PER_SEC + self.nanosecond as i128)
+                .ok_or_else(|| "date/time out of range".to_string());
+        }
+
+        let year = self.year.ok_or_else(|| "missing year".to_string())?;
+
+        let (month, day) = match self.day_of_year {
+            Some(day_of_year) => {
+                let days_in_year = if is_leap_year(year as i64) { 366 } else { 365 };
+
+                if !(1..=days_in_year).contains(&day_of_year) {
+                    return Err(format!("invalid day of the year {}", day_of_year));
+                }
+
+                let days = days_from_civil(year as i64, 1, 1) + day_of_year as i64 - 1;
+                let (_, month, day) = civil_from_days(days);
+                (month as i128, day as i128)
+            }
+            None => (self.month.unwrap_or(1), self.day.unwrap_or(1)),
+        };
+
+        let hour = match self.hour12 {
+            Some(hour) if !(1..=12).contains(&hour) => {
+                return Err(format!("invalid hour {} on a 12-hour clock", hour))
+            }
+            Some(hour) => hour % 12 + if self.pm { 12 } else { 0 },
+            None => self.hour.unwrap_or(0),
+        };
+
+        DateTime::from_parts(
+            (year, month, day),
+            (hour, self.minute, self.second),
+            self.nanosecond,
+            self.offset,
+        )
+    }
+}
@@ -3,21 +3,25 @@
 use crate::any::{Dynamic, Union};
 use crate::calc_fn_hash;
 use crate::error::ParseErrorType;
+use crate::fn_native::{FnPtr, NativeCallContext};
 use crate::optimize::OptimizationLevel;
 use crate::packages::{CorePackage, Package, PackageLibrary, StandardPackage};
-use crate::parser::{Expr, FnDef, ReturnType, Stmt};
+use crate::parser::{Expr, FnDef, Pattern, ReturnType, Stmt};
 use crate::result::EvalAltResult;
 use crate::scope::{EntryType as ScopeEntryType, Scope};
+use crate::syntax::{CustomSyntax, EvalContext, Expression};
 use crate::token::Position;
 
 use crate::stdlib::{
     any::TypeId,
     boxed::Box,
-    collections::HashMap,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
     format,
     hash::{Hash, Hasher},
     iter::once,
     mem,
+    num::NonZeroUsize,
     ops::{Deref, DerefMut},
     rc::Rc,
     string::{String, ToString},
@@ -36,24 +40,28 @@ use ahash::AHasher;
 /// Not available under the `no_index` feature.
 pub type Array = Vec<Dynamic>;
 
-/// An dynamic hash map of `Dynamic` values with `String` keys.
-///
-/// Not available under the `no_object` feature.
-pub type Map = HashMap<String, Dynamic>;
+pub use crate::map::Map;
 
 pub type FnCallArgs<'a> = [&'a mut Dynamic];
 
 #[cfg(feature = "sync")]
-pub type FnAny =
-    dyn Fn(&mut FnCallArgs, Position) -> Result<Dynamic, Box<EvalAltResult>> + Send + Sync;
+pub type FnAny = dyn Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>
+    + Send
+    + Sync;
 #[cfg(not(feature = "sync"))]
-pub type FnAny = dyn Fn(&mut FnCallArgs, Position) -> Result<Dynamic, Box<EvalAltResult>>;
+pub type FnAny = dyn Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>;
 
 #[cfg(feature = "sync")]
 pub type IteratorFn = dyn Fn(Dynamic) -> Box<dyn Iterator<Item = Dynamic>> + Send + Sync;
 #[cfg(not(feature = "sync"))]
 pub type IteratorFn = dyn Fn(Dynamic) -> Box<dyn Iterator<Item = Dynamic>>;
 
+#[cfg(feature = "sync")]
+pub type OnVarFn =
+    dyn Fn(&str, usize, &EvalContext) -> Result<Option<Dynamic>, Box<EvalAltResult>> + Send + Sync;
+#[cfg(not(feature = "sync"))]
+pub type OnVarFn = dyn Fn(&str, usize, &EvalContext) -> Result<Option<Dynamic>, Box<EvalAltResult>>;
+
 #[cfg(debug_assertions)]
 pub const MAX_CALL_STACK_DEPTH: usize = 28;
 
@@ -71,9 +79,16 @@ pub const KEYWORD_PRINT: &str = "print";
 pub const KEYWORD_DEBUG: &str = "debug";
 pub const KEYWORD_TYPE_OF: &str = "type_of";
 pub const KEYWORD_EVAL: &str = "eval";
+pub const KEYWORD_THIS: &str = "this";
+pub const KEYWORD_FN_PTR: &str = "Fn";
+pub const KEYWORD_FN_PTR_CALL: &str = "call";
+pub const KEYWORD_FN_PTR_CURRY: &str = "curry";
+pub const FN_ANONYMOUS: &str = "anon$";
 pub const FUNC_TO_STRING: &str = "to_string";
 pub const FUNC_GETTER: &str = "get$";
 pub const FUNC_SETTER: &str = "set$";
+pub const FUNC_INDEXER_GET: &str = "index$get$";
+pub const FUNC_INDEXER_SET: &str = "index$set$";
 
 /// A type that encapsulates a mutation target for an expression with side effects.
 enum Target<'a> {
@@ -208,14 +223,15 @@ impl State {
 
 /// A type that holds a library (`HashMap`) of script-defined functions.
 ///
-/// Since script-defined functions have `Dynamic` parameters, functions with the same name
-/// and number of parameters are considered equivalent.
+/// Functions with the same name are kept together as a list of overloads, distinguished by the
+/// number of parameters they accept and the type annotations on their parameters.
+/// Functions without any type annotations have `Dynamic` parameters and match arguments of all types.
 ///
 /// The key of the `HashMap` is a `u64` hash calculated by the function `calc_fn_def`.
 #[derive(Debug, Clone)]
 pub struct FunctionsLib(
-    #[cfg(feature = "sync")] HashMap<u64, Arc<FnDef>>,
-    #[cfg(not(feature = "sync"))] HashMap<u64, Rc<FnDef>>,
+    #[cfg(feature = "sync")] HashMap<u64, Vec<Arc<FnDef>>>,
+    #[cfg(not(feature = "sync"))] HashMap<u64, Vec<Rc<FnDef>>>,
 );
 
 impl FunctionsLib {
@@ -225,30 +241,72 @@ impl FunctionsLib {
     }
     /// Create a new `FunctionsLib` from a collection of `FnDef`.
     pub fn from_vec(vec: Vec<FnDef>) -> Self {
-        FunctionsLib(
-            vec.into_iter()
-                .map(|f| {
-                    let hash = calc_fn_def(&f.name, f.params.len());
+        let mut lib = Self::new();
 
-                    #[cfg(feature = "sync")]
-                    {
-                        (hash, Arc::new(f))
-                    }
-                    #[cfg(not(feature = "sync"))]
-                    {
-                        (hash, Rc::new(f))
-                    }
-                })
-                .collect(),
-        )
+        vec.into_iter().for_each(|f| {
+            #[cfg(feature = "sync")]
+            lib.add_function(Arc::new(f));
+            #[cfg(not(feature = "sync"))]
+            lib.add_function(Rc::new(f));
+        });
+
+        lib
     }
-    /// Does a certain function exist in the `FunctionsLib`?
-    pub fn has_function(&self, name: &str, params: usize) -> bool {
-        self.contains_key(&calc_fn_def(name, params))
+    /// Add a function definition to the `FunctionsLib`, replacing any existing overload
+    /// with the same signature.
+    #[cfg(feature = "sync")]
+    fn add_function(&mut self, f: Arc<FnDef>) {
+        let overloads = self.entry(calc_fn_def(&f.name)).or_default();
+
+        match overloads.iter_mut().find(|x| x.same_signature(&f)) {
+            Some(x) => *x = f,
+            None => overloads.push(f),
+        }
+    }
+    /// Add a function definition to the `FunctionsLib`, replacing any existing overload
+    /// with the same signature.
+    #[cfg(not(feature = "sync"))]
+    fn add_function(&mut self, f: Rc<FnDef>) {
+        let overloads = self.entry(calc_fn_def(&f.name)).or_default();
+
+        match overloads.iter_mut().find(|x| x.same_signature(&f)) {
+            Some(x) => *x = f,
+            None => overloads.push(f),
+        }
+    }
+    /// Does a certain function, callable with a certain number of arguments, exist in the `FunctionsLib`?
+    pub fn has_function(&self, name: &str, num_args: usize) -> bool {
+        self.get(&calc_fn_def(name))
+            .into_iter()
+            .flatten()
+            .any(|f| f.accepts_args(num_args))
     }
     /// Get a function definition from the `FunctionsLib`.
-    pub fn get_function(&self, name: &str, params: usize) -> Option<&FnDef> {
-        self.get(&calc_fn_def(name, params)).map(|f| f.as_ref())
+    ///
+    /// `type_of` returns the type name of the argument at a certain position.
+    /// Among all overloads that accept the number of arguments and whose parameter type annotations
+    /// match the arguments, the one with the most typed parameters wins. Then the one needing the
+    /// fewest default values wins, then one without a rest parameter. On a tie, the function
+    /// defined last wins.
+    pub fn get_function<'a>(
+        &self,
+        name: &str,
+        num_args: usize,
+        type_of: impl Fn(usize) -> &'a str,
+    ) -> Option<&FnDef> {
+        self.get(&calc_fn_def(name)).and_then(|overloads| {
+            overloads
+                .iter()
+                .filter(|f| f.accepts_args(num_args))
+                .filter_map(|f| {
+                    f.match_types(num_args, &type_of).map(|score| {
+                        let defaults = f.fixed_params().saturating_sub(num_args);
+                        ((score, Reverse(defaults), !f.rest_param), f)
+                    })
+                })
+                .max_by_key(|(key, _)| *key)
+                .map(|(_, f)| f.as_ref())
+        })
     }
     /// Merge another `FunctionsLib` into this `FunctionsLib`.
     pub fn merge(&self, other: &Self) -> Self {
@@ -258,7 +316,10 @@ impl FunctionsLib {
             self.clone()
         } else {
             let mut functions = self.clone();
-            functions.extend(other.iter().map(|(hash, fn_def)| (*hash, fn_def.clone())));
+            other
+                .values()
+                .flatten()
+                .for_each(|f| functions.add_function(f.clone()));
             functions
         }
     }
@@ -266,9 +327,9 @@ impl FunctionsLib {
 
 impl Deref for FunctionsLib {
     #[cfg(feature = "sync")]
-    type Target = HashMap<u64, Arc<FnDef>>;
+    type Target = HashMap<u64, Vec<Arc<FnDef>>>;
     #[cfg(not(feature = "sync"))]
-    type Target = HashMap<u64, Rc<FnDef>>;
+    type Target = HashMap<u64, Vec<Rc<FnDef>>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -277,11 +338,11 @@ impl Deref for FunctionsLib {
 
 impl DerefMut for FunctionsLib {
     #[cfg(feature = "sync")]
-    fn deref_mut(&mut self) -> &mut HashMap<u64, Arc<FnDef>> {
+    fn deref_mut(&mut self) -> &mut HashMap<u64, Vec<Arc<FnDef>>> {
         &mut self.0
     }
     #[cfg(not(feature = "sync"))]
-    fn deref_mut(&mut self) -> &mut HashMap<u64, Rc<FnDef>> {
+    fn deref_mut(&mut self) -> &mut HashMap<u64, Vec<Rc<FnDef>>> {
         &mut self.0
     }
 }
@@ -314,6 +375,15 @@ pub struct Engine {
     pub(crate) type_iterators: HashMap<TypeId, Box<IteratorFn>>,
     /// A hashmap mapping type names to pretty-print names.
     pub(crate) type_names: HashMap<String, String>,
+    /// A hashmap mapping custom operator symbols to their precedence.
+    pub(crate) custom_operators: HashMap<String, u8>,
+    /// A hashmap mapping custom syntax keywords to their definitions.
+    pub(crate) custom_syntax: HashMap<String, CustomSyntax>,
+    /// A set of keywords and operators that are disabled in scripts.
+    pub(crate) disabled_symbols: HashSet<String>,
+    /// Parameter types of the raw functions taking `Dynamic` parameters, which accept arguments of
+    /// any type, indexed by function name and number of parameters.
+    pub(crate) dynamic_fns: HashMap<(String, usize), Vec<Vec<TypeId>>>,
 
     /// Closure for implementing the `print` command.
     #[cfg(feature = "sync")]
@@ -329,6 +399,9 @@ pub struct Engine {
     #[cfg(not(feature = "sync"))]
     pub(crate) debug: Box<dyn Fn(&str) + 'static>,
 
+    /// Closure for resolving variables not found in the `Scope`.
+    pub(crate) var_resolver: Option<Box<OnVarFn>>,
+
     /// Optimize the AST after compilation.
     pub(crate) optimization_level: OptimizationLevel,
 
@@ -336,6 +409,11 @@ pub struct Engine {
     ///
     /// Defaults to 28 for debug builds and 256 for non-debug builds.
     pub(crate) max_call_stack_depth: usize,
+
+    /// Reject the use of undeclared variables when compiling scripts.
+    pub(crate) strict_variables: bool,
+    /// Names of variables provided outside of the `Scope`, e.g. by a variable resolver.
+    pub(crate) external_vars: HashSet<String>,
 }
 
 impl Default for Engine {
@@ -346,10 +424,15 @@ impl Default for Engine {
             functions: HashMap::with_capacity(FUNCTIONS_COUNT),
             type_iterators: HashMap::new(),
             type_names: HashMap::new(),
+            custom_operators: HashMap::new(),
+            custom_syntax: HashMap::new(),
+            disabled_symbols: HashSet::new(),
+            dynamic_fns: HashMap::new(),
 
             // default print/debug implementations
             print: Box::new(default_print),
             debug: Box::new(default_print),
+            var_resolver: None,
 
             // optimization level
             #[cfg(feature = "no_optimize")]
@@ -364,6 +447,8 @@ impl Default for Engine {
             optimization_level: OptimizationLevel::Full,
 
             max_call_stack_depth: MAX_CALL_STACK_DEPTH,
+            strict_variables: false,
+            external_vars: HashSet::new(),
         };
 
         #[cfg(feature = "no_stdlib")]
@@ -397,6 +482,11 @@ fn extract_prop_from_getter(fn_name: &str) -> Option<&str> {
     }
 }
 
+/// Can the value be indexed without a registered indexer?
+fn is_builtin_indexable(val: &Dynamic) -> bool {
+    matches!(val.0, Union::Array(_) | Union::Map(_) | Union::Str(_))
+}
+
 /// Make setter function
 pub fn make_setter(id: &str) -> String {
     format!("{}{}", FUNC_SETTER, id)
@@ -433,18 +523,24 @@ pub fn calc_fn_spec(fn_name: &str, params: impl Iterator<Item = TypeId>) -> u64
     s.finish()
 }
 
-/// Calculate a `u64` hash key from a function name and number of parameters (without regard to types).
-pub(crate) fn calc_fn_def(fn_name: &str, params: usize) -> u64 {
+/// Calculate a `u64` hash key from a function name (without regard to parameters).
+pub(crate) fn calc_fn_def(fn_name: &str) -> u64 {
     #[cfg(feature = "no_std")]
     let mut s: AHasher = Default::default();
     #[cfg(not(feature = "no_std"))]
     let mut s = DefaultHasher::new();
 
     s.write(fn_name.as_bytes());
-    s.write_usize(params);
     s.finish()
 }
 
+/// Is a `break` or `continue` statement with the `target` label meant for the loop with the given `label`?
+///
+/// Unlabeled `break` and `continue` statements always target the innermost loop.
+fn is_loop_target(target: &Option<String>, label: &Option<String>) -> bool {
+    target.is_none() || target == label
+}
+
 /// Print/debug to stdout
 fn default_print(s: &str) {
     #[cfg(not(feature = "no_std"))]
@@ -478,8 +574,13 @@ impl Engine {
             functions: HashMap::with_capacity(FUNCTIONS_COUNT / 2),
             type_iterators: HashMap::new(),
             type_names: HashMap::new(),
+            custom_operators: HashMap::new(),
+            custom_syntax: HashMap::new(),
+            disabled_symbols: HashSet::new(),
+            dynamic_fns: HashMap::new(),
             print: Box::new(|_| {}),
             debug: Box::new(|_| {}),
+            var_resolver: None,
 
             #[cfg(feature = "no_optimize")]
             optimization_level: OptimizationLevel::None,
@@ -493,6 +594,8 @@ impl Engine {
             optimization_level: OptimizationLevel::Full,
 
             max_call_stack_depth: MAX_CALL_STACK_DEPTH,
+            strict_variables: false,
+            external_vars: HashSet::new(),
         }
     }
 
@@ -519,6 +622,93 @@ impl Engine {
         self.max_call_stack_depth = levels
     }
 
+    /// Control whether the `Engine` rejects scripts that use undeclared variables.
+    ///
+    /// Under strict variables mode, using a variable that is neither declared in the script,
+    /// nor present in the `Scope` passed to `compile_with_scope`, nor declared via
+    /// `declare_external_var` fails with `ParseErrorType::VariableUndefined` at compile time,
+    /// instead of `ErrorVariableNotFound` at runtime.
+    ///
+    /// A variable resolver registered via `on_var` does not turn off the check: declare the names
+    /// of the variables it provides via `declare_external_var`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, ParseErrorType, Scope};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_strict_variables(true);
+    ///
+    /// let err = engine.compile("let x = 40; y + 2").expect_err("should error");
+    /// assert_eq!(*err.error_type(), ParseErrorType::VariableUndefined("y".into()));
+    ///
+    /// let mut scope = Scope::new();
+    /// scope.push("y", 40_i64);
+    ///
+    /// assert!(engine.compile_with_scope(&scope, "let x = 40; y + 2").is_ok());
+    /// ```
+    pub fn set_strict_variables(&mut self, enable: bool) {
+        self.strict_variables = enable
+    }
+
+    /// Declare a variable that is provided outside of the `Scope`, e.g. by a variable resolver
+    /// registered via `on_var`, so that scripts can use it under strict variables mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, ParseErrorType};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_strict_variables(true);
+    /// engine.on_var(|name, _, _| match name {
+    ///     "answer" => Ok(Some(42_i64.into())),
+    ///     _ => Ok(None),
+    /// });
+    ///
+    /// // Names provided by the resolver must be declared
+    /// let err = engine.compile("answer").expect_err("should error");
+    /// assert_eq!(*err.error_type(), ParseErrorType::VariableUndefined("answer".into()));
+    ///
+    /// engine.declare_external_var("answer");
+    ///
+    /// assert_eq!(engine.eval::<i64>("answer")?, 42);
+    ///
+    /// // Typos are still caught
+    /// let err = engine.compile("anwser").expect_err("should error");
+    /// assert_eq!(*err.error_type(), ParseErrorType::VariableUndefined("anwser".into()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn declare_external_var(&mut self, name: &str) {
+        self.external_vars.insert(name.into());
+    }
+
+    /// Disable a keyword or operator (e.g. `"while"`, `"eval"` or `"+="`), so that any script
+    /// using it fails to compile with `ParseErrorType::DisabledSymbol`.
+    ///
+    /// Unlike the compile-time features, this is configured per `Engine`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, ParseErrorType};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.disable_symbol("while");
+    ///
+    /// let err = engine.compile("while true {}").expect_err("should error");
+    /// assert_eq!(*err.error_type(), ParseErrorType::DisabledSymbol("while".into()));
+    /// ```
+    pub fn disable_symbol(&mut self, symbol: &str) {
+        self.disabled_symbols.insert(symbol.into());
+    }
+
     /// Universal method for calling functions either registered with the `Engine` or written in Rhai
     pub(crate) fn call_fn_raw(
         &self,
@@ -536,34 +726,35 @@ impl Engine {
         }
 
         // First search in script-defined functions (can override built-in)
-        if let Some(fn_def) = fn_lib.get_function(fn_name, args.len()) {
-            return self.call_fn_from_lib(scope, fn_lib, fn_def, args, pos, level);
+        if let Some(fn_def) = fn_lib.get_function(fn_name, args.len(), |i| {
+            self.map_type_name(args[i].type_name())
+        }) {
+            return self.call_fn_from_lib(scope, fn_lib, fn_def, args, None, pos, level);
         }
 
         // Search built-in's and external functions
-        let fn_spec = calc_fn_hash(fn_name, args.iter().map(|a| a.type_id()));
-
-        if let Some(func) = self.functions.get(&fn_spec).or_else(|| {
-            self.packages
-                .iter()
-                .find(|pkg| pkg.functions.contains_key(&fn_spec))
-                .and_then(|pkg| pkg.functions.get(&fn_spec))
-        }) {
+        if let Some(func) = self.get_native_fn(fn_name, args) {
             // Run external function
-            let result = func(args, pos)?;
+            let context = NativeCallContext {
+                engine: self,
+                fn_lib,
+                pos,
+                level,
+            };
+            let result = func(&context, args)?;
 
             // See if the function match print/debug (which requires special processing)
             return Ok(match fn_name {
                 KEYWORD_PRINT => (self.print)(result.as_str().map_err(|type_name| {
                     Box::new(EvalAltResult::ErrorMismatchOutputType(
-                        type_name.into(),
+                        self.map_type_name(type_name).into(),
                         pos,
                     ))
                 })?)
                 .into(),
                 KEYWORD_DEBUG => (self.debug)(result.as_str().map_err(|type_name| {
                     Box::new(EvalAltResult::ErrorMismatchOutputType(
-                        type_name.into(),
+                        self.map_type_name(type_name).into(),
                         pos,
                     ))
                 })?)
@@ -572,6 +763,14 @@ impl Engine {
             });
         }
 
+        if fn_name == FUNC_INDEXER_GET || fn_name == FUNC_INDEXER_SET {
+            // Indexer not found
+            return Err(Box::new(EvalAltResult::ErrorIndexingType(
+                self.map_type_name(args[0].type_name()).into(),
+                pos,
+            )));
+        }
+
         if let Some(prop) = extract_prop_from_getter(fn_name) {
             // Getter function not found
             return Err(Box::new(EvalAltResult::ErrorDotExpr(
@@ -605,13 +804,59 @@ impl Engine {
         )))
     }
 
+    /// Search for a native function, registered with the `Engine` or in a package, matching the arguments.
+    ///
+    /// If no function matches the argument types exactly, raw functions with `Dynamic` parameters
+    /// are searched, preferring those with the fewest `Dynamic` parameters.
+    pub(crate) fn get_native_fn(&self, fn_name: &str, args: &FnCallArgs) -> Option<&FnAny> {
+        let get_fn = |hash| {
+            self.functions
+                .get(&hash)
+                .or_else(|| {
+                    self.packages
+                        .iter()
+                        .find(|pkg| pkg.functions.contains_key(&hash))
+                        .and_then(|pkg| pkg.functions.get(&hash))
+                })
+                .map(|func| func.as_ref())
+        };
+
+        let hash = calc_fn_hash(fn_name, args.iter().map(|a| a.type_id()));
+
+        if let Some(func) = get_fn(hash) {
+            return Some(func);
+        }
+
+        let dynamic = TypeId::of::<Dynamic>();
+        let key = (fn_name.to_string(), args.len());
+
+        // Pick the matching overload with the fewest `Dynamic` parameters
+        once(&self.dynamic_fns)
+            .chain(self.packages.iter().map(|pkg| &pkg.dynamic_fns))
+            .filter_map(|fns| fns.get(&key))
+            .flatten()
+            .filter(|params| {
+                params
+                    .iter()
+                    .zip(args.iter())
+                    .all(|(&t, a)| t == dynamic || t == a.type_id())
+            })
+            .min_by_key(|params| params.iter().filter(|&&t| t == dynamic).count())
+            .and_then(|params| get_fn(calc_fn_hash(fn_name, params.iter().cloned())))
+    }
+
     /// Call a script-defined function.
+    ///
+    /// If `this_ptr` is provided, the function is called as a method and the variable `this`
+    /// is bound to the object.  The object is moved into the function's scope for the duration
+    /// of the call and moved back afterwards, so any changes made via `this` are kept.
     pub(crate) fn call_fn_from_lib(
         &self,
         scope: Option<&mut Scope>,
         fn_lib: &FunctionsLib,
         fn_def: &FnDef,
         args: &mut FnCallArgs,
+        mut this_ptr: Option<&mut Dynamic>,
         pos: Position,
         level: usize,
     ) -> Result<Dynamic, Box<EvalAltResult>> {
@@ -621,25 +866,29 @@ impl Engine {
                 let scope_len = scope.len();
                 let mut state = State::new();
 
-                scope.extend(
-                    // Put arguments into scope as variables - variable name is copied
-                    // TODO - avoid copying variable name
-                    fn_def
-                        .params
-                        .iter()
-                        .zip(args.into_iter().map(|v| v.clone()))
-                        .map(|(name, value)| (name.clone(), ScopeEntryType::Normal, value)),
-                );
+                // Bind `this` below the parameters so that variable offsets remain valid
+                if let Some(obj) = this_ptr.as_mut() {
+                    scope.push_dynamic(KEYWORD_THIS, mem::replace(*obj, ().into()));
+                }
 
-                // Evaluate the function at one higher level of call depth
+                // Put arguments into scope as variables, then
+                // evaluate the function at one higher level of call depth
                 let result = self
-                    .eval_stmt(scope, &mut state, fn_lib, &fn_def.body, level + 1)
+                    .bind_fn_params(scope, &mut state, fn_lib, fn_def, args, level + 1)
+                    .and_then(|_| {
+                        self.eval_stmt(scope, &mut state, fn_lib, &fn_def.body, level + 1)
+                    })
                     .or_else(|err| match *err {
                         // Convert return statement to return value
                         EvalAltResult::Return(x, _) => Ok(x),
                         _ => Err(EvalAltResult::set_position(err, pos)),
                     });
 
+                // Move the object back out of the scope
+                if let Some(obj) = this_ptr {
+                    *obj = mem::replace(scope.get_mut(scope_len).0, ().into());
+                }
+
                 scope.rewind(scope_len);
 
                 return result;
@@ -649,25 +898,69 @@ impl Engine {
                 let mut scope = Scope::new();
                 let mut state = State::new();
 
-                scope.extend(
-                    // Put arguments into scope as variables
-                    fn_def
-                        .params
-                        .iter()
-                        .zip(args.into_iter().map(|v| v.clone()))
-                        .map(|(name, value)| (name, ScopeEntryType::Normal, value)),
-                );
+                // Bind `this` below the parameters so that variable offsets remain valid
+                if let Some(obj) = this_ptr.as_mut() {
+                    scope.push_dynamic(KEYWORD_THIS, mem::replace(*obj, ().into()));
+                }
 
-                // Evaluate the function at one higher level of call depth
-                return self
-                    .eval_stmt(&mut scope, &mut state, fn_lib, &fn_def.body, level + 1)
+                // Put arguments into scope as variables, then
+                // evaluate the function at one higher level of call depth
+                let result = self
+                    .bind_fn_params(&mut scope, &mut state, fn_lib, fn_def, args, level + 1)
+                    .and_then(|_| {
+                        self.eval_stmt(&mut scope, &mut state, fn_lib, &fn_def.body, level + 1)
+                    })
                     .or_else(|err| match *err {
                         // Convert return statement to return value
                         EvalAltResult::Return(x, _) => Ok(x),
                         _ => Err(EvalAltResult::set_position(err, pos)),
                     });
+
+                // Move the object back out of the scope
+                if let Some(obj) = this_ptr {
+                    *obj = mem::replace(scope.get_mut(0).0, ().into());
+                }
+
+                return result;
+            }
+        }
+    }
+
+    /// Push the arguments of a script-defined function call into a `Scope` as its parameters.
+    ///
+    /// Missing arguments are filled in by evaluating the default values of their parameters,
+    /// and extra arguments are collected into an array for the rest parameter.
+    fn bind_fn_params(
+        &self,
+        scope: &mut Scope,
+        state: &mut State,
+        fn_lib: &FunctionsLib,
+        fn_def: &FnDef,
+        args: &mut FnCallArgs,
+        level: usize,
+    ) -> Result<(), Box<EvalAltResult>> {
+        let num_fixed = fn_def.fixed_params();
+
+        for (i, name) in fn_def.params.iter().take(num_fixed).enumerate() {
+            let value = match (args.get(i), &fn_def.param_defaults[i]) {
+                (Some(arg), _) => (*arg).clone(),
+                (None, Some(expr)) => self.eval_expr(scope, state, fn_lib, expr, level)?,
+                (None, None) => unreachable!(),
+            };
+
+            // TODO - avoid copying variable name
+            scope.push_dynamic(name.clone(), value);
+        }
+
+        #[cfg(not(feature = "no_index"))]
+        {
+            if fn_def.rest_param {
+                let rest: Array = args.iter().skip(num_fixed).map(|v| (*v).clone()).collect();
+                scope.push(fn_def.params[num_fixed].clone(), rest);
             }
         }
+
+        Ok(())
     }
 
     // Has a system function an override?
@@ -706,6 +999,47 @@ impl Engine {
                 )))
             }
 
+            // Fn - create a function pointer
+            KEYWORD_FN_PTR if args.len() == 1 && !self.has_override(fn_lib, KEYWORD_FN_PTR) => {
+                let fn_name = args[0].as_str().map_err(|type_name| {
+                    EvalAltResult::ErrorMismatchDataType(
+                        "string".into(),
+                        self.map_type_name(type_name).into(),
+                        pos,
+                    )
+                })?;
+
+                Ok(Dynamic::from(FnPtr::new(fn_name)))
+            }
+
+            // call - call a function pointer with the curried arguments followed by the other arguments
+            KEYWORD_FN_PTR_CALL if !args.is_empty() && args[0].is::<FnPtr>() => {
+                let fn_ptr = args[0].downcast_ref::<FnPtr>().unwrap().clone();
+                let mut arg_values: Vec<_> = fn_ptr.curry().to_vec();
+                let mut args: Vec<_> = arg_values
+                    .iter_mut()
+                    .chain(args.iter_mut().skip(1).map(|v| &mut **v))
+                    .collect();
+
+                self.call_fn_raw(
+                    None,
+                    fn_lib,
+                    fn_ptr.fn_name(),
+                    &mut args,
+                    def_val,
+                    pos,
+                    level,
+                )
+            }
+
+            // curry - bind arguments to a function pointer
+            KEYWORD_FN_PTR_CURRY if !args.is_empty() && args[0].is::<FnPtr>() => {
+                let mut fn_ptr = args[0].downcast_ref::<FnPtr>().unwrap().clone();
+                fn_ptr.add_curry(args.iter().skip(1).map(|v| (*v).clone()));
+
+                Ok(Dynamic::from(fn_ptr))
+            }
+
             _ => self.call_fn_raw(None, fn_lib, fn_name, args, def_val, pos, level),
         }
     }
@@ -718,9 +1052,9 @@ impl Engine {
         script: &Dynamic,
         pos: Position,
     ) -> Result<Dynamic, Box<EvalAltResult>> {
-        let script = script
-            .as_str()
-            .map_err(|type_name| EvalAltResult::ErrorMismatchOutputType(type_name.into(), pos))?;
+        let script = script.as_str().map_err(|type_name| {
+            EvalAltResult::ErrorMismatchOutputType(self.map_type_name(type_name).into(), pos)
+        })?;
 
         // Compile the script text
         // No optimizations because we only run it once
@@ -759,6 +1093,7 @@ impl Engine {
         rhs: &Expr,
         idx_values: &mut StaticVec,
         is_index: bool,
+        is_safe: bool,
         op_pos: Position,
         level: usize,
         mut new_val: Option<Dynamic>,
@@ -770,20 +1105,58 @@ impl Engine {
             Target::StringChar(ref mut x) => &mut x.2,
         };
 
+        // Safe navigation on () - short-circuit the rest of the chain
+        if is_safe && obj.is::<()>() {
+            return Ok((().into(), false));
+        }
+
         // Pop the last index value
         let mut idx_val = idx_values.pop();
 
         if is_index {
             match rhs {
+                // {xxx:custom}[idx].dot_rhs...
+                Expr::Dot(_, idx_rhs, is_safe, pos) |
+                // {xxx:custom}[idx][dot_rhs]...
+                Expr::Index(_, idx_rhs, is_safe, pos) if !is_builtin_indexable(obj) => {
+                    let is_index = matches!(rhs, Expr::Index(_,_,_,_));
+                    let mut buf: Dynamic = ().into();
+                    let mut args = [obj, &mut idx_val, &mut buf];
+
+                    let indexed_val = &mut self.exec_fn_call(fn_lib, FUNC_INDEXER_GET, &mut args[..2], None, op_pos, 0)?;
+                    let (result, changed) = self.eval_dot_index_chain_helper(
+                        fn_lib, indexed_val.into(), idx_rhs.as_ref(), idx_values, is_index, *is_safe, *pos, level, new_val
+                    )?;
+
+                    // Feed the value back via the indexer just in case it has been updated
+                    if changed {
+                        args[2] = indexed_val;
+                        self.exec_fn_call(fn_lib, FUNC_INDEXER_SET, &mut args, None, op_pos, 0)?;
+                    }
+
+                    Ok((result, changed))
+                }
+                // {xxx:custom}[rhs] = new_val
+                _ if new_val.is_some() && !is_builtin_indexable(obj) => {
+                    let mut args = [obj, &mut idx_val, new_val.as_mut().unwrap()];
+                    self.exec_fn_call(fn_lib, FUNC_INDEXER_SET, &mut args, None, op_pos, 0)
+                        .map(|v| (v, true))
+                }
+                // {xxx:custom}[rhs]
+                _ if !is_builtin_indexable(obj) => {
+                    let mut args = [obj, &mut idx_val];
+                    self.exec_fn_call(fn_lib, FUNC_INDEXER_GET, &mut args, None, op_pos, 0)
+                        .map(|v| (v, false))
+                }
                 // xxx[idx].dot_rhs...
-                Expr::Dot(idx, idx_rhs, pos) |
+                Expr::Dot(idx, idx_rhs, is_safe, pos) |
                 // xxx[idx][dot_rhs]...
-                Expr::Index(idx, idx_rhs, pos) => {
-                    let is_index = matches!(rhs, Expr::Index(_,_,_));
+                Expr::Index(idx, idx_rhs, is_safe, pos) => {
+                    let is_index = matches!(rhs, Expr::Index(_,_,_,_));
 
                     let indexed_val = self.get_indexed_mut(obj, idx_val, idx.position(), op_pos, false)?;
                     self.eval_dot_index_chain_helper(
-                        fn_lib, indexed_val, idx_rhs.as_ref(), idx_values, is_index, *pos, level, new_val
+                        fn_lib, indexed_val, idx_rhs.as_ref(), idx_values, is_index, *is_safe, *pos, level, new_val
                     )
                 }
                 // xxx[rhs] = new_val
@@ -801,13 +1174,27 @@ impl Engine {
             match rhs {
                 // xxx.fn_name(arg_expr_list)
                 Expr::FunctionCall(fn_name, _, def_val, pos) => {
-                    let mut args: Vec<_> = once(obj)
-                        .chain(idx_val.downcast_mut::<Array>().unwrap().iter_mut())
-                        .collect();
+                    let arg_values = idx_val.downcast_mut::<Array>().unwrap();
+
+                    // Script-defined method - bind `this` to the object
+                    if let Some(fn_def) = fn_lib.get_function(fn_name, arg_values.len(), |i| {
+                        self.map_type_name(arg_values[i].type_name())
+                    }) {
+                        if level > self.max_call_stack_depth {
+                            return Err(Box::new(EvalAltResult::ErrorStackOverflow(*pos)));
+                        }
+
+                        let mut args: Vec<_> = arg_values.iter_mut().collect();
+                        return self
+                            .call_fn_from_lib(None, fn_lib, fn_def, &mut args, Some(obj), *pos, level)
+                            .map(|v| (v, true));
+                    }
+
+                    let mut args: Vec<_> = once(obj).chain(arg_values.iter_mut()).collect();
                     let def_val = def_val.as_deref();
                     // A function call is assumed to have side effects, so the value is changed
                     // TODO - Remove assumption of side effects by checking whether the first parameter is &mut
-                    self.exec_fn_call(fn_lib, fn_name, &mut args, def_val, *pos, 0).map(|v| (v, true))
+                    self.exec_fn_call(fn_lib, fn_name, &mut args, def_val, *pos, level).map(|v| (v, true))
                 }
                 // {xxx:map}.id = ???
                 Expr::Property(id, pos) if obj.is::<Map>() && new_val.is_some() => {
@@ -835,10 +1222,10 @@ impl Engine {
                     self.exec_fn_call(fn_lib, &fn_name, &mut args, None, *pos, 0).map(|v| (v, false))
                 }
                 // {xxx:map}.idx_lhs[idx_expr]
-                Expr::Index(dot_lhs, dot_rhs, pos) |
+                Expr::Index(dot_lhs, dot_rhs, is_safe, pos) |
                 // {xxx:map}.dot_lhs.rhs
-                Expr::Dot(dot_lhs, dot_rhs, pos) if obj.is::<Map>() => {
-                    let is_index = matches!(rhs, Expr::Index(_,_,_));
+                Expr::Dot(dot_lhs, dot_rhs, is_safe, pos) if obj.is::<Map>() => {
+                    let is_index = matches!(rhs, Expr::Index(_,_,_,_));
 
                     let indexed_val = if let Expr::Property(id, pos) = dot_lhs.as_ref() {
                         self.get_indexed_mut(obj, id.to_string().into(), *pos, op_pos, false)?
@@ -850,14 +1237,14 @@ impl Engine {
                         )));
                     };
                     self.eval_dot_index_chain_helper(
-                        fn_lib, indexed_val, dot_rhs, idx_values, is_index, *pos, level, new_val
+                        fn_lib, indexed_val, dot_rhs, idx_values, is_index, *is_safe, *pos, level, new_val
                     )
                 }
                 // xxx.idx_lhs[idx_expr]
-                Expr::Index(dot_lhs, dot_rhs, pos) |
+                Expr::Index(dot_lhs, dot_rhs, is_safe, pos) |
                 // xxx.dot_lhs.rhs
-                Expr::Dot(dot_lhs, dot_rhs, pos) => {
-                    let is_index = matches!(rhs, Expr::Index(_,_,_));
+                Expr::Dot(dot_lhs, dot_rhs, is_safe, pos) => {
+                    let is_index = matches!(rhs, Expr::Index(_,_,_,_));
                     let mut buf: Dynamic = ().into();
                     let mut args = [obj, &mut buf];
 
@@ -872,7 +1259,7 @@ impl Engine {
                         )));
                     });
                     let (result, changed) = self.eval_dot_index_chain_helper(
-                        fn_lib, indexed_val.into(), dot_rhs, idx_values, is_index, *pos, level, new_val
+                        fn_lib, indexed_val.into(), dot_rhs, idx_values, is_index, *is_safe, *pos, level, new_val
                     )?;
 
                     // Feed the value back via a setter just in case it has been updated
@@ -904,6 +1291,7 @@ impl Engine {
         dot_lhs: &Expr,
         dot_rhs: &Expr,
         is_index: bool,
+        is_safe: bool,
         op_pos: Position,
         level: usize,
         new_val: Option<Dynamic>,
@@ -917,6 +1305,32 @@ impl Engine {
             Expr::Variable(id, index, pos) => {
                 let (target, typ) = match index {
                     Some(i) if !state.always_search => scope.get_mut(scope.len() - i.get()),
+                    // Variables provided by the variable resolver are read-only
+                    _ if scope.get(id).is_none() => {
+                        let val =
+                            self.resolve_var(scope, state, fn_lib, id, *index, *pos, level)?;
+
+                        if new_val.is_some() {
+                            return Err(Box::new(EvalAltResult::ErrorAssignmentToConstant(
+                                id.to_string(),
+                                *pos,
+                            )));
+                        }
+
+                        return self
+                            .eval_dot_index_chain_helper(
+                                fn_lib,
+                                val.into(),
+                                dot_rhs,
+                                idx_values,
+                                is_index,
+                                is_safe,
+                                op_pos,
+                                level,
+                                new_val,
+                            )
+                            .map(|(v, _)| v);
+                    }
                     _ => search_scope(scope, id, *pos)?,
                 };
 
@@ -937,6 +1351,7 @@ impl Engine {
                     dot_rhs,
                     idx_values,
                     is_index,
+                    is_safe,
                     op_pos,
                     level,
                     new_val,
@@ -959,6 +1374,7 @@ impl Engine {
                     dot_rhs,
                     idx_values,
                     is_index,
+                    is_safe,
                     op_pos,
                     level,
                     new_val,
@@ -968,6 +1384,45 @@ impl Engine {
         }
     }
 
+    /// Resolve a variable not found in the `Scope` via the variable resolver registered with `on_var`.
+    ///
+    /// The index passed to the resolver is the variable's offset from the end of the `Scope`
+    /// as calculated during parsing, or zero if it is not known.
+    fn resolve_var(
+        &self,
+        scope: &mut Scope,
+        state: &mut State,
+        fn_lib: &FunctionsLib,
+        name: &str,
+        index: Option<NonZeroUsize>,
+        pos: Position,
+        level: usize,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let not_found = || Box::new(EvalAltResult::ErrorVariableNotFound(name.into(), pos));
+
+        let resolver = match &self.var_resolver {
+            Some(resolver) => resolver,
+            None => return Err(not_found()),
+        };
+
+        let index = match index {
+            Some(index) if !state.always_search => index.get(),
+            _ => 0,
+        };
+
+        let context = EvalContext {
+            engine: self,
+            scope,
+            state,
+            fn_lib,
+            level,
+        };
+
+        resolver(name, index, &context)
+            .map_err(|err| EvalAltResult::set_position(err, pos))?
+            .ok_or_else(not_found)
+    }
+
     /// Evaluate a chain of indexes and store the results in a list.
     /// The first few results are stored in the array `list` which is of fixed length.
     /// Any spill-overs are stored in `more`, which is dynamic.
@@ -994,7 +1449,7 @@ impl Engine {
             }
             // Store a placeholder - no need to copy the property name
             Expr::Property(_, _) => idx_values.push(()),
-            Expr::Index(lhs, rhs, _) | Expr::Dot(lhs, rhs, _) => {
+            Expr::Index(lhs, rhs, _, _) | Expr::Dot(lhs, rhs, _, _) => {
                 // Evaluate in left-to-right order
                 let lhs_val = match lhs.as_ref() {
                     Expr::Property(_, _) => ().into(), // Store a placeholder in case of a property
@@ -1052,7 +1507,7 @@ impl Engine {
                     .map_err(|_| EvalAltResult::ErrorStringIndexExpr(idx_pos))?;
 
                 Ok(if create {
-                    map.entry(index).or_insert(().into()).into()
+                    map.get_or_insert(index, ().into()).into()
                 } else {
                     map.get_mut(&index)
                         .map(Target::from)
@@ -1142,7 +1597,7 @@ impl Engine {
     }
 
     /// Evaluate an expression
-    fn eval_expr(
+    pub(crate) fn eval_expr(
         &self,
         scope: &mut Scope,
         state: &mut State,
@@ -1156,10 +1611,16 @@ impl Engine {
             Expr::FloatConstant(f, _) => Ok((*f).into()),
             Expr::StringConstant(s, _) => Ok(s.to_string().into()),
             Expr::CharConstant(c, _) => Ok((*c).into()),
-            Expr::Variable(_, Some(index), _) if !state.always_search => {
-                Ok(scope.get_mut(scope.len() - index.get()).0.clone())
-            }
-            Expr::Variable(id, _, pos) => search_scope(scope, id, *pos).map(|(v, _)| v.clone()),
+            Expr::Variable(id, index, pos) => match index {
+                Some(index) if !state.always_search => {
+                    Ok(scope.get_mut(scope.len() - index.get()).0.clone())
+                }
+                // Variables not in the scope may be provided by the variable resolver
+                _ if scope.get(id).is_none() => {
+                    self.resolve_var(scope, state, fn_lib, id, *index, *pos, level)
+                }
+                _ => search_scope(scope, id, *pos).map(|(v, _)| v.clone()),
+            },
             Expr::Property(_, _) => panic!("unexpected property."),
 
             // Statement block
@@ -1192,18 +1653,20 @@ impl Engine {
 
                     // idx_lhs[idx_expr] = rhs
                     #[cfg(not(feature = "no_index"))]
-                    Expr::Index(idx_lhs, idx_expr, op_pos) => {
+                    Expr::Index(idx_lhs, idx_expr, is_safe, op_pos) => {
                         let new_val = Some(rhs_val);
                         self.eval_dot_index_chain(
-                            scope, state, fn_lib, idx_lhs, idx_expr, true, *op_pos, level, new_val,
+                            scope, state, fn_lib, idx_lhs, idx_expr, true, *is_safe, *op_pos,
+                            level, new_val,
                         )
                     }
                     // dot_lhs.dot_rhs = rhs
                     #[cfg(not(feature = "no_object"))]
-                    Expr::Dot(dot_lhs, dot_rhs, _) => {
+                    Expr::Dot(dot_lhs, dot_rhs, is_safe, _) => {
                         let new_val = Some(rhs_val);
                         self.eval_dot_index_chain(
-                            scope, state, fn_lib, dot_lhs, dot_rhs, false, *op_pos, level, new_val,
+                            scope, state, fn_lib, dot_lhs, dot_rhs, false, *is_safe, *op_pos,
+                            level, new_val,
                         )
                     }
                     // Error assignment to constant
@@ -1223,14 +1686,14 @@ impl Engine {
 
             // lhs[idx_expr]
             #[cfg(not(feature = "no_index"))]
-            Expr::Index(lhs, idx_expr, op_pos) => self.eval_dot_index_chain(
-                scope, state, fn_lib, lhs, idx_expr, true, *op_pos, level, None,
+            Expr::Index(lhs, idx_expr, is_safe, op_pos) => self.eval_dot_index_chain(
+                scope, state, fn_lib, lhs, idx_expr, true, *is_safe, *op_pos, level, None,
             ),
 
             // lhs.dot_rhs
             #[cfg(not(feature = "no_object"))]
-            Expr::Dot(lhs, dot_rhs, op_pos) => self.eval_dot_index_chain(
-                scope, state, fn_lib, lhs, dot_rhs, false, *op_pos, level, None,
+            Expr::Dot(lhs, dot_rhs, is_safe, op_pos) => self.eval_dot_index_chain(
+                scope, state, fn_lib, lhs, dot_rhs, false, *is_safe, *op_pos, level, None,
             ),
 
             #[cfg(not(feature = "no_index"))]
@@ -1326,6 +1789,30 @@ impl Engine {
                     })?)
             .into()),
 
+            Expr::Coalesce(lhs, rhs, _) => {
+                match self.eval_expr(scope, state, fn_lib, lhs.as_ref(), level)? {
+                    // Short-circuit if not ()
+                    val if !val.is::<()>() => Ok(val),
+                    _ => self.eval_expr(scope, state, fn_lib, rhs.as_ref(), level),
+                }
+            }
+
+            Expr::Custom(key, inputs, pos) => {
+                let syntax = self.custom_syntax.get(key).ok_or_else(|| {
+                    Box::new(EvalAltResult::ErrorFunctionNotFound(key.clone(), *pos))
+                })?;
+                let inputs: Vec<_> = inputs.iter().map(Expression::from).collect();
+                let mut context = EvalContext {
+                    engine: self,
+                    scope,
+                    state,
+                    fn_lib,
+                    level,
+                };
+
+                (syntax.func)(&mut context, &inputs)
+            }
+
             Expr::True(_) => Ok(true.into()),
             Expr::False(_) => Ok(false.into()),
             Expr::Unit(_) => Ok(().into()),
@@ -1392,7 +1879,7 @@ impl Engine {
                 }),
 
             // While loop
-            Stmt::While(guard, body) => loop {
+            Stmt::While(guard, body, label) => loop {
                 match self
                     .eval_expr(scope, state, fn_lib, guard, level)?
                     .as_bool()
@@ -1400,8 +1887,13 @@ impl Engine {
                     Ok(true) => match self.eval_stmt(scope, state, fn_lib, body, level) {
                         Ok(_) => (),
                         Err(err) => match *err {
-                            EvalAltResult::ErrorLoopBreak(false, _) => (),
-                            EvalAltResult::ErrorLoopBreak(true, _) => return Ok(().into()),
+                            EvalAltResult::ErrorLoopBreak(false, ref target, _, _)
+                                if is_loop_target(target, label) => {}
+                            EvalAltResult::ErrorLoopBreak(true, ref target, _, _)
+                                if is_loop_target(target, label) =>
+                            {
+                                return Ok(().into())
+                            }
                             _ => return Err(err),
                         },
                     },
@@ -1412,20 +1904,53 @@ impl Engine {
                 }
             },
 
+            // Do loop
+            Stmt::Do(body, guard, is_until, label) => loop {
+                match self.eval_stmt(scope, state, fn_lib, body, level) {
+                    Ok(_) => (),
+                    Err(err) => match *err {
+                        EvalAltResult::ErrorLoopBreak(false, ref target, _, _)
+                            if is_loop_target(target, label) => {}
+                        EvalAltResult::ErrorLoopBreak(true, ref target, _, _)
+                            if is_loop_target(target, label) =>
+                        {
+                            return Ok(().into())
+                        }
+                        _ => return Err(err),
+                    },
+                }
+
+                match self
+                    .eval_expr(scope, state, fn_lib, guard, level)?
+                    .as_bool()
+                {
+                    Ok(guard_val) if guard_val == *is_until => return Ok(().into()),
+                    Ok(_) => (),
+                    Err(_) => {
+                        return Err(Box::new(EvalAltResult::ErrorLogicGuard(guard.position())))
+                    }
+                }
+            },
+
             // Loop statement
-            Stmt::Loop(body) => loop {
+            Stmt::Loop(body, label) => loop {
                 match self.eval_stmt(scope, state, fn_lib, body, level) {
                     Ok(_) => (),
                     Err(err) => match *err {
-                        EvalAltResult::ErrorLoopBreak(false, _) => (),
-                        EvalAltResult::ErrorLoopBreak(true, _) => return Ok(().into()),
+                        EvalAltResult::ErrorLoopBreak(false, ref target, _, _)
+                            if is_loop_target(target, label) => {}
+                        EvalAltResult::ErrorLoopBreak(true, target, value, _)
+                            if is_loop_target(&target, label) =>
+                        {
+                            return Ok(value)
+                        }
                         _ => return Err(err),
                     },
                 }
             },
 
             // For loop
-            Stmt::For(name, expr, body) => {
+            Stmt::For(pattern, expr, body, label) => {
                 let arr = self.eval_expr(scope, state, fn_lib, expr, level)?;
                 let tid = arr.type_id();
 
@@ -1435,24 +1960,39 @@ impl Engine {
                         .find(|pkg| pkg.type_iterators.contains_key(&tid))
                         .and_then(|pkg| pkg.type_iterators.get(&tid))
                 }) {
-                    // Add the loop variable
-                    scope.push(name.clone(), ());
-                    let index = scope.len() - 1;
+                    // Add the loop variables
+                    let index = scope.len();
+                    pattern
+                        .variables()
+                        .into_iter()
+                        .for_each(|name| scope.push(name.clone(), ()));
 
                     for a in iter_fn(arr) {
-                        *scope.get_mut(index).0 = a;
+                        match pattern {
+                            Pattern::Variable(_) => *scope.get_mut(index).0 = a,
+                            _ => self
+                                .destructure(pattern, a, expr.position())?
+                                .into_iter()
+                                .enumerate()
+                                .for_each(|(i, v)| *scope.get_mut(index + i).0 = v),
+                        }
 
                         match self.eval_stmt(scope, state, fn_lib, body, level) {
                             Ok(_) => (),
                             Err(err) => match *err {
-                                EvalAltResult::ErrorLoopBreak(false, _) => (),
-                                EvalAltResult::ErrorLoopBreak(true, _) => break,
+                                EvalAltResult::ErrorLoopBreak(false, ref target, _, _)
+                                    if is_loop_target(target, label) => {}
+                                EvalAltResult::ErrorLoopBreak(true, ref target, _, _)
+                                    if is_loop_target(target, label) =>
+                                {
+                                    break
+                                }
                                 _ => return Err(err),
                             },
                         }
                     }
 
-                    scope.rewind(scope.len() - 1);
+                    scope.rewind(index);
                     Ok(().into())
                 } else {
                     Err(Box::new(EvalAltResult::ErrorFor(expr.position())))
@@ -1460,10 +2000,27 @@ impl Engine {
             }
 
             // Continue statement
-            Stmt::Continue(pos) => Err(Box::new(EvalAltResult::ErrorLoopBreak(false, *pos))),
+            Stmt::Continue(label, pos) => Err(Box::new(EvalAltResult::ErrorLoopBreak(
+                false,
+                label.clone(),
+                ().into(),
+                *pos,
+            ))),
 
             // Break statement
-            Stmt::Break(pos) => Err(Box::new(EvalAltResult::ErrorLoopBreak(true, *pos))),
+            Stmt::Break(label, value, pos) => {
+                let value = match value {
+                    Some(expr) => self.eval_expr(scope, state, fn_lib, expr, level)?,
+                    None => ().into(),
+                };
+
+                Err(Box::new(EvalAltResult::ErrorLoopBreak(
+                    true,
+                    label.clone(),
+                    value,
+                    *pos,
+                )))
+            }
 
             // Empty return
             Stmt::ReturnWithVal(None, ReturnType::Return, pos) => {
@@ -1490,16 +2047,34 @@ impl Engine {
             }
 
             // Let statement
-            Stmt::Let(name, Some(expr), _) => {
+            Stmt::Let(Pattern::Variable(name), Some(expr), _) => {
                 let val = self.eval_expr(scope, state, fn_lib, expr, level)?;
                 // TODO - avoid copying variable name in inner block?
                 scope.push_dynamic_value(name.clone(), ScopeEntryType::Normal, val, false);
                 Ok(().into())
             }
 
-            Stmt::Let(name, None, _) => {
+            // Let statement with destructuring
+            Stmt::Let(pattern, Some(expr), _) => {
+                let val = self.eval_expr(scope, state, fn_lib, expr, level)?;
+                let values = self.destructure(pattern, val, expr.position())?;
+                // TODO - avoid copying variable names in inner block?
+                scope.extend(
+                    pattern
+                        .variables()
+                        .into_iter()
+                        .zip(values)
+                        .map(|(name, value)| (name.clone(), ScopeEntryType::Normal, value)),
+                );
+                Ok(().into())
+            }
+
+            Stmt::Let(pattern, None, _) => {
                 // TODO - avoid copying variable name in inner block?
-                scope.push(name.clone(), ());
+                pattern
+                    .variables()
+                    .into_iter()
+                    .for_each(|name| scope.push(name.clone(), ()));
                 Ok(().into())
             }
 
@@ -1515,6 +2090,62 @@ impl Engine {
         }
     }
 
+    /// Destructure a value according to a pattern.
+    /// Returns the values of the variables bound by the pattern, in order of binding.
+    ///
+    /// Array elements or object map properties that do not exist are bound to `()`.
+    fn destructure(
+        &self,
+        pattern: &Pattern,
+        value: Dynamic,
+        pos: Position,
+    ) -> Result<Vec<Dynamic>, Box<EvalAltResult>> {
+        let type_name = value.type_name();
+
+        match pattern {
+            Pattern::Variable(_) => Ok(vec![value]),
+
+            Pattern::Array(names, rest) => {
+                let mut arr = value.try_cast::<Array>().ok_or_else(|| {
+                    Box::new(EvalAltResult::ErrorPatternMismatch(
+                        "array".into(),
+                        self.map_type_name(type_name).into(),
+                        pos,
+                    ))
+                })?;
+
+                let rest_values = if arr.len() > names.len() {
+                    arr.split_off(names.len())
+                } else {
+                    Vec::new()
+                };
+
+                arr.resize(names.len(), ().into());
+
+                if rest.is_some() {
+                    arr.push(rest_values.into());
+                }
+
+                Ok(arr)
+            }
+
+            Pattern::Map(props) => {
+                let map = value.try_cast::<Map>().ok_or_else(|| {
+                    Box::new(EvalAltResult::ErrorPatternMismatch(
+                        "map".into(),
+                        self.map_type_name(type_name).into(),
+                        pos,
+                    ))
+                })?;
+
+                Ok(props
+                    .iter()
+                    .map(|(prop, _)| map.get(prop).cloned().unwrap_or_else(|| ().into()))
+                    .collect())
+            }
+        }
+    }
+
     /// Map a type_name into a pretty-print name
     pub(crate) fn map_type_name<'a>(&'a self, name: &'a str) -> &'a str {
         self.type_names
@@ -21,6 +21,8 @@ pub enum LexError {
     MalformedIdentifier(String),
     /// Bad keyword encountered when tokenizing the script text.
     ImproperKeyword(String),
+    /// A keyword or operator that has been disabled on the `Engine`. Wrapped value is the symbol.
+    DisabledSymbol(String),
 }
 
 impl Error for LexError {}
@@ -35,6 +37,17 @@ impl fmt::Display for LexError {
             Self::MalformedIdentifier(s) => write!(f, "Variable name is not proper: '{}'", s),
             Self::UnterminatedString => write!(f, "Open string is not terminated"),
             Self::ImproperKeyword(s) => write!(f, "{}", s),
+            Self::DisabledSymbol(s) => write!(f, "'{}' is disabled", s),
+        }
+    }
+}
+
+impl LexError {
+    /// Convert a `LexError` into a `ParseError` at the specified position.
+    pub(crate) fn to_err(&self, pos: Position) -> Box<ParseError> {
+        match self {
+            Self::DisabledSymbol(s) => ParseErrorType::DisabledSymbol(s.clone()).into_err(pos),
+            err => ParseErrorType::BadInput(err.to_string()).into_err(pos),
         }
     }
 }
@@ -76,6 +89,9 @@ pub enum ParseErrorType {
     PropertyExpected,
     /// Missing a variable name after the `let`, `const` or `for` keywords.
     VariableExpected,
+    /// A variable is used without being declared, under strict variables mode.
+    /// Wrapped value is the variable name.
+    VariableUndefined(String),
     /// Missing an expression. Wrapped value is the expression type.
     ExprExpected(String),
     /// Defining a function `fn` in an appropriate place (e.g. inside another function).
@@ -94,6 +110,11 @@ pub enum ParseErrorType {
     ///
     /// Never appears under the `no_function` feature.
     FnDuplicatedParam(String, String),
+    /// A function parameter is missing its type after the `:` annotation.
+    /// Wrapped values are the function name and parameter name.
+    ///
+    /// Never appears under the `no_function` feature.
+    FnMissingParamType(String, String),
     /// A function definition is missing the body. Wrapped value is the function name.
     ///
     /// Never appears under the `no_function` feature.
@@ -104,6 +125,14 @@ pub enum ParseErrorType {
     AssignmentToConstant(String),
     /// Break statement not inside a loop.
     LoopBreak,
+    /// A `break` or `continue` statement refers to a label that is not on any enclosing loop.
+    /// Wrapped value is the label name.
+    LoopLabelNotFound(String),
+    /// A `break` statement carries a value but the loop it breaks out of is not a `loop`.
+    LoopBreakValue,
+    /// A keyword or operator that has been disabled on the `Engine` is used.
+    /// Wrapped value is the keyword or operator.
+    DisabledSymbol(String),
 }
 
 impl ParseErrorType {
@@ -141,15 +170,20 @@ impl ParseError {
             ParseErrorType::ForbiddenConstantExpr(_) => "Expecting a constant",
             ParseErrorType::PropertyExpected => "Expecting name of a property",
             ParseErrorType::VariableExpected => "Expecting name of a variable",
+            ParseErrorType::VariableUndefined(_) => "Undefined variable",
             ParseErrorType::ExprExpected(_) => "Expecting an expression",
             ParseErrorType::FnMissingName => "Expecting name in function declaration",
             ParseErrorType::FnMissingParams(_) => "Expecting parameters in function declaration",
             ParseErrorType::FnDuplicatedParam(_,_) => "Duplicated parameters in function declaration",
+            ParseErrorType::FnMissingParamType(_,_) => "Expecting type name for parameter in function declaration",
             ParseErrorType::FnMissingBody(_) => "Expecting body statement block for function declaration",
             ParseErrorType::WrongFnDefinition => "Function definitions must be at global level and cannot be inside a block or another function",
             ParseErrorType::AssignmentToInvalidLHS => "Cannot assign to this expression",
             ParseErrorType::AssignmentToConstant(_) => "Cannot assign to a constant variable.",
-            ParseErrorType::LoopBreak => "Break statement should only be used inside a loop"
+            ParseErrorType::LoopBreak => "Break statement should only be used inside a loop",
+            ParseErrorType::LoopLabelNotFound(_) => "Loop label is not defined on any enclosing loop",
+            ParseErrorType::LoopBreakValue => "Only a 'loop' statement can be broken out of with a value",
+            ParseErrorType::DisabledSymbol(_) => "Use of a disabled keyword or operator",
         }
     }
 }
@@ -181,6 +215,8 @@ impl fmt::Display for ParseError {
 
             ParseErrorType::ExprExpected(s) => write!(f, "Expecting {} expression", s)?,
 
+            ParseErrorType::VariableUndefined(s) => write!(f, "Undefined variable: '{}'", s)?,
+
             ParseErrorType::FnMissingParams(s) => {
                 write!(f, "Expecting parameters for function '{}'", s)?
             }
@@ -193,8 +229,20 @@ impl fmt::Display for ParseError {
                 write!(f, "Duplicated parameter '{}' for function '{}'", arg, s)?
             }
 
+            ParseErrorType::FnMissingParamType(s, arg) => write!(
+                f,
+                "Expecting type name for parameter '{}' of function '{}'",
+                arg, s
+            )?,
+
             ParseErrorType::MissingToken(token, s) => write!(f, "Expecting '{}' {}", token, s)?,
 
+            ParseErrorType::LoopLabelNotFound(s) => {
+                write!(f, "Loop label '{}' is not defined on any enclosing loop", s)?
+            }
+
+            ParseErrorType::DisabledSymbol(s) => write!(f, "'{}' is disabled", s)?,
+
             ParseErrorType::AssignmentToConstant(s) if s.is_empty() => {
                 write!(f, "{}", self.desc())?
             }
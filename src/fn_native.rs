@@ -0,0 +1,100 @@
+//! Module containing the context passed to native Rust functions, and function pointers.
+
+use crate::any::Dynamic;
+use crate::engine::{Engine, FunctionsLib};
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+use crate::stdlib::{boxed::Box, fmt, string::String, vec::Vec};
+
+/// The context of a call to a native Rust function.
+///
+/// It gives the function access to the `Engine` and the script-defined functions,
+/// so that it can call back into the script, e.g. to invoke a callback passed in by name.
+#[derive(Clone, Copy)]
+pub struct NativeCallContext<'a> {
+    pub(crate) engine: &'a Engine,
+    pub(crate) fn_lib: &'a FunctionsLib,
+    pub(crate) pos: Position,
+    pub(crate) level: usize,
+}
+
+impl<'a> NativeCallContext<'a> {
+    /// Get the scripting `Engine`.
+    pub fn engine(&self) -> &'a Engine {
+        self.engine
+    }
+    /// Get the library of script-defined functions visible to the call.
+    pub fn fn_lib(&self) -> &'a FunctionsLib {
+        self.fn_lib
+    }
+    /// Get the `Position` of the function call in the script.
+    pub fn position(&self) -> Position {
+        self.pos
+    }
+    /// Get the current level of function calls, zero being the global level.
+    pub fn call_level(&self) -> usize {
+        self.level
+    }
+    /// Call a function, either script-defined or registered with the `Engine`, with the given arguments.
+    ///
+    /// Script-defined functions cannot access the caller's `Scope`.
+    pub fn call_fn(
+        &self,
+        fn_name: &str,
+        args: &mut [&mut Dynamic],
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        self.engine
+            .call_fn_raw(None, self.fn_lib, fn_name, args, None, self.pos, self.level)
+    }
+}
+
+/// A function pointer, referring to a script-defined or registered function by name.
+///
+/// Arguments can be bound to the function pointer in advance (i.e. curried), which are then
+/// passed to the function before the arguments of each call.  Closures in scripts evaluate to
+/// function pointers with their captured variables curried.
+#[derive(Debug, Clone)]
+pub struct FnPtr {
+    name: String,
+    curry: Vec<Dynamic>,
+}
+
+impl FnPtr {
+    /// Create a new `FnPtr` referring to a function by name.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            curry: Vec::new(),
+        }
+    }
+    /// Get the name of the function.
+    pub fn fn_name(&self) -> &str {
+        &self.name
+    }
+    /// Get the curried arguments.
+    pub fn curry(&self) -> &[Dynamic] {
+        &self.curry
+    }
+    /// Bind more arguments to the function pointer.
+    pub fn add_curry(&mut self, args: impl IntoIterator<Item = Dynamic>) {
+        self.curry.extend(args);
+    }
+    /// Call the function, passing the curried arguments followed by `args`.
+    pub fn call_dynamic(
+        &self,
+        context: &NativeCallContext,
+        args: impl IntoIterator<Item = Dynamic>,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        let mut arg_values: Vec<_> = self.curry.iter().cloned().chain(args).collect();
+        let mut args: Vec<_> = arg_values.iter_mut().collect();
+
+        context.call_fn(&self.name, &mut args)
+    }
+}
+
+impl fmt::Display for FnPtr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fn({})", self.name)
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::any::{Dynamic, Variant};
 use crate::engine::{calc_fn_spec, Engine, FnCallArgs};
+use crate::fn_native::NativeCallContext;
 use crate::result::EvalAltResult;
 use crate::token::Position;
 
@@ -98,6 +99,35 @@ pub trait RegisterResultFn<FN, ARGS, RET> {
     fn register_result_fn(&mut self, name: &str, f: FN);
 }
 
+/// A trait to register fallible custom functions that receive a `NativeCallContext` with the `Engine`.
+pub trait RegisterContextFn<FN, ARGS, RET> {
+    /// Register a custom fallible function with the `Engine`.
+    /// The function receives a `NativeCallContext` before its arguments, which it can use to call back into the script.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, RegisterContextFn, NativeCallContext};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // You must use the trait rhai::RegisterContextFn to get this method.
+    /// // Call the script-defined function named 'callback' with 'x'.
+    /// engine.register_context_fn("apply", |context: &NativeCallContext, x: i64, callback: String| {
+    ///     context.call_fn(&callback, &mut [&mut x.into()])
+    /// });
+    ///
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// assert_eq!(engine.eval::<i64>(r#"fn double(x) { x * 2 } apply(21, "double")"#)?, 42);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn register_context_fn(&mut self, name: &str, f: FN);
+}
+
 // These types are used to build a unique _marker_ tuple type for each combination
 // of function parameter types in order to make each trait implementation unique.
 // That is because stable Rust currently does not allow distinguishing implementations
@@ -135,14 +165,18 @@ macro_rules! count_args {
 
 /// This macro creates a closure wrapping a registered function.
 macro_rules! make_func {
-	($fn_name:ident : $fn:ident : $map:expr ; $($par:ident => $clone:expr),*) => {
-//   ^ function name
-//                    ^ function pointer
-//                                ^ result mapping function
-//                                              ^ function parameter generic type name (A, B, C etc.)
-//                                                            ^ dereferencing function
-
-		move |args: &mut FnCallArgs, pos: Position| {
+	($context:ident : $fn_name:ident : $fn:ident $(($pass:ident))? : $map:expr ; $($par:ident => $clone:expr),*) => {
+//   ^ name of the NativeCallContext parameter
+//                    ^ function name
+//                                     ^ function pointer
+//                                                 ^ passes the NativeCallContext to the function if present
+//                                                                 ^ result mapping function
+//                                                                               ^ function parameter generic type name (A, B, C etc.)
+//                                                                                             ^ dereferencing function
+
+		move |$context: &NativeCallContext, args: &mut FnCallArgs| {
+			let pos = $context.position();
+
 			// Check for length at the beginning to avoid per-element bound checks.
 			const NUM_ARGS: usize = count_args!($($par)*);
 
@@ -159,7 +193,7 @@ macro_rules! make_func {
 
 			// Call the user-supplied function using ($clone) to
 			// potentially clone the value, otherwise pass the reference.
-			let r = $fn($(($clone)($par)),*);
+			let r = $fn($($pass,)? $(($clone)($par)),*);
 			$map(r, pos)
 		};
 	};
@@ -213,7 +247,7 @@ macro_rules! def_register {
         {
             fn register_fn(&mut self, name: &str, f: FN) {
                 let fn_name = name.to_string();
-                let func = make_func!(fn_name : f : map_dynamic ; $($par => $clone),*);
+                let func = make_func!(_context : fn_name : f : map_dynamic ; $($par => $clone),*);
                 let hash = calc_fn_spec(name, [$(TypeId::of::<$par>()),*].iter().cloned());
                 self.functions.insert(hash, Box::new(func));
             }
@@ -231,7 +265,7 @@ macro_rules! def_register {
         {
             fn register_dynamic_fn(&mut self, name: &str, f: FN) {
                 let fn_name = name.to_string();
-                let func = make_func!(fn_name : f : map_identity ; $($par => $clone),*);
+                let func = make_func!(_context : fn_name : f : map_identity ; $($par => $clone),*);
                 let hash = calc_fn_spec(name, [$(TypeId::of::<$par>()),*].iter().cloned());
                 self.functions.insert(hash, Box::new(func));
             }
@@ -250,7 +284,26 @@ macro_rules! def_register {
         {
             fn register_result_fn(&mut self, name: &str, f: FN) {
                 let fn_name = name.to_string();
-                let func = make_func!(fn_name : f : map_result ; $($par => $clone),*);
+                let func = make_func!(_context : fn_name : f : map_result ; $($par => $clone),*);
+                let hash = calc_fn_spec(name, [$(TypeId::of::<$par>()),*].iter().cloned());
+                self.functions.insert(hash, Box::new(func));
+            }
+        }
+
+        impl<
+            $($par: Variant + Clone,)*
+
+            #[cfg(feature = "sync")]
+            FN: Fn(&NativeCallContext, $($param),*) -> Result<RET, Box<EvalAltResult>> + Send + Sync + 'static,
+            #[cfg(not(feature = "sync"))]
+            FN: Fn(&NativeCallContext, $($param),*) -> Result<RET, Box<EvalAltResult>> + 'static,
+
+            RET: Variant + Clone
+        > RegisterContextFn<FN, ($($mark,)*), RET> for Engine
+        {
+            fn register_context_fn(&mut self, name: &str, f: FN) {
+                let fn_name = name.to_string();
+                let func = make_func!(context : fn_name : f (context) : map_result ; $($par => $clone),*);
                 let hash = calc_fn_spec(name, [$(TypeId::of::<$par>()),*].iter().cloned());
                 self.functions.insert(hash, Box::new(func));
             }
@@ -0,0 +1,607 @@
+//! Module implementing a JSON reader producing `Dynamic` values, and a JSON writer for `Dynamic` values.
+
+use crate::any::{Dynamic, Union};
+use crate::engine::{Array, Map};
+use crate::error::{ParseError, ParseErrorType};
+use crate::fn_native::NativeCallContext;
+use crate::parser::INT;
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
+
+use crate::stdlib::{
+    boxed::Box,
+    char,
+    fmt::Write,
+    format,
+    iter::Peekable,
+    str::Chars,
+    string::{String, ToString},
+};
+
+/// Maximum nesting level of arrays and objects in a JSON text.
+pub const MAX_JSON_DEPTH: usize = 128;
+
+/// Maximum number of spaces to indent each level by when pretty-printing JSON text.
+pub const MAX_JSON_INDENT: usize = 16;
+
+/// A reader of JSON text following RFC 8259.
+struct JsonReader<'a> {
+    /// The input stream.
+    stream: Peekable<Chars<'a>>,
+    /// Position of the last character read.
+    pos: Position,
+    /// Map `null` to `()`? Otherwise `null` is an error.
+    has_null: bool,
+    /// Current nesting level of arrays and objects.
+    depth: usize,
+}
+
+impl<'a> JsonReader<'a> {
+    /// Make an error at the current position.
+    fn error<T>(&self, message: String) -> Result<T, Box<ParseError>> {
+        Err(ParseErrorType::BadInput(format!("Invalid JSON: {}", message)).into_err(self.pos))
+    }
+
+    /// Make an error for an unexpected character, or the end of input.
+    fn unexpected<T>(&self, ch: Option<char>) -> Result<T, Box<ParseError>> {
+        match ch {
+            Some(ch) => self.error(format!("unexpected character '{}'", ch.escape_debug())),
+            None => self.error("unexpected end of input".into()),
+        }
+    }
+
+    /// Read the next character.
+    fn next_char(&mut self) -> Option<char> {
+        let ch = self.stream.next()?;
+
+        if ch == '\n' {
+            self.pos.new_line();
+        } else {
+            self.pos.advance();
+        }
+
+        Some(ch)
+    }
+
+    /// Skip whitespace and peek at the next character.
+    fn peek_token(&mut self) -> Option<char> {
+        while let Some(' ') | Some('\t') | Some('\n') | Some('\r') = self.stream.peek() {
+            self.next_char();
+        }
+        self.stream.peek().cloned()
+    }
+
+    /// Read the next character, which must be `expected`.
+    fn expect(&mut self, expected: char) -> Result<(), Box<ParseError>> {
+        match self.next_char() {
+            Some(ch) if ch == expected => Ok(()),
+            ch => self.unexpected(ch),
+        }
+    }
+
+    /// Read a complete JSON text, which must not be followed by anything other than whitespace.
+    fn read_text(&mut self) -> Result<Dynamic, Box<ParseError>> {
+        let value = self.read_value()?;
+
+        match self.peek_token() {
+            None => Ok(value),
+            ch => {
+                self.next_char();
+                self.unexpected(ch)
+            }
+        }
+    }
+
+    /// Read a JSON value.
+    fn read_value(&mut self) -> Result<Dynamic, Box<ParseError>> {
+        match self.peek_token() {
+            Some('{') => self.read_object(),
+            Some('[') => self.read_array(),
+            Some('"') => Ok(self.read_string()?.into()),
+            Some('-') | Some('0'..='9') => self.read_number(),
+            Some('t') => self.read_literal("true", true.into()),
+            Some('f') => self.read_literal("false", false.into()),
+            Some('n') if self.has_null => self.read_literal("null", ().into()),
+            Some('n') => {
+                self.read_literal("null", ().into())?;
+                self.error("null values are not allowed".into())
+            }
+            ch => {
+                self.next_char();
+                self.unexpected(ch)
+            }
+        }
+    }
+
+    /// Read a literal keyword.
+    fn read_literal(&mut self, keyword: &str, value: Dynamic) -> Result<Dynamic, Box<ParseError>> {
+        for expected in keyword.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    /// Enter a nested array or object.
+    fn enter(&mut self) -> Result<(), Box<ParseError>> {
+        self.depth += 1;
+
+        if self.depth > MAX_JSON_DEPTH {
+            self.error(format!("nesting deeper than {} levels", MAX_JSON_DEPTH))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Read a JSON array.
+    fn read_array(&mut self) -> Result<Dynamic, Box<ParseError>> {
+        self.expect('[')?;
+        self.enter()?;
+
+        let mut array = Array::new();
+
+        if self.peek_token() == Some(']') {
+            self.next_char();
+        } else {
+            loop {
+                array.push(self.read_value()?);
+
+                self.peek_token();
+
+                match self.next_char() {
+                    Some(',') => (),
+                    Some(']') => break,
+                    ch => return self.unexpected(ch),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        Ok(Dynamic(Union::Array(Box::new(array))))
+    }
+
+    /// Read a JSON object.
+    fn read_object(&mut self) -> Result<Dynamic, Box<ParseError>> {
+        self.expect('{')?;
+        self.enter()?;
+
+        let mut map = Map::new();
+
+        if self.peek_token() == Some('}') {
+            self.next_char();
+        } else {
+            loop {
+                match self.peek_token() {
+                    Some('"') => (),
+                    ch => {
+                        self.next_char();
+                        return self.unexpected(ch);
+                    }
+                }
+
+                let key = self.read_string()?;
+
+                self.peek_token();
+                self.expect(':')?;
+
+                // Duplicated keys - the last one wins
+                map.insert(key, self.read_value()?);
+
+                self.peek_token();
+
+                match self.next_char() {
+                    Some(',') => (),
+                    Some('}') => break,
+                    ch => return self.unexpected(ch),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        Ok(Dynamic(Union::Map(Box::new(map))))
+    }
+
+    /// Read a JSON string.
+    fn read_string(&mut self) -> Result<String, Box<ParseError>> {
+        self.expect('"')?;
+
+        let mut result = String::new();
+
+        loop {
+            match self.next_char() {
+                Some('"') => return Ok(result),
+                Some('\\') => {
+                    let ch = match self.next_char() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\x08',
+                        Some('f') => '\x0c',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.read_unicode_escape()?,
+                        ch => return self.unexpected(ch),
+                    };
+                    result.push(ch);
+                }
+                Some(ch) if ch < ' ' => {
+                    return self.error(format!(
+                        "control character '{}' must be escaped",
+                        ch.escape_debug()
+                    ))
+                }
+                Some(ch) => result.push(ch),
+                None => return self.error("unterminated string".into()),
+            }
+        }
+    }
+
+    /// Read four hex digits following `\u`.
+    fn read_hex4(&mut self) -> Result<u32, Box<ParseError>> {
+        let mut value = 0;
+
+        for _ in 0..4 {
+            match self.next_char() {
+                Some(ch) if ch.is_ascii_hexdigit() => value = value * 16 + ch.to_digit(16).unwrap(),
+                ch => return self.unexpected(ch),
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Read a `\uXXXX` escape sequence (after the `\u`), combining surrogate pairs.
+    fn read_unicode_escape(&mut self) -> Result<char, Box<ParseError>> {
+        let high = self.read_hex4()?;
+
+        let code = match high {
+            0xD800..=0xDBFF => {
+                // High surrogate - must be followed by a low surrogate
+                if self.next_char() != Some('\\') || self.next_char() != Some('u') {
+                    return self.error("invalid surrogate pair in string".into());
+                }
+
+                match self.read_hex4()? {
+                    low @ 0xDC00..=0xDFFF => 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00),
+                    _ => return self.error("invalid surrogate pair in string".into()),
+                }
+            }
+            0xDC00..=0xDFFF => return self.error("invalid surrogate pair in string".into()),
+            code => code,
+        };
+
+        Ok(char::from_u32(code).unwrap())
+    }
+
+    /// Read a JSON number.
+    ///
+    /// Integers that fit into `INT` are returned as `INT`, all other numbers as `FLOAT`.
+    fn read_number(&mut self) -> Result<Dynamic, Box<ParseError>> {
+        let mut text = String::new();
+        let mut is_float = false;
+
+        if self.stream.peek() == Some(&'-') {
+            text.push(self.next_char().unwrap());
+        }
+
+        // Integral part - no leading zeros
+        match self.next_char() {
+            Some('0') => text.push('0'),
+            Some(ch @ '1'..='9') => {
+                text.push(ch);
+                self.read_digits(&mut text);
+            }
+            ch => return self.unexpected(ch),
+        }
+
+        // Fraction
+        if self.stream.peek() == Some(&'.') {
+            is_float = true;
+            text.push(self.next_char().unwrap());
+
+            if self.read_digits(&mut text) == 0 {
+                let ch = self.next_char();
+                return self.unexpected(ch);
+            }
+        }
+
+        // Exponent
+        if let Some('e') | Some('E') = self.stream.peek() {
+            is_float = true;
+            text.push(self.next_char().unwrap());
+
+            if let Some('+') | Some('-') = self.stream.peek() {
+                text.push(self.next_char().unwrap());
+            }
+
+            if self.read_digits(&mut text) == 0 {
+                let ch = self.next_char();
+                return self.unexpected(ch);
+            }
+        }
+
+        if !is_float {
+            if let Ok(n) = text.parse::<INT>() {
+                return Ok(n.into());
+            }
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        {
+            if let Ok(n) = text.parse::<FLOAT>() {
+                if !n.is_finite() {
+                    return self.error(format!("number '{}' is out of range", text));
+                }
+                return Ok(n.into());
+            }
+        }
+
+        self.error(format!("number '{}' is not supported", text))
+    }
+
+    /// Read a run of decimal digits, returning the number of digits read.
+    fn read_digits(&mut self, text: &mut String) -> usize {
+        let mut count = 0;
+
+        while let Some('0'..='9') = self.stream.peek() {
+            text.push(self.next_char().unwrap());
+            count += 1;
+        }
+
+        count
+    }
+}
+
+/// Parse a JSON text into a `Dynamic`.
+///
+/// Objects become object maps, arrays become arrays, and `null` becomes `()` if `has_null` is `true`
+/// (otherwise it is an error).
+pub fn parse_json(json: &str, has_null: bool) -> Result<Dynamic, Box<ParseError>> {
+    JsonReader {
+        stream: json.chars().peekable(),
+        pos: Position::new(1, 0),
+        has_null,
+        depth: 0,
+    }
+    .read_text()
+}
+
+/// A writer of `Dynamic` values into JSON text.
+struct JsonWriter<'a, F: Fn(&'static str) -> &'a str> {
+    /// The output JSON text.
+    output: String,
+    /// Number of spaces to indent each level by, or `None` for compact output.
+    indent: Option<usize>,
+    /// Map a type name into a pretty-print name for error messages.
+    map_type_name: F,
+    /// Position to report errors at.
+    pos: Position,
+}
+
+impl<'a, F: Fn(&'static str) -> &'a str> JsonWriter<'a, F> {
+    /// Create a new writer, limiting the indentation to `MAX_JSON_INDENT` spaces.
+    fn new(indent: Option<usize>, map_type_name: F, pos: Position) -> Self {
+        Self {
+            output: String::new(),
+            indent: indent.map(|n| n.min(MAX_JSON_INDENT)),
+            map_type_name,
+            pos,
+        }
+    }
+
+    /// Enter a nested array or object at the specified nesting level.
+    fn enter(&self, level: usize) -> Result<(), Box<EvalAltResult>> {
+        if level >= MAX_JSON_DEPTH {
+            Err(Box::new(EvalAltResult::ErrorRuntime(
+                format!(
+                    "Cannot serialize to JSON: nesting deeper than {} levels",
+                    MAX_JSON_DEPTH
+                ),
+                self.pos,
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Start a new line at the specified nesting level, if pretty-printing.
+    fn new_line(&mut self, level: usize) {
+        if let Some(indent) = self.indent {
+            self.output.push('\n');
+            (0..level * indent).for_each(|_| self.output.push(' '));
+        }
+    }
+
+    /// Write a value at the specified nesting level.
+    fn write_value(&mut self, value: &Dynamic, level: usize) -> Result<(), Box<EvalAltResult>> {
+        match &value.0 {
+            Union::Unit(_) => self.output.push_str("null"),
+            Union::Bool(b) => self.output.push_str(if *b { "true" } else { "false" }),
+            Union::Str(s) => self.write_string(s),
+            Union::Char(ch) => self.write_string(&ch.to_string()),
+            Union::Int(n) => write!(self.output, "{}", n).unwrap(),
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(f) => self.write_float(*f, value)?,
+
+            Union::Array(array) => self.write_array(array, level)?,
+            Union::Map(map) => self.write_map(map, level)?,
+            Union::Variant(_) => self.write_number(value)?,
+        }
+
+        Ok(())
+    }
+
+    /// Write an array at the specified nesting level.
+    fn write_array(&mut self, array: &Array, level: usize) -> Result<(), Box<EvalAltResult>> {
+        self.enter(level)?;
+
+        if array.is_empty() {
+            self.output.push_str("[]");
+            return Ok(());
+        }
+
+        self.output.push('[');
+        for (i, item) in array.iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.new_line(level + 1);
+            self.write_value(item, level + 1)?;
+        }
+        self.new_line(level);
+        self.output.push(']');
+
+        Ok(())
+    }
+
+    /// Write an object map at the specified nesting level.
+    fn write_map(&mut self, map: &Map, level: usize) -> Result<(), Box<EvalAltResult>> {
+        self.enter(level)?;
+
+        if map.is_empty() {
+            self.output.push_str("{}");
+            return Ok(());
+        }
+
+        self.output.push('{');
+        for (i, (key, item)) in map.iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.new_line(level + 1);
+            self.write_string(key);
+            self.output
+                .push_str(if self.indent.is_some() { ": " } else { ":" });
+            self.write_value(item, level + 1)?;
+        }
+        self.new_line(level);
+        self.output.push('}');
+
+        Ok(())
+    }
+
+    /// Write a finite floating-point number, keeping the decimal point so that it reads back as a float.
+    #[cfg(not(feature = "no_float"))]
+    fn write_float(&mut self, f: FLOAT, value: &Dynamic) -> Result<(), Box<EvalAltResult>> {
+        if !f.is_finite() {
+            return Err(self.type_error(value));
+        }
+        write!(self.output, "{:?}", f).unwrap();
+        Ok(())
+    }
+
+    /// Write a number held in a type other than `INT` or `FLOAT`.
+    fn write_number(&mut self, value: &Dynamic) -> Result<(), Box<EvalAltResult>> {
+        macro_rules! write_int {
+            ($($t:ty),*) => {
+                $(if let Some(n) = value.downcast_ref::<$t>() {
+                    write!(self.output, "{}", n).unwrap();
+                    return Ok(());
+                })*
+            };
+        }
+        macro_rules! write_float {
+            ($($t:ty),*) => {
+                $(if let Some(&f) = value.downcast_ref::<$t>() {
+                    if !f.is_finite() {
+                        return Err(self.type_error(value));
+                    }
+                    write!(self.output, "{:?}", f).unwrap();
+                    return Ok(());
+                })*
+            };
+        }
+
+        write_int!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        write_float!(f32, f64);
+
+        Err(self.type_error(value))
+    }
+
+    /// Write a string with JSON escaping.
+    fn write_string(&mut self, s: &str) {
+        self.output.push('"');
+
+        for ch in s.chars() {
+            match ch {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                '\x08' => self.output.push_str("\\b"),
+                '\x0c' => self.output.push_str("\\f"),
+                ch if ch < ' ' => write!(self.output, "\\u{:04x}", ch as u32).unwrap(),
+                ch => self.output.push(ch),
+            }
+        }
+
+        self.output.push('"');
+    }
+
+    /// Make an error for a value that cannot be represented in JSON.
+    fn type_error(&self, value: &Dynamic) -> Box<EvalAltResult> {
+        Box::new(EvalAltResult::ErrorMismatchDataType(
+            "JSON value".into(),
+            (self.map_type_name)(value.type_name()).into(),
+            self.pos,
+        ))
+    }
+}
+
+/// Serialize a `Dynamic` into a JSON text.
+///
+/// `()` becomes `null` and characters become strings. Custom types and non-finite
+/// floating-point numbers cannot be represented in JSON and cause an error.
+///
+/// If `indent` is `Some`, the output is pretty-printed with each level indented by
+/// that number of spaces (at most `MAX_JSON_INDENT`).
+pub fn to_json<'a>(
+    value: &Dynamic,
+    indent: Option<usize>,
+    map_type_name: impl Fn(&'static str) -> &'a str,
+) -> Result<String, Box<EvalAltResult>> {
+    let mut writer = JsonWriter::new(indent, map_type_name, Position::none());
+    writer.write_value(value, 0)?;
+    Ok(writer.output)
+}
+
+/// Convert a script-supplied indentation into a number of spaces, clamped to `0..=MAX_JSON_INDENT`.
+fn script_indent(indent: Option<INT>) -> Option<usize> {
+    indent.map(|n| n.max(0).min(MAX_JSON_INDENT as INT) as usize)
+}
+
+/// Serialize an array into a JSON text, for a script.
+pub fn array_to_json(
+    context: &NativeCallContext,
+    array: &Array,
+    indent: Option<INT>,
+) -> Result<String, Box<EvalAltResult>> {
+    let engine = context.engine();
+    let mut writer = JsonWriter::new(
+        script_indent(indent),
+        |name| engine.map_type_name(name),
+        context.position(),
+    );
+    writer.write_array(array, 0)?;
+    Ok(writer.output)
+}
+
+/// Serialize an object map into a JSON text, for a script.
+pub fn map_to_json(
+    context: &NativeCallContext,
+    map: &Map,
+    indent: Option<INT>,
+) -> Result<String, Box<EvalAltResult>> {
+    let engine = context.engine();
+    let mut writer = JsonWriter::new(
+        script_indent(indent),
+        |name| engine.map_type_name(name),
+        context.position(),
+    );
+    writer.write_map(map, 0)?;
+    Ok(writer.output)
+}
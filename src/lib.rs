@@ -61,6 +61,7 @@
 //! | `only_i64`    | Set the system integer type to `i64` and disable all other integer types. `INT` is set to `i64`.                                                         |
 //! | `no_std`      | Build for `no-std`. Notice that additional dependencies will be pulled in to replace `std` features.                                                     |
 //! | `sync`        | Restrict all values types to those that are `Send + Sync`. Under this feature, `Engine`, `Scope` and `AST` are all `Send + Sync`.                        |
+//! | `serde`       | Enable conversion between Rust types and `Dynamic` via `serde`. Pulls in the `serde` dependency.                                                         |
 //!
 //! [Check out the README on GitHub for details on the Rhai language!](https://github.com/jonathandturner/rhai)
 
@@ -71,27 +72,38 @@ extern crate alloc;
 
 mod any;
 mod api;
+mod datetime;
 mod engine;
 mod error;
 mod fn_call;
 mod fn_func;
+mod fn_native;
 mod fn_register;
+mod json;
+mod map;
 mod optimize;
 pub mod packages;
 mod parser;
 mod result;
 mod scope;
+#[cfg(feature = "serde")]
+mod serde;
 mod stdlib;
+mod syntax;
 mod token;
 
 pub use any::Dynamic;
+pub use datetime::{DateTime, Duration};
 pub use engine::{calc_fn_spec as calc_fn_hash, Engine};
 pub use error::{ParseError, ParseErrorType};
 pub use fn_call::FuncArgs;
-pub use fn_register::{RegisterDynamicFn, RegisterFn, RegisterResultFn};
+pub use fn_native::{FnPtr, NativeCallContext};
+pub use fn_register::{RegisterContextFn, RegisterDynamicFn, RegisterFn, RegisterResultFn};
+pub use json::{MAX_JSON_DEPTH, MAX_JSON_INDENT};
 pub use parser::{AST, INT};
 pub use result::EvalAltResult;
 pub use scope::Scope;
+pub use syntax::{EvalContext, Expression};
 pub use token::Position;
 
 #[cfg(not(feature = "no_function"))]
@@ -108,3 +120,6 @@ pub use parser::FLOAT;
 
 #[cfg(not(feature = "no_optimize"))]
 pub use optimize::OptimizationLevel;
+
+#[cfg(feature = "serde")]
+pub use serde::{de::from_dynamic, ser::to_dynamic};
@@ -0,0 +1,240 @@
+//! Module defining the object map type, which preserves the insertion order of its properties.
+
+use crate::any::Dynamic;
+
+use crate::stdlib::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    iter::FromIterator,
+    mem,
+    ops::{Index, IndexMut},
+    slice,
+    string::String,
+    vec,
+    vec::Vec,
+};
+
+/// A dynamic hash map of `Dynamic` values with `String` keys.
+///
+/// Properties are kept in the order in which they are first inserted, so iteration, printing and
+/// serialization of an object map are deterministic.  Replacing the value of an existing property
+/// keeps its position, while removing a property moves all subsequent properties up by one.
+///
+/// Not available under the `no_object` feature.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(not(feature = "no_object"))]
+/// # {
+/// use rhai::{Dynamic, Map, INT};
+///
+/// let mut map = Map::new();
+/// map.insert("b".into(), Dynamic::from(1 as INT));
+/// map.insert("a".into(), Dynamic::from(2 as INT));
+/// map.insert("b".into(), Dynamic::from(3 as INT));
+///
+/// assert_eq!(map.keys().cloned().collect::<Vec<_>>(), vec!["b", "a"]);
+/// assert_eq!(map["b"].as_int().unwrap(), 3);
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Map {
+    /// Properties in order of insertion.
+    entries: Vec<(String, Dynamic)>,
+    /// Index into `entries` of each property.
+    indices: HashMap<String, usize>,
+}
+
+impl Map {
+    /// Create a new, empty object map.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new, empty object map with space for at least `capacity` properties.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            indices: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Number of properties in the object map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Is the object map empty?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all the properties.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices.clear();
+    }
+
+    /// Does the object map contain a property?
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        String: Borrow<Q>,
+    {
+        self.indices.contains_key(key)
+    }
+
+    /// Get a reference to the value of a property.
+    pub fn get<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> Option<&Dynamic>
+    where
+        String: Borrow<Q>,
+    {
+        self.indices.get(key).map(|&index| &self.entries[index].1)
+    }
+
+    /// Get a mutable reference to the value of a property.
+    pub fn get_mut<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut Dynamic>
+    where
+        String: Borrow<Q>,
+    {
+        match self.indices.get(key) {
+            Some(&index) => Some(&mut self.entries[index].1),
+            None => None,
+        }
+    }
+
+    /// Set the value of a property, returning the previous value if the property already exists.
+    ///
+    /// A new property is added at the end.  An existing property keeps its position.
+    pub fn insert(&mut self, key: String, value: Dynamic) -> Option<Dynamic> {
+        match self.indices.get(&key) {
+            Some(&index) => Some(mem::replace(&mut self.entries[index].1, value)),
+            None => {
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Get a mutable reference to the value of a property, first adding it with a default value
+    /// if it does not exist.
+    pub fn get_or_insert(&mut self, key: String, default: Dynamic) -> &mut Dynamic {
+        let index = match self.indices.get(&key) {
+            Some(&index) => index,
+            None => {
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, default));
+                self.entries.len() - 1
+            }
+        };
+
+        &mut self.entries[index].1
+    }
+
+    /// Remove a property, returning its value if it exists.
+    ///
+    /// All subsequent properties move up by one, so this takes linear time.
+    pub fn remove<Q: Hash + Eq + ?Sized>(&mut self, key: &Q) -> Option<Dynamic>
+    where
+        String: Borrow<Q>,
+    {
+        let index = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(index);
+
+        let indices = &mut self.indices;
+
+        self.entries[index..]
+            .iter()
+            .for_each(|(k, _)| *indices.get_mut::<String>(k).unwrap() -= 1);
+
+        Some(value)
+    }
+
+    /// Iterate through the properties in order of insertion.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&String, &Dynamic)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Iterate through the properties in order of insertion, with mutable references to the values.
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (&String, &mut Dynamic)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Iterate through the property names in order of insertion.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &String> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate through the property values in order of insertion.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &Dynamic> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// Iterate through mutable references to the property values in order of insertion.
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Dynamic> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<Q: Hash + Eq + ?Sized> Index<&Q> for Map
+where
+    String: Borrow<Q>,
+{
+    type Output = Dynamic;
+
+    fn index(&self, key: &Q) -> &Dynamic {
+        self.get(key).expect("property not found")
+    }
+}
+
+impl<Q: Hash + Eq + ?Sized> IndexMut<&Q> for Map
+where
+    String: Borrow<Q>,
+{
+    fn index_mut(&mut self, key: &Q) -> &mut Dynamic {
+        self.get_mut(key).expect("property not found")
+    }
+}
+
+impl FromIterator<(String, Dynamic)> for Map {
+    fn from_iter<T: IntoIterator<Item = (String, Dynamic)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(String, Dynamic)> for Map {
+    fn extend<T: IntoIterator<Item = (String, Dynamic)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Dynamic);
+    type IntoIter = vec::IntoIter<(String, Dynamic)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = &'a (String, Dynamic);
+    type IntoIter = slice::Iter<'a, (String, Dynamic)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
@@ -1,18 +1,15 @@
 use crate::any::Dynamic;
-use crate::calc_fn_hash;
 use crate::engine::{
-    Engine, FnAny, FnCallArgs, FunctionsLib, KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_PRINT,
-    KEYWORD_TYPE_OF,
+    Engine, FnCallArgs, FunctionsLib, KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_PRINT, KEYWORD_TYPE_OF,
 };
-use crate::packages::PackageLibrary;
-use crate::parser::{map_dynamic_to_expr, Expr, FnDef, ReturnType, Stmt, AST};
+use crate::fn_native::NativeCallContext;
+use crate::parser::{map_dynamic_to_expr, Expr, FnDef, Pattern, ReturnType, Stmt, AST};
 use crate::result::EvalAltResult;
 use crate::scope::{Entry as ScopeEntry, EntryType as ScopeEntryType, Scope};
 use crate::token::Position;
 
 use crate::stdlib::{
     boxed::Box,
-    collections::HashMap,
     rc::Rc,
     string::{String, ToString},
     sync::Arc,
@@ -54,18 +51,14 @@ struct State<'a> {
     /// An `Engine` instance for eager function evaluation.
     engine: &'a Engine,
     /// Library of script-defined functions.
-    fn_lib: &'a [(&'a str, usize)],
+    fn_lib: &'a [&'a FnDef],
     /// Optimization level.
     optimization_level: OptimizationLevel,
 }
 
 impl<'a> State<'a> {
     /// Create a new State.
-    pub fn new(
-        engine: &'a Engine,
-        fn_lib: &'a [(&'a str, usize)],
-        level: OptimizationLevel,
-    ) -> Self {
+    pub fn new(engine: &'a Engine, fn_lib: &'a [&'a FnDef], level: OptimizationLevel) -> Self {
         Self {
             changed: false,
             constants: vec![],
@@ -112,24 +105,24 @@ impl<'a> State<'a> {
 
 /// Call a registered function
 fn call_fn(
-    packages: &Vec<PackageLibrary>,
-    functions: &HashMap<u64, Box<FnAny>>,
+    engine: &Engine,
     fn_name: &str,
     args: &mut FnCallArgs,
     pos: Position,
 ) -> Result<Option<Dynamic>, Box<EvalAltResult>> {
+    // Script-defined functions are not available during optimization
+    let fn_lib = FunctionsLib::new();
+    let context = NativeCallContext {
+        engine,
+        fn_lib: &fn_lib,
+        pos,
+        level: 0,
+    };
+
     // Search built-in's and external functions
-    let hash = calc_fn_hash(fn_name, args.iter().map(|a| a.type_id()));
-
-    functions
-        .get(&hash)
-        .or_else(|| {
-            packages
-                .iter()
-                .find(|p| p.functions.contains_key(&hash))
-                .and_then(|p| p.functions.get(&hash))
-        })
-        .map(|func| func(args, pos))
+    engine
+        .get_native_fn(fn_name, args)
+        .map(|func| func(&context, args))
         .transpose()
 }
 
@@ -184,18 +177,18 @@ fn optimize_stmt<'a>(stmt: Stmt, state: &mut State<'a>, preserve_result: bool) -
             ),
         },
         // while expr { block }
-        Stmt::While(expr, block) => match *expr {
+        Stmt::While(expr, block, label) => match *expr {
             // while false { block } -> Noop
             Expr::False(pos) => {
                 state.set_dirty();
                 Stmt::Noop(pos)
             }
             // while true { block } -> loop { block }
-            Expr::True(_) => Stmt::Loop(Box::new(optimize_stmt(*block, state, false))),
+            Expr::True(_) => Stmt::Loop(Box::new(optimize_stmt(*block, state, false)), label),
             // while expr { block }
             expr => match optimize_stmt(*block, state, false) {
                 // while expr { break; } -> { expr; }
-                Stmt::Break(pos) => {
+                Stmt::Break(None, None, pos) => {
                     // Only a single break statement - turn into running the guard expression once
                     state.set_dirty();
                     let mut statements = vec![Stmt::Expr(Box::new(optimize_expr(expr, state)))];
@@ -205,31 +198,39 @@ fn optimize_stmt<'a>(stmt: Stmt, state: &mut State<'a>, preserve_result: bool) -
                     Stmt::Block(statements, pos)
                 }
                 // while expr { block }
-                stmt => Stmt::While(Box::new(optimize_expr(expr, state)), Box::new(stmt)),
+                stmt => Stmt::While(Box::new(optimize_expr(expr, state)), Box::new(stmt), label),
             },
         },
+        // do { block } while/until expr
+        Stmt::Do(block, expr, is_until, label) => Stmt::Do(
+            Box::new(optimize_stmt(*block, state, false)),
+            Box::new(optimize_expr(*expr, state)),
+            is_until,
+            label,
+        ),
         // loop { block }
-        Stmt::Loop(block) => match optimize_stmt(*block, state, false) {
+        Stmt::Loop(block, label) => match optimize_stmt(*block, state, false) {
             // loop { break; } -> Noop
-            Stmt::Break(pos) => {
+            Stmt::Break(None, None, pos) => {
                 // Only a single break statement
                 state.set_dirty();
                 Stmt::Noop(pos)
             }
             // loop { block }
-            stmt => Stmt::Loop(Box::new(stmt)),
+            stmt => Stmt::Loop(Box::new(stmt), label),
         },
-        // for id in expr { block }
-        Stmt::For(id, expr, block) => Stmt::For(
-            id,
+        // for pattern in expr { block }
+        Stmt::For(pattern, expr, block, label) => Stmt::For(
+            pattern,
             Box::new(optimize_expr(*expr, state)),
             Box::new(optimize_stmt(*block, state, false)),
+            label,
         ),
-        // let id = expr;
-        Stmt::Let(id, Some(expr), pos) => {
-            Stmt::Let(id, Some(Box::new(optimize_expr(*expr, state))), pos)
+        // let pattern = expr;
+        Stmt::Let(pattern, Some(expr), pos) => {
+            Stmt::Let(pattern, Some(Box::new(optimize_expr(*expr, state))), pos)
         }
-        // let id;
+        // let pattern;
         Stmt::Let(_, None, _) => stmt,
         // { block }
         Stmt::Block(block, pos) => {
@@ -268,7 +269,10 @@ fn optimize_stmt<'a>(stmt: Stmt, state: &mut State<'a>, preserve_result: bool) -
             while let Some(expr) = result.pop() {
                 match expr {
                     Stmt::Let(_, None, _) => removed = true,
-                    Stmt::Let(_, Some(val_expr), _) if val_expr.is_pure() => removed = true,
+                    // Destructuring may fail, so only remove simple variables
+                    Stmt::Let(Pattern::Variable(_), Some(val_expr), _) if val_expr.is_pure() => {
+                        removed = true
+                    }
                     _ => {
                         result.push(expr);
                         break;
@@ -300,7 +304,7 @@ fn optimize_stmt<'a>(stmt: Stmt, state: &mut State<'a>, preserve_result: bool) -
                 }
 
                 match stmt {
-                    Stmt::ReturnWithVal(_, _, _) | Stmt::Break(_) => {
+                    Stmt::ReturnWithVal(_, _, _) | Stmt::Break(_, _, _) | Stmt::Continue(_, _) => {
                         dead_code = true;
                     }
                     _ => (),
@@ -337,6 +341,10 @@ fn optimize_stmt<'a>(stmt: Stmt, state: &mut State<'a>, preserve_result: bool) -
         Stmt::ReturnWithVal(Some(expr), is_return, pos) => {
             Stmt::ReturnWithVal(Some(Box::new(optimize_expr(*expr, state))), is_return, pos)
         }
+        // break 'label expr;
+        Stmt::Break(label, Some(expr), pos) => {
+            Stmt::Break(label, Some(Box::new(optimize_expr(*expr, state))), pos)
+        }
         // All other statements - skip
         stmt => stmt,
     }
@@ -395,7 +403,7 @@ fn optimize_expr<'a>(expr: Expr, state: &mut State<'a>) -> Expr {
 
         // lhs.rhs
         #[cfg(not(feature = "no_object"))]
-        Expr::Dot(lhs, rhs, pos) => match (*lhs, *rhs) {
+        Expr::Dot(lhs, rhs, is_safe, pos) => match (*lhs, *rhs) {
             // map.string
             (Expr::Map(items, pos), Expr::Property(s, _)) if items.iter().all(|(_, x, _)| x.is_pure()) => {
                 // Map literal where everything is pure - promote the indexed item.
@@ -409,13 +417,14 @@ fn optimize_expr<'a>(expr: Expr, state: &mut State<'a>) -> Expr {
             (lhs, rhs) => Expr::Dot(
                 Box::new(optimize_expr(lhs, state)),
                 Box::new(optimize_expr(rhs, state)),
+                is_safe,
                 pos,
             )
         }
 
         // lhs[rhs]
         #[cfg(not(feature = "no_index"))]
-        Expr::Index(lhs, rhs, pos) => match (*lhs, *rhs) {
+        Expr::Index(lhs, rhs, is_safe, pos) => match (*lhs, *rhs) {
             // array[int]
             (Expr::Array(mut items, pos), Expr::IntegerConstant(i, _))
                 if i >= 0 && (i as usize) < items.len() && items.iter().all(Expr::is_pure) =>
@@ -446,6 +455,7 @@ fn optimize_expr<'a>(expr: Expr, state: &mut State<'a>) -> Expr {
             (lhs, rhs) => Expr::Index(
                 Box::new(optimize_expr(lhs, state)),
                 Box::new(optimize_expr(rhs, state)),
+                is_safe,
                 pos,
             ),
         },
@@ -556,6 +566,35 @@ fn optimize_expr<'a>(expr: Expr, state: &mut State<'a>) -> Expr {
                 pos
             ),
         },
+        // lhs ?? rhs
+        Expr::Coalesce(lhs, rhs, pos) => match (*lhs, *rhs) {
+            // () ?? rhs -> rhs
+            (Expr::Unit(_), rhs) => {
+                state.set_dirty();
+                rhs
+            }
+            // constant ?? rhs -> constant
+            (lhs, _) if lhs.is_constant() => {
+                state.set_dirty();
+                lhs
+            }
+            // lhs ?? rhs
+            (lhs, rhs) => Expr::Coalesce(
+                Box::new(optimize_expr(lhs, state)),
+                Box::new(optimize_expr(rhs, state)),
+                pos
+            ),
+        },
+
+        // keyword input ... - variables are left alone because they may be identifiers
+        Expr::Custom(key, inputs, pos) => Expr::Custom(
+            key,
+            inputs.into_iter().map(|input| match input {
+                Expr::Variable(_, _, _) => input,
+                input => optimize_expr(input, state),
+            }).collect(),
+            pos,
+        ),
 
         // Do not call some special keywords
         Expr::FunctionCall(id, args, def_value, pos) if DONT_EVAL_KEYWORDS.contains(&id.as_ref())=>
@@ -567,7 +606,7 @@ fn optimize_expr<'a>(expr: Expr, state: &mut State<'a>) -> Expr {
                 && args.iter().all(|expr| expr.is_constant()) // all arguments are constants
         => {
             // First search in script-defined functions (can override built-in)
-            if state.fn_lib.iter().find(|(name, len)| name == &id && *len == args.len()).is_some() {
+            if state.fn_lib.iter().any(|f| f.name == id && f.accepts_args(args.len())) {
                 // A script-defined function overrides the built-in function - do not make the call
                 return Expr::FunctionCall(id, Box::new(args.into_iter().map(|a| optimize_expr(a, state)).collect()), def_value, pos);
             }
@@ -583,7 +622,7 @@ fn optimize_expr<'a>(expr: Expr, state: &mut State<'a>) -> Expr {
                 ""
             };
 
-            call_fn(&state.engine.packages, &state.engine.functions, &id, &mut call_args, pos).ok()
+            call_fn(state.engine, &id, &mut call_args, pos).ok()
                 .and_then(|result|
                     result.or_else(|| {
                         if !arg_for_type_of.is_empty() {
@@ -625,7 +664,7 @@ fn optimize<'a>(
     statements: Vec<Stmt>,
     engine: &Engine,
     scope: &Scope,
-    fn_lib: &'a [(&'a str, usize)],
+    fn_lib: &'a [&'a FnDef],
     level: OptimizationLevel,
 ) -> Vec<Stmt> {
     // If optimization level is None then skip optimizing
@@ -714,10 +753,7 @@ pub fn optimize_into_ast(
     #[cfg(feature = "no_optimize")]
     const level: OptimizationLevel = OptimizationLevel::None;
 
-    let fn_lib: Vec<_> = functions
-        .iter()
-        .map(|fn_def| (fn_def.name.as_str(), fn_def.params.len()))
-        .collect();
+    let fn_lib: Vec<_> = functions.iter().collect();
 
     let lib = FunctionsLib::from_vec(
         functions
@@ -190,7 +190,7 @@ fn modulo_u<T: Rem>(x: T, y: T) -> <T as Rem>::Output {
     x % y
 }
 // Checked power
-fn pow_i_i(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
+pub(super) fn pow_i_i(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
     #[cfg(not(feature = "only_i32"))]
     {
         if y > (u32::MAX as INT) {
@@ -231,17 +231,17 @@ fn pow_i_i(x: INT, y: INT) -> Result<INT, Box<EvalAltResult>> {
     }
 }
 // Unchecked integer power - may panic on overflow or if the power index is too high (> u32::MAX)
-fn pow_i_i_u(x: INT, y: INT) -> INT {
+pub(super) fn pow_i_i_u(x: INT, y: INT) -> INT {
     x.pow(y as u32)
 }
 // Floating-point power - always well-defined
 #[cfg(not(feature = "no_float"))]
-fn pow_f_f(x: FLOAT, y: FLOAT) -> FLOAT {
+pub(super) fn pow_f_f(x: FLOAT, y: FLOAT) -> FLOAT {
     x.powf(y)
 }
 // Checked power
 #[cfg(not(feature = "no_float"))]
-fn pow_f_i(x: FLOAT, y: INT) -> Result<FLOAT, Box<EvalAltResult>> {
+pub(super) fn pow_f_i(x: FLOAT, y: INT) -> Result<FLOAT, Box<EvalAltResult>> {
     // Raise to power that is larger than an i32
     if y > (i32::MAX as INT) {
         return Err(Box::new(EvalAltResult::ErrorArithmetic(
@@ -255,7 +255,7 @@ fn pow_f_i(x: FLOAT, y: INT) -> Result<FLOAT, Box<EvalAltResult>> {
 // Unchecked power - may be incorrect if the power index is too high (> i32::MAX)
 #[cfg(feature = "unchecked")]
 #[cfg(not(feature = "no_float"))]
-fn pow_f_i_u(x: FLOAT, y: INT) -> FLOAT {
+pub(super) fn pow_f_i_u(x: FLOAT, y: INT) -> FLOAT {
     x.powi(y as i32)
 }
 
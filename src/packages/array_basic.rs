@@ -1,12 +1,29 @@
-use super::{reg_binary, reg_binary_mut, reg_trinary_mut, reg_unary_mut};
+use super::{
+    reg_binary, reg_binary_mut, reg_raw, reg_trinary_mut, reg_unary_mut, PackageStore,
+    MAX_ARRAY_SIZE,
+};
 
-use crate::any::{Dynamic, Variant};
+use crate::any::{Dynamic, Union, Variant};
 use crate::def_package;
 use crate::engine::Array;
-use crate::fn_register::{map_dynamic as map, map_identity as pass};
+use crate::fn_native::{FnPtr, NativeCallContext};
+use crate::fn_register::{map_dynamic as map, map_identity as pass, map_result as result};
+use crate::json::array_to_json;
 use crate::parser::INT;
+use crate::result::EvalAltResult;
+use crate::token::Position;
 
-use crate::stdlib::{any::TypeId, boxed::Box, string::String};
+use crate::stdlib::{
+    any::TypeId,
+    boxed::Box,
+    cmp::{min, Ordering},
+    format,
+    string::String,
+    vec,
+    vec::Vec,
+};
+
+type FuncReturn<T> = Result<T, Box<EvalAltResult>>;
 
 // Register array utility functions
 fn push<T: Variant + Clone>(list: &mut Array, item: T) {
@@ -21,12 +38,353 @@ fn ins<T: Variant + Clone>(list: &mut Array, position: INT, item: T) {
         list.insert(position as usize, Dynamic::from(item));
     }
 }
-fn pad<T: Variant + Clone>(list: &mut Array, len: INT, item: T) {
+fn pad<T: Variant + Clone>(list: &mut Array, len: INT, item: T) -> FuncReturn<()> {
+    if len > MAX_ARRAY_SIZE as INT {
+        return Err(Box::new(EvalAltResult::ErrorArithmetic(
+            format!(
+                "Array exceeds the maximum size of {} elements",
+                MAX_ARRAY_SIZE
+            ),
+            Position::none(),
+        )));
+    }
+
     if len >= 0 {
         while list.len() < len as usize {
             push(list, item.clone());
         }
     }
+
+    Ok(())
+}
+
+/// Calculate the range of elements starting from `start` with `len` elements, clamped to the array.
+/// A negative `start` counts from the end of the array.
+fn calc_range(list: &Array, start: INT, len: INT) -> (usize, usize) {
+    let start = if start < 0 {
+        list.len().saturating_sub(start.unsigned_abs() as usize)
+    } else {
+        min(start as usize, list.len())
+    };
+    let len = if len <= 0 {
+        0
+    } else {
+        min(len as usize, list.len() - start)
+    };
+
+    (start, start + len)
+}
+fn splice(list: &mut Array, start: INT, len: INT, replace: Array) {
+    let (start, end) = calc_range(list, start, len);
+    list.splice(start..end, replace);
+}
+fn extract(list: &mut Array, start: INT, len: INT) -> Array {
+    let (start, end) = calc_range(list, start, len);
+    list[start..end].to_vec()
+}
+fn drain_range(list: &mut Array, start: INT, len: INT) -> Array {
+    let (start, end) = calc_range(list, start, len);
+    list.drain(start..end).collect()
+}
+fn retain_range(list: &mut Array, start: INT, len: INT) -> Array {
+    let (start, end) = calc_range(list, start, len);
+    let mut removed = list.split_off(end);
+    removed.splice(0..0, list.drain(..start));
+    removed
+}
+
+/// Call a callback with `args`, the last of which is the current element.  If the callback is a
+/// script-defined function taking one more parameter, the index of the element is passed as well.
+fn call_with_index(
+    context: &NativeCallContext,
+    callback: &FnPtr,
+    mut args: Vec<Dynamic>,
+    index: usize,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let fn_name = callback.fn_name();
+    let num_args = callback.curry().len() + args.len();
+
+    if !context.fn_lib().has_function(fn_name, num_args)
+        && context.fn_lib().has_function(fn_name, num_args + 1)
+    {
+        args.push((index as INT).into());
+    }
+
+    callback.call_dynamic(context, args)
+}
+/// Make sure that the result of a callback is a boolean.
+pub(super) fn to_bool(
+    context: &NativeCallContext,
+    value: Dynamic,
+) -> Result<bool, Box<EvalAltResult>> {
+    value.as_bool().map_err(|type_name| {
+        Box::new(EvalAltResult::ErrorMismatchDataType(
+            "bool".into(),
+            context.engine().map_type_name(type_name).into(),
+            context.position(),
+        ))
+    })
+}
+/// Call a predicate on each element, returning whether it is matched.
+fn matches(
+    context: &NativeCallContext,
+    list: &Array,
+    filter: &FnPtr,
+) -> Result<Vec<bool>, Box<EvalAltResult>> {
+    list.iter()
+        .enumerate()
+        .map(|(i, item)| {
+            to_bool(
+                context,
+                call_with_index(context, filter, vec![item.clone()], i)?,
+            )
+        })
+        .collect()
+}
+/// Compare two values of the same built-in type in their natural order.
+fn natural_order(x: &Dynamic, y: &Dynamic) -> Option<Ordering> {
+    match (&x.0, &y.0) {
+        (Union::Unit(_), Union::Unit(_)) => Some(Ordering::Equal),
+        (Union::Bool(x), Union::Bool(y)) => Some(x.cmp(y)),
+        (Union::Str(x), Union::Str(y)) => Some(x.cmp(y)),
+        (Union::Char(x), Union::Char(y)) => Some(x.cmp(y)),
+        (Union::Int(x), Union::Int(y)) => Some(x.cmp(y)),
+        #[cfg(not(feature = "no_float"))]
+        (Union::Float(x), Union::Float(y)) => x.partial_cmp(y),
+        _ => None,
+    }
+}
+/// Stable merge sort with a comparison that can fail.
+///
+/// An inconsistent comparison only results in an unspecified order, never a panic.
+fn merge_sort(
+    list: &mut Array,
+    compare: &mut impl FnMut(&Dynamic, &Dynamic) -> Result<Ordering, Box<EvalAltResult>>,
+) -> Result<(), Box<EvalAltResult>> {
+    if list.len() <= 1 {
+        return Ok(());
+    }
+
+    let mut right = list.split_off(list.len() / 2);
+    merge_sort(list, compare)?;
+    merge_sort(&mut right, compare)?;
+
+    let mut merged = Vec::with_capacity(list.len() + right.len());
+    let mut left = list.drain(..).peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(x), Some(y)) = (left.peek(), right.peek()) {
+        if compare(y, x)? == Ordering::Less {
+            merged.push(right.next().unwrap());
+        } else {
+            merged.push(left.next().unwrap());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+
+    *list = merged;
+    Ok(())
+}
+
+fn map_array(context: &NativeCallContext, list: &mut Array, mapper: &FnPtr) -> FuncReturn<Dynamic> {
+    list.iter()
+        .enumerate()
+        .map(|(i, item)| call_with_index(context, mapper, vec![item.clone()], i))
+        .collect::<Result<Array, _>>()
+        .map(Dynamic::from)
+}
+fn filter(context: &NativeCallContext, list: &mut Array, filter: &FnPtr) -> FuncReturn<Dynamic> {
+    let matched = matches(context, list, filter)?;
+
+    Ok(list
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| *matched)
+        .map(|(item, _)| item.clone())
+        .collect::<Array>()
+        .into())
+}
+/// Reduce an array, starting from `initial` or, if there is none, from the first element to visit.
+fn reduce(
+    context: &NativeCallContext,
+    list: &Array,
+    reducer: &FnPtr,
+    initial: Option<Dynamic>,
+    rev: bool,
+) -> FuncReturn<Dynamic> {
+    let mut indices = (0..list.len()).map(|i| if rev { list.len() - 1 - i } else { i });
+
+    let initial = match initial {
+        Some(value) => value,
+        None => match indices.next() {
+            Some(i) => list[i].clone(),
+            None => return Ok(().into()),
+        },
+    };
+
+    indices.try_fold(initial, |acc, i| {
+        call_with_index(context, reducer, vec![acc, list[i].clone()], i)
+    })
+}
+fn some(context: &NativeCallContext, list: &mut Array, filter: &FnPtr) -> FuncReturn<Dynamic> {
+    for (i, item) in list.iter().enumerate() {
+        if to_bool(
+            context,
+            call_with_index(context, filter, vec![item.clone()], i)?,
+        )? {
+            return Ok(true.into());
+        }
+    }
+    Ok(false.into())
+}
+fn all(context: &NativeCallContext, list: &mut Array, filter: &FnPtr) -> FuncReturn<Dynamic> {
+    for (i, item) in list.iter().enumerate() {
+        if !to_bool(
+            context,
+            call_with_index(context, filter, vec![item.clone()], i)?,
+        )? {
+            return Ok(false.into());
+        }
+    }
+    Ok(true.into())
+}
+fn find_index(
+    context: &NativeCallContext,
+    list: &Array,
+    filter: &FnPtr,
+) -> FuncReturn<Option<usize>> {
+    for (i, item) in list.iter().enumerate() {
+        if to_bool(
+            context,
+            call_with_index(context, filter, vec![item.clone()], i)?,
+        )? {
+            return Ok(Some(i));
+        }
+    }
+    Ok(None)
+}
+fn sort_by(context: &NativeCallContext, list: &mut Array, comparer: &FnPtr) -> FuncReturn<Dynamic> {
+    let mut sorted = list.clone();
+
+    merge_sort(&mut sorted, &mut |x, y| {
+        let result = comparer.call_dynamic(context, vec![x.clone(), y.clone()])?;
+
+        result.as_int().map(|v| v.cmp(&0)).map_err(|type_name| {
+            Box::new(EvalAltResult::ErrorMismatchDataType(
+                "integer".into(),
+                context.engine().map_type_name(type_name).into(),
+                context.position(),
+            ))
+        })
+    })?;
+
+    *list = sorted;
+    Ok(().into())
+}
+fn sort(context: &NativeCallContext, list: &mut Array) -> FuncReturn<Dynamic> {
+    let mut sorted = list.clone();
+
+    merge_sort(&mut sorted, &mut |x, y| {
+        natural_order(x, y).ok_or_else(|| {
+            let engine = context.engine();
+
+            Box::new(EvalAltResult::ErrorRuntime(
+                format!(
+                    "Cannot compare {} with {} in the natural order",
+                    engine.map_type_name(x.type_name()),
+                    engine.map_type_name(y.type_name())
+                ),
+                context.position(),
+            ))
+        })
+    })?;
+
+    *list = sorted;
+    Ok(().into())
+}
+fn dedup_by(
+    context: &NativeCallContext,
+    list: &mut Array,
+    comparer: &FnPtr,
+) -> FuncReturn<Dynamic> {
+    let mut result = Array::with_capacity(list.len());
+
+    for item in list.iter() {
+        let is_dup = match result.last() {
+            Some(last) => to_bool(
+                context,
+                comparer.call_dynamic(context, vec![last.clone(), item.clone()])?,
+            )?,
+            None => false,
+        };
+
+        if !is_dup {
+            result.push(item.clone());
+        }
+    }
+
+    *list = result;
+    Ok(().into())
+}
+fn drain(context: &NativeCallContext, list: &mut Array, filter: &FnPtr) -> FuncReturn<Dynamic> {
+    let matched = matches(context, list, filter)?;
+    let (removed, kept) = list
+        .drain(..)
+        .zip(matched)
+        .partition::<Vec<_>, _>(|(_, matched)| *matched);
+
+    *list = kept.into_iter().map(|(item, _)| item).collect();
+    Ok(removed
+        .into_iter()
+        .map(|(item, _)| item)
+        .collect::<Array>()
+        .into())
+}
+fn retain(context: &NativeCallContext, list: &mut Array, filter: &FnPtr) -> FuncReturn<Dynamic> {
+    let matched = matches(context, list, filter)?;
+    let (kept, removed) = list
+        .drain(..)
+        .zip(matched)
+        .partition::<Vec<_>, _>(|(_, matched)| *matched);
+
+    *list = kept.into_iter().map(|(item, _)| item).collect();
+    Ok(removed
+        .into_iter()
+        .map(|(item, _)| item)
+        .collect::<Array>()
+        .into())
+}
+
+/// Register an array function taking a function pointer as callback.
+fn reg_callback(
+    lib: &mut PackageStore,
+    fn_name: &'static str,
+    func: fn(&NativeCallContext, &mut Array, &FnPtr) -> FuncReturn<Dynamic>,
+) {
+    reg_raw(
+        lib,
+        fn_name,
+        &[TypeId::of::<Array>(), TypeId::of::<FnPtr>()],
+        move |context, args| {
+            let callback = args[1].downcast_ref::<FnPtr>().unwrap().clone();
+            func(context, args[0].downcast_mut::<Array>().unwrap(), &callback)
+        },
+    );
+}
+/// Register `reduce` or `reduce_rev` with an initial value of any type.
+fn reg_reduce(lib: &mut PackageStore, fn_name: &'static str, rev: bool) {
+    let arg_types = [
+        TypeId::of::<Array>(),
+        TypeId::of::<FnPtr>(),
+        TypeId::of::<Dynamic>(),
+    ];
+
+    reg_raw(lib, fn_name, &arg_types, move |context, args| {
+        let reducer = args[1].downcast_ref::<FnPtr>().unwrap();
+        let list = args[0].downcast_ref::<Array>().unwrap();
+        reduce(context, list, reducer, Some(args[2].clone()), rev)
+    });
 }
 
 macro_rules! reg_op { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
@@ -35,12 +393,15 @@ macro_rules! reg_op { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
 macro_rules! reg_tri { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
     $(reg_trinary_mut($lib, $op, $func::<$par>, map);)* };
 }
+macro_rules! reg_pad { ($lib:expr, $($par:ty),*) => {
+    $(reg_trinary_mut($lib, "pad", pad::<$par>, result);)* };
+}
 
 #[cfg(not(feature = "no_index"))]
 def_package!(crate:BasicArrayPackage:"Basic array utilities.", lib, {
-    reg_op!(lib, "push", push, INT, bool, char, String, Array, ());
-    reg_tri!(lib, "pad", pad, INT, bool, char, String, Array, ());
-    reg_tri!(lib, "insert", ins, INT, bool, char, String, Array, ());
+    reg_op!(lib, "push", push, INT, bool, char, String, Array, FnPtr, ());
+    reg_pad!(lib, INT, bool, char, String, Array, FnPtr, ());
+    reg_tri!(lib, "insert", ins, INT, bool, char, String, Array, FnPtr, ());
 
     reg_binary_mut(lib, "append", |x: &mut Array, y: Array| x.extend(y), map);
     reg_binary(
@@ -57,14 +418,14 @@ def_package!(crate:BasicArrayPackage:"Basic array utilities.", lib, {
     #[cfg(not(feature = "only_i64"))]
     {
         reg_op!(lib, "push", push, i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
-        reg_tri!(lib, "pad", pad, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_pad!(lib, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
         reg_tri!(lib, "insert", ins, i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
     }
 
     #[cfg(not(feature = "no_float"))]
     {
         reg_op!(lib, "push", push, f32, f64);
-        reg_tri!(lib, "pad", pad, f32, f64);
+        reg_pad!(lib, f32, f64);
         reg_tri!(lib, "insert", ins, f32, f64);
     }
 
@@ -113,6 +474,80 @@ def_package!(crate:BasicArrayPackage:"Basic array utilities.", lib, {
         map,
     );
 
+    reg_unary_mut(lib, "reverse", |list: &mut Array| list.reverse(), map);
+    reg_binary_mut(
+        lib,
+        "extract",
+        |list: &mut Array, start: INT| extract(list, start, INT::MAX),
+        map,
+    );
+    reg_trinary_mut(lib, "extract", extract, map);
+    reg_trinary_mut(lib, "drain", drain_range, map);
+    reg_trinary_mut(lib, "retain", retain_range, map);
+
+    reg_raw(
+        lib,
+        "splice",
+        &[TypeId::of::<Array>(), TypeId::of::<INT>(), TypeId::of::<INT>(), TypeId::of::<Array>()],
+        |_, args| {
+            let start = args[1].as_int().unwrap();
+            let len = args[2].as_int().unwrap();
+            let replace = args[3].downcast_ref::<Array>().unwrap().clone();
+            splice(args[0].downcast_mut::<Array>().unwrap(), start, len, replace);
+            Ok(().into())
+        },
+    );
+
+    // Register functions taking callbacks
+    reg_callback(lib, "map", map_array);
+    reg_callback(lib, "filter", filter);
+    reg_callback(lib, "some", some);
+    reg_callback(lib, "all", all);
+    reg_callback(lib, "find", |context, list, filter| {
+        Ok(find_index(context, list, filter)?.map_or_else(|| ().into(), |i| list[i].clone()))
+    });
+    reg_callback(lib, "find_index", |context, list, filter| {
+        Ok(find_index(context, list, filter)?.map_or(-1, |i| i as INT).into())
+    });
+    reg_callback(lib, "sort", sort_by);
+    reg_callback(lib, "dedup", dedup_by);
+    reg_callback(lib, "drain", drain);
+    reg_callback(lib, "retain", retain);
+    reg_callback(lib, "reduce", |context, list, reducer| {
+        reduce(context, list, reducer, None, false)
+    });
+    reg_callback(lib, "reduce_rev", |context, list, reducer| {
+        reduce(context, list, reducer, None, true)
+    });
+    reg_reduce(lib, "reduce", false);
+    reg_reduce(lib, "reduce_rev", true);
+
+    reg_raw(lib, "sort", &[TypeId::of::<Array>()], |context, args| {
+        sort(context, args[0].downcast_mut::<Array>().unwrap())
+    });
+    reg_unary_mut(
+        lib,
+        "dedup",
+        |list: &mut Array| list.dedup_by(|x, y| natural_order(x, y) == Some(Ordering::Equal)),
+        map,
+    );
+
+    // Register JSON serialization functions
+    reg_raw(lib, "to_json", &[TypeId::of::<Array>()], |context, args| {
+        let list = args[0].downcast_ref::<Array>().unwrap();
+        Ok(array_to_json(context, list, None)?.into())
+    });
+    reg_raw(
+        lib,
+        "to_json",
+        &[TypeId::of::<Array>(), TypeId::of::<INT>()],
+        |context, args| {
+            let indent = *args[1].downcast_ref::<INT>().unwrap();
+            let list = args[0].downcast_ref::<Array>().unwrap();
+            Ok(array_to_json(context, list, Some(indent))?.into())
+        },
+    );
+
     // Register array iterator
     lib.type_iterators.insert(
         TypeId::of::<Array>(),
@@ -0,0 +1,392 @@
+use super::logic::{eq, gt, gte, lt, lte, ne};
+use super::{
+    reg_binary, reg_binary_mut, reg_none, reg_raw, reg_trinary, reg_unary, reg_unary_mut, Package,
+    PackageLibrary, PackageStore,
+};
+
+use crate::any::Dynamic;
+use crate::datetime::{DateTime, Duration};
+use crate::engine::{FUNC_TO_STRING, KEYWORD_DEBUG, KEYWORD_PRINT};
+use crate::fn_register::{map_dynamic as map, map_result as result};
+use crate::parser::INT;
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
+
+use crate::stdlib::{
+    any::TypeId,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+fn out_of_range(message: String) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorArithmetic(message, Position::none()))
+}
+
+fn parse_error(message: String) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(message, Position::none()))
+}
+
+/// Convert a number to `INT`, checking for overflow unless under `unchecked`.
+fn to_int(value: i128) -> Result<INT, Box<EvalAltResult>> {
+    #[cfg(not(feature = "unchecked"))]
+    {
+        if value < INT::MIN as i128 || value > INT::MAX as i128 {
+            return Err(out_of_range(format!(
+                "Integer overflow for date/time value: {}",
+                value
+            )));
+        }
+    }
+
+    Ok(value as INT)
+}
+
+fn make_datetime(
+    date: (INT, INT, INT),
+    time: (INT, INT, INT),
+) -> Result<DateTime, Box<EvalAltResult>> {
+    let date = (date.0 as i128, date.1 as i128, date.2 as i128);
+    let time = (time.0 as i128, time.1 as i128, time.2 as i128);
+
+    DateTime::from_parts(date, time, 0, 0)
+        .map_err(|err| out_of_range(format!("Cannot create date/time: {}", err)))
+}
+
+/// Create a duration of a number of time units, each `unit` nanoseconds long.
+fn make_duration(value: INT, unit: i128) -> Result<Duration, Box<EvalAltResult>> {
+    Duration::from_nanoseconds(value as i128 * unit)
+        .ok_or_else(|| out_of_range(format!("Duration out of range: {} x {}ns", value, unit)))
+}
+
+fn add(datetime: DateTime, duration: Duration) -> Result<DateTime, Box<EvalAltResult>> {
+    datetime.checked_add(duration).ok_or_else(|| {
+        out_of_range(format!(
+            "Date/time out of range: {} + {}",
+            datetime, duration
+        ))
+    })
+}
+
+fn sub(datetime: DateTime, duration: Duration) -> Result<DateTime, Box<EvalAltResult>> {
+    datetime.checked_sub(duration).ok_or_else(|| {
+        out_of_range(format!(
+            "Date/time out of range: {} - {}",
+            datetime, duration
+        ))
+    })
+}
+
+fn parse_datetime(s: &str, fmt: Option<&str>) -> Result<DateTime, Box<EvalAltResult>> {
+    match fmt {
+        Some(fmt) => DateTime::parse(s, fmt),
+        None => DateTime::parse_iso8601(s),
+    }
+    .map_err(|err| parse_error(format!("Cannot parse '{}' as a date/time: {}", s, err)))
+}
+
+fn format_datetime(datetime: &mut DateTime, fmt: &str) -> Result<String, Box<EvalAltResult>> {
+    datetime.format(fmt).map_err(|err| {
+        Box::new(EvalAltResult::ErrorRuntime(
+            format!("Invalid date/time format string: {}", err),
+            Position::none(),
+        ))
+    })
+}
+
+macro_rules! reg_getter {
+    ($lib:expr, $($name:expr => $func:expr),*) => {
+        $(
+            reg_unary_mut($lib, $name, $func, result);
+            #[cfg(not(feature = "no_object"))]
+            reg_unary_mut($lib, concat!("get$", $name), $func, result);
+        )*
+    };
+}
+
+macro_rules! reg_cmp {
+    ($lib:expr, $($type:ty),*) => {
+        $(
+            reg_binary($lib, "<", lt::<$type>, map);
+            reg_binary($lib, "<=", lte::<$type>, map);
+            reg_binary($lib, ">", gt::<$type>, map);
+            reg_binary($lib, ">=", gte::<$type>, map);
+            reg_binary($lib, "==", eq::<$type>, map);
+            reg_binary($lib, "!=", ne::<$type>, map);
+        )*
+    };
+}
+
+macro_rules! reg_print {
+    ($lib:expr, $($type:ty),*) => {
+        $(
+            reg_unary_mut($lib, KEYWORD_PRINT, |x: &mut $type| x.to_string(), map);
+            reg_unary_mut($lib, FUNC_TO_STRING, |x: &mut $type| x.to_string(), map);
+            reg_unary_mut($lib, KEYWORD_DEBUG, |x: &mut $type| x.to_string(), map);
+        )*
+    };
+}
+
+/// Register all the date/time functions except `now`.
+fn init(lib: &mut PackageStore) {
+    // Date/times
+    reg_trinary(
+        lib,
+        "datetime",
+        |year: INT, month: INT, day: INT| make_datetime((year, month, day), (0, 0, 0)),
+        result,
+    );
+    reg_raw(lib, "datetime", &[TypeId::of::<INT>(); 6], |_, args| {
+        let mut parts = [0; 6];
+
+        parts.iter_mut().zip(args.iter()).for_each(|(part, arg)| {
+            *part = arg.downcast_ref::<INT>().cloned().unwrap();
+        });
+
+        let date = (parts[0], parts[1], parts[2]);
+        let time = (parts[3], parts[4], parts[5]);
+        make_datetime(date, time).map(Dynamic::from)
+    });
+    reg_unary(
+        lib,
+        "from_unix_time",
+        |seconds: INT| {
+            Duration::from_nanoseconds(seconds as i128 * NANOS_PER_SEC)
+                .and_then(|duration| DateTime::UNIX_EPOCH.checked_add(duration))
+                .ok_or_else(|| out_of_range(format!("Date/time out of range: {} seconds", seconds)))
+        },
+        result,
+    );
+    reg_unary_mut(
+        lib,
+        "parse_datetime",
+        |s: &mut String| parse_datetime(s, None),
+        result,
+    );
+    reg_binary_mut(
+        lib,
+        "parse_datetime",
+        |s: &mut String, fmt: String| parse_datetime(s, Some(&fmt)),
+        result,
+    );
+    reg_binary_mut(
+        lib,
+        "format",
+        |datetime: &mut DateTime, fmt: String| format_datetime(datetime, &fmt),
+        result,
+    );
+
+    reg_getter!(lib,
+        "unix_time" => |x: &mut DateTime| to_int(x.unix_time() as i128),
+        "year" => |x: &mut DateTime| Ok(x.year() as INT),
+        "month" => |x: &mut DateTime| Ok(x.month() as INT),
+        "day" => |x: &mut DateTime| Ok(x.day() as INT),
+        "hour" => |x: &mut DateTime| Ok(x.hour() as INT),
+        "minute" => |x: &mut DateTime| Ok(x.minute() as INT),
+        "second" => |x: &mut DateTime| Ok(x.second() as INT),
+        "nanosecond" => |x: &mut DateTime| Ok(x.nanosecond() as INT),
+        "weekday" => |x: &mut DateTime| Ok(x.weekday() as INT),
+        "day_of_year" => |x: &mut DateTime| Ok(x.day_of_year() as INT)
+    );
+
+    // Durations
+    reg_unary(
+        lib,
+        "days",
+        |n: INT| make_duration(n, 86_400 * NANOS_PER_SEC),
+        result,
+    );
+    reg_unary(
+        lib,
+        "hours",
+        |n: INT| make_duration(n, 3_600 * NANOS_PER_SEC),
+        result,
+    );
+    reg_unary(
+        lib,
+        "minutes",
+        |n: INT| make_duration(n, 60 * NANOS_PER_SEC),
+        result,
+    );
+    reg_unary(
+        lib,
+        "seconds",
+        |n: INT| make_duration(n, NANOS_PER_SEC),
+        result,
+    );
+    reg_unary(
+        lib,
+        "milliseconds",
+        |n: INT| make_duration(n, 1_000_000),
+        result,
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    reg_unary(
+        lib,
+        "seconds",
+        |n: FLOAT| {
+            Duration::from_seconds_float(n)
+                .ok_or_else(|| out_of_range(format!("Duration out of range: {} seconds", n)))
+        },
+        result,
+    );
+
+    reg_unary_mut(
+        lib,
+        "parse_duration",
+        |s: &mut String| {
+            Duration::parse_iso8601(s)
+                .map_err(|err| parse_error(format!("Cannot parse '{}' as a duration: {}", s, err)))
+        },
+        result,
+    );
+
+    reg_getter!(lib,
+        "total_days" => |x: &mut Duration| to_int(x.as_nanoseconds() / (86_400 * NANOS_PER_SEC)),
+        "total_hours" => |x: &mut Duration| to_int(x.as_nanoseconds() / (3_600 * NANOS_PER_SEC)),
+        "total_minutes" => |x: &mut Duration| to_int(x.as_nanoseconds() / (60 * NANOS_PER_SEC)),
+        "total_milliseconds" => |x: &mut Duration| to_int(x.as_nanoseconds() / 1_000_000)
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    reg_getter!(lib,
+        "total_seconds" => |x: &mut Duration| Ok::<_, Box<EvalAltResult>>(x.as_seconds_float())
+    );
+
+    #[cfg(feature = "no_float")]
+    reg_getter!(lib,
+        "total_seconds" => |x: &mut Duration| to_int(x.as_seconds() as i128)
+    );
+
+    reg_unary(lib, "abs", |x: Duration| x.abs(), map);
+    reg_unary(lib, "-", |x: Duration| -x, map);
+
+    // Arithmetic
+    reg_binary(lib, "+", add, result);
+    reg_binary(lib, "+", |x: Duration, y: DateTime| add(y, x), result);
+    reg_binary(lib, "-", sub, result);
+    reg_binary(
+        lib,
+        "-",
+        |x: DateTime, y: DateTime| x.duration_since(y),
+        map,
+    );
+
+    reg_binary(
+        lib,
+        "+",
+        |x: Duration, y: Duration| {
+            x.checked_add(y)
+                .ok_or_else(|| out_of_range(format!("Duration out of range: {} + {}", x, y)))
+        },
+        result,
+    );
+    reg_binary(
+        lib,
+        "-",
+        |x: Duration, y: Duration| {
+            x.checked_sub(y)
+                .ok_or_else(|| out_of_range(format!("Duration out of range: {} - {}", x, y)))
+        },
+        result,
+    );
+    reg_binary(
+        lib,
+        "*",
+        |x: Duration, y: INT| {
+            x.checked_mul(y as i128)
+                .ok_or_else(|| out_of_range(format!("Duration out of range: {} * {}", x, y)))
+        },
+        result,
+    );
+    reg_binary(
+        lib,
+        "*",
+        |x: INT, y: Duration| {
+            y.checked_mul(x as i128)
+                .ok_or_else(|| out_of_range(format!("Duration out of range: {} * {}", x, y)))
+        },
+        result,
+    );
+    reg_binary(
+        lib,
+        "/",
+        |x: Duration, y: INT| {
+            x.checked_div(y as i128)
+                .ok_or_else(|| out_of_range(format!("Division by zero: {} / {}", x, y)))
+        },
+        result,
+    );
+
+    reg_cmp!(lib, DateTime, Duration);
+    reg_print!(lib, DateTime, Duration);
+}
+
+/// Package of date/time functions, with UTC date/times (`DateTime`) and durations (`Duration`).
+///
+/// The current date/time is available via `now()`, which reads the system clock.  Under `no_std`
+/// there is no system clock, so `now()` is only registered when the package is created with
+/// `BasicDateTimePackage::with_clock`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// # #[cfg(not(feature = "no_stdlib"))]
+/// # {
+/// use rhai::Engine;
+///
+/// let engine = Engine::new();
+///
+/// let result = engine.eval::<String>(
+///     r#"
+///         let date = parse_datetime("2026-10-17T12:00:00Z") + days(30);
+///         format(date, "%A %e %B")
+///     "#,
+/// )?;
+///
+/// assert_eq!(result, "Monday 16 November");
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub struct BasicDateTimePackage(PackageLibrary);
+
+impl BasicDateTimePackage {
+    /// Create a new `BasicDateTimePackage` whose `now()` function gets the current date/time from
+    /// a host-supplied clock instead of the system clock.
+    ///
+    /// This is the only way to make `now()` available under `no_std`.  It is also handy for
+    /// testing scripts against a fixed date/time.
+    pub fn with_clock(clock: fn() -> DateTime) -> Self {
+        let mut lib = PackageStore::new();
+        init(&mut lib);
+        reg_none(&mut lib, "now", clock, map);
+        Self(lib.into())
+    }
+}
+
+impl Package for BasicDateTimePackage {
+    fn new() -> Self {
+        let mut lib = PackageStore::new();
+        Self::init(&mut lib);
+        Self(lib.into())
+    }
+
+    fn get(&self) -> PackageLibrary {
+        self.0.clone()
+    }
+
+    fn init(lib: &mut PackageStore) {
+        init(lib);
+
+        #[cfg(not(feature = "no_std"))]
+        reg_none(lib, "now", DateTime::now, map);
+    }
+}
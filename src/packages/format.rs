@@ -0,0 +1,401 @@
+//! Module implementing `format`-style formatting of `Dynamic` values.
+//!
+//! The format string syntax follows Rust's `format!` macro: `{}` is replaced by the next argument,
+//! `{n}` by the n-th argument, and `{{`/`}}` stand for literal braces.  A format specification after
+//! a colon controls the layout: `{:[[fill]align][+][#][0][width][.precision][type]}`.
+
+use super::MAX_FORMAT_WIDTH;
+
+use crate::any::{Dynamic, Union};
+use crate::engine::{FUNC_TO_STRING, KEYWORD_DEBUG};
+use crate::fn_native::NativeCallContext;
+use crate::result::EvalAltResult;
+
+use crate::stdlib::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Alignment of a formatted value within its width.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A parsed format specification, i.e. the part of a placeholder after the colon.
+#[derive(Debug, Clone, Default)]
+struct FormatSpec {
+    /// Character used to fill up the width.
+    fill: Option<char>,
+    /// Alignment within the width, if specified.
+    align: Option<Align>,
+    /// Always show the sign of numbers?
+    plus: bool,
+    /// Alternate form, i.e. `0x`, `0b` or `0o` prefixes.
+    alternate: bool,
+    /// Pad numbers with zeros after the sign?
+    zero: bool,
+    /// Minimum width in characters.
+    width: usize,
+    /// Digits after the decimal point for floating-point numbers, maximum length for strings.
+    precision: Option<usize>,
+    /// Type of the formatting: `x`, `X`, `b`, `o`, `e`, `E` or `?`.
+    kind: Option<char>,
+}
+
+impl FormatSpec {
+    /// Parse a format specification.
+    fn parse(spec: &str) -> Result<Self, String> {
+        let chars: Vec<_> = spec.chars().collect();
+        let mut result = Self::default();
+        let mut i = 0;
+
+        let to_align = |ch: Option<&char>| match ch {
+            Some('<') => Some(Align::Left),
+            Some('^') => Some(Align::Center),
+            Some('>') => Some(Align::Right),
+            _ => None,
+        };
+
+        if let Some(align) = to_align(chars.get(1)) {
+            result.fill = Some(chars[0]);
+            result.align = Some(align);
+            i = 2;
+        } else if let Some(align) = to_align(chars.first()) {
+            result.align = Some(align);
+            i = 1;
+        }
+
+        if chars.get(i) == Some(&'+') {
+            result.plus = true;
+            i += 1;
+        }
+        if chars.get(i) == Some(&'#') {
+            result.alternate = true;
+            i += 1;
+        }
+        if chars.get(i) == Some(&'0') {
+            result.zero = true;
+            i += 1;
+        }
+
+        let (width, next) = parse_number(&chars, i)?;
+        result.width = width.unwrap_or(0);
+        i = next;
+
+        if chars.get(i) == Some(&'.') {
+            let (precision, next) = parse_number(&chars, i + 1)?;
+
+            if precision.is_none() {
+                return Err(format!("missing precision in '{{:{}}}'", spec));
+            }
+
+            result.precision = precision;
+            i = next;
+        }
+
+        match chars.get(i) {
+            Some(ch @ 'x') | Some(ch @ 'X') | Some(ch @ 'b') | Some(ch @ 'o') | Some(ch @ 'e')
+            | Some(ch @ 'E') | Some(ch @ '?') => {
+                result.kind = Some(*ch);
+                i += 1;
+            }
+            _ => (),
+        }
+
+        if i < chars.len() {
+            return Err(format!("invalid format specification '{{:{}}}'", spec));
+        }
+
+        Ok(result)
+    }
+}
+
+/// Parse a run of decimal digits starting at `index`, returning the number (if any) and the next index.
+///
+/// Numbers larger than `MAX_FORMAT_WIDTH` are errors.
+fn parse_number(chars: &[char], index: usize) -> Result<(Option<usize>, usize), String> {
+    let end = chars[index.min(chars.len())..]
+        .iter()
+        .position(|ch| !ch.is_ascii_digit())
+        .map_or(chars.len(), |len| index + len);
+
+    if end <= index {
+        return Ok((None, index));
+    }
+
+    chars[index..end]
+        .iter()
+        .collect::<String>()
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n <= MAX_FORMAT_WIDTH)
+        .map(|n| (Some(n), end))
+        .ok_or_else(|| {
+            format!(
+                "width or precision larger than {} in format specification",
+                MAX_FORMAT_WIDTH
+            )
+        })
+}
+
+/// Format an integer according to the type of a format specification.
+macro_rules! format_int {
+    ($value:expr, $spec:expr) => {
+        match $spec.kind {
+            None | Some('?') => format!("{}", $value),
+            Some('x') => format!("{:x}", $value),
+            Some('X') => format!("{:X}", $value),
+            Some('b') => format!("{:b}", $value),
+            Some('o') => format!("{:o}", $value),
+            Some('e') => format!("{:e}", $value),
+            Some('E') => format!("{:E}", $value),
+            Some(_) => unreachable!(),
+        }
+    };
+}
+
+/// Format a floating-point number according to the type and precision of a format specification.
+#[cfg(not(feature = "no_float"))]
+macro_rules! format_float {
+    ($value:expr, $spec:expr) => {
+        match ($spec.kind, $spec.precision) {
+            (None, None) => Some(format!("{}", $value)),
+            (None, Some(precision)) => Some(format!("{:.*}", precision, $value)),
+            (Some('e'), None) => Some(format!("{:e}", $value)),
+            (Some('e'), Some(precision)) => Some(format!("{:.*e}", precision, $value)),
+            (Some('E'), None) => Some(format!("{:E}", $value)),
+            (Some('E'), Some(precision)) => Some(format!("{:.*E}", precision, $value)),
+            (Some('?'), _) => Some(format!("{:?}", $value)),
+            _ => None,
+        }
+    };
+}
+
+/// Format a number, returning `None` if the value is not a number or the type does not apply to it.
+fn format_number(value: &Dynamic, spec: &FormatSpec) -> Option<String> {
+    #[cfg(not(feature = "no_float"))]
+    let is_int_kind = matches!(spec.kind, Some('x') | Some('X') | Some('b') | Some('o'));
+
+    match &value.0 {
+        Union::Int(value) => return Some(format_int!(value, spec)),
+        #[cfg(not(feature = "no_float"))]
+        Union::Float(value) if !is_int_kind => return format_float!(value, spec),
+        _ => (),
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        if let Some(value) = value.downcast_ref::<f32>() {
+            return if is_int_kind {
+                None
+            } else {
+                format_float!(value, spec)
+            };
+        }
+    }
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        macro_rules! format_variant {
+            ($($t:ty),*) => {
+                $(
+                    if let Some(value) = value.downcast_ref::<$t>() {
+                        return Some(format_int!(value, spec));
+                    }
+                )*
+            };
+        }
+
+        format_variant!(i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
+    }
+
+    None
+}
+
+/// Add the sign, prefix and zero-padding to a formatted number.
+fn finish_number(body: String, spec: &FormatSpec) -> String {
+    let (sign, digits) = if let Some(digits) = body.strip_prefix('-') {
+        ("-", digits)
+    } else if spec.plus {
+        ("+", &body[..])
+    } else {
+        ("", &body[..])
+    };
+
+    let prefix = match spec.kind {
+        Some('x') | Some('X') if spec.alternate => "0x",
+        Some('b') if spec.alternate => "0b",
+        Some('o') if spec.alternate => "0o",
+        _ => "",
+    };
+
+    let len = sign.len() + prefix.len() + digits.chars().count();
+
+    if spec.zero && len < spec.width {
+        let zeros = "0".repeat(spec.width - len);
+        format!("{}{}{}{}", sign, prefix, zeros, digits)
+    } else {
+        format!("{}{}{}", sign, prefix, digits)
+    }
+}
+
+/// Pad a formatted value to the width of a format specification.
+fn pad(body: String, spec: &FormatSpec, default_align: Align) -> String {
+    let len = body.chars().count();
+
+    if len >= spec.width {
+        return body;
+    }
+
+    let fill = spec.fill.unwrap_or(' ');
+    let padding = spec.width - len;
+
+    let (left, right) = match spec.align.unwrap_or(default_align) {
+        Align::Left => (0, padding),
+        Align::Center => (padding / 2, padding - padding / 2),
+        Align::Right => (padding, 0),
+    };
+
+    let fill = fill.to_string();
+    format!("{}{}{}", fill.repeat(left), body, fill.repeat(right))
+}
+
+/// Format a single value according to a format specification.
+fn format_value(
+    context: &NativeCallContext,
+    value: &mut Dynamic,
+    spec: &FormatSpec,
+) -> Result<String, Box<EvalAltResult>> {
+    if let Some(body) = format_number(value, spec) {
+        return Ok(pad(finish_number(body, spec), spec, Align::Right));
+    }
+
+    let body = match (&value.0, spec.kind) {
+        (Union::Str(s), None) => s.to_string(),
+        (Union::Char(ch), None) => ch.to_string(),
+        (Union::Bool(b), None) => b.to_string(),
+        (Union::Unit(_), None) => "".to_string(),
+        (Union::Str(s), Some('?')) => format!("{:?}", s),
+        (Union::Char(ch), Some('?')) => format!("{:?}", ch),
+        (Union::Bool(b), Some('?')) => b.to_string(),
+        (_, Some(kind)) if kind != '?' => {
+            return Err(Box::new(EvalAltResult::ErrorRuntime(
+                format!(
+                    "Cannot format {} with type '{}'",
+                    context.engine().map_type_name(value.type_name()),
+                    kind
+                ),
+                context.position(),
+            )))
+        }
+        (_, kind) => {
+            let mut args = [value];
+
+            // Call the `debug` function directly, bypassing the engine's `debug` output
+            let debug = kind.and_then(|_| context.engine().get_native_fn(KEYWORD_DEBUG, &args));
+
+            let result = match debug {
+                Some(func) => func(context, &mut args)?,
+                None => context.call_fn(FUNC_TO_STRING, &mut args)?,
+            };
+            let type_name = context
+                .engine()
+                .map_type_name(result.type_name())
+                .to_string();
+
+            result.take_string().map_err(|_| {
+                Box::new(EvalAltResult::ErrorMismatchDataType(
+                    "string".into(),
+                    type_name,
+                    context.position(),
+                ))
+            })?
+        }
+    };
+
+    let body = match spec.precision {
+        Some(precision) => body.chars().take(precision).collect(),
+        None => body,
+    };
+
+    Ok(pad(body, spec, Align::Left))
+}
+
+/// Format a list of values according to a format string.
+pub fn format_string(
+    context: &NativeCallContext,
+    fmt: &str,
+    args: &mut [Dynamic],
+) -> Result<String, Box<EvalAltResult>> {
+    let error = |message: String| {
+        Box::new(EvalAltResult::ErrorRuntime(
+            format!("Invalid format string: {}", message),
+            context.position(),
+        ))
+    };
+
+    let mut result = String::new();
+    let mut chars = fmt.chars().peekable();
+    let mut next_arg = 0;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                result.push('}');
+            }
+            '}' => return Err(error("unmatched '}'".into())),
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => placeholder.push(ch),
+                        None => return Err(error("unmatched '{'".into())),
+                    }
+                }
+
+                let (index, spec) = match placeholder.find(':') {
+                    Some(pos) => (&placeholder[..pos], &placeholder[pos + 1..]),
+                    None => (&placeholder[..], ""),
+                };
+
+                let index = if index.is_empty() {
+                    next_arg += 1;
+                    next_arg - 1
+                } else {
+                    index
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| error(format!("invalid argument index '{}'", index)))?
+                };
+
+                let spec = FormatSpec::parse(spec).map_err(error)?;
+
+                if index >= args.len() {
+                    return Err(error(format!(
+                        "argument {} is missing, only {} given",
+                        index,
+                        args.len()
+                    )));
+                }
+
+                result.push_str(&format_value(context, &mut args[index], &spec)?);
+            }
+            ch => result.push(ch),
+        }
+    }
+
+    Ok(result)
+}
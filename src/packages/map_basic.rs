@@ -1,22 +1,122 @@
-use super::{reg_binary, reg_binary_mut, reg_unary_mut};
+use super::array_basic::to_bool;
+use super::{reg_binary, reg_binary_mut, reg_raw, reg_trinary_mut, reg_unary_mut, PackageStore};
 
-use crate::any::Dynamic;
+use crate::any::{Dynamic, Union, Variant};
 use crate::def_package;
-use crate::engine::Map;
+use crate::engine::{Array, Map};
+use crate::fn_native::{FnPtr, NativeCallContext};
 use crate::fn_register::map_dynamic as map;
+use crate::json::map_to_json;
 use crate::parser::INT;
+use crate::result::EvalAltResult;
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
 
 use crate::stdlib::{
+    any::TypeId,
+    boxed::Box,
     string::{String, ToString},
+    vec,
     vec::Vec,
 };
 
+type FuncReturn<T> = Result<T, Box<EvalAltResult>>;
+
 fn map_get_keys(map: &mut Map) -> Vec<Dynamic> {
     map.iter().map(|(k, _)| k.to_string().into()).collect()
 }
 fn map_get_values(map: &mut Map) -> Vec<Dynamic> {
     map.iter().map(|(_, v)| v.clone()).collect()
 }
+fn map_to_pairs(map: Map) -> impl Iterator<Item = Dynamic> {
+    map.into_iter()
+        .map(|(k, v)| Dynamic::from(vec![Dynamic::from(k), v]))
+}
+fn get_or<T: Variant + Clone>(map: &mut Map, name: String, default: T) -> Dynamic {
+    map.get(&name)
+        .cloned()
+        .unwrap_or_else(|| Dynamic::from(default))
+}
+fn set<T: Variant + Clone>(map: &mut Map, name: String, value: T) {
+    map.insert(name, Dynamic::from(value));
+}
+/// Merge a map into another, recursively merging nested maps.
+fn merge(map1: &mut Map, map2: Map) {
+    map2.into_iter().for_each(|(key, value)| {
+        match (map1.get_mut(&key).map(|v| &mut v.0), value.0) {
+            (Some(Union::Map(target)), Union::Map(source)) => merge(target, *source),
+            (_, value) => {
+                map1.insert(key, Dynamic(value));
+            }
+        }
+    });
+}
+/// Call a callback with each key and value, returning the results.
+fn call_each(
+    context: &NativeCallContext,
+    map: &Map,
+    callback: &FnPtr,
+) -> FuncReturn<Vec<(String, Dynamic)>> {
+    map.iter()
+        .map(|(key, value)| {
+            callback
+                .call_dynamic(context, vec![key.to_string().into(), value.clone()])
+                .map(|result| (key.clone(), result))
+        })
+        .collect()
+}
+fn filter(context: &NativeCallContext, map: &mut Map, filter: &FnPtr) -> FuncReturn<Dynamic> {
+    let mut result = Map::new();
+
+    for (key, matched) in call_each(context, map, filter)? {
+        if to_bool(context, matched)? {
+            let value = map.get(&key).unwrap().clone();
+            result.insert(key, value);
+        }
+    }
+
+    Ok(result.into())
+}
+fn map_values(context: &NativeCallContext, map: &mut Map, mapper: &FnPtr) -> FuncReturn<Dynamic> {
+    Ok(call_each(context, map, mapper)?
+        .into_iter()
+        .collect::<Map>()
+        .into())
+}
+fn drain(context: &NativeCallContext, map: &mut Map, filter: &FnPtr) -> FuncReturn<Dynamic> {
+    let mut removed = Map::new();
+
+    for (key, matched) in call_each(context, map, filter)? {
+        if to_bool(context, matched)? {
+            let value = map.remove(&key).unwrap();
+            removed.insert(key, value);
+        }
+    }
+
+    Ok(removed.into())
+}
+
+/// Register a map function taking a function pointer as callback.
+fn reg_callback(
+    lib: &mut PackageStore,
+    fn_name: &'static str,
+    func: fn(&NativeCallContext, &mut Map, &FnPtr) -> FuncReturn<Dynamic>,
+) {
+    reg_raw(
+        lib,
+        fn_name,
+        &[TypeId::of::<Map>(), TypeId::of::<FnPtr>()],
+        move |context, args| {
+            let callback = args[1].downcast_ref::<FnPtr>().unwrap().clone();
+            func(context, args[0].downcast_mut::<Map>().unwrap(), &callback)
+        },
+    );
+}
+
+macro_rules! reg_tri { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
+    $(reg_trinary_mut($lib, $op, $func::<$par>, map);)* };
+}
 
 #[cfg(not(feature = "no_object"))]
 def_package!(crate:BasicMapPackage:"Basic object map utilities.", lib, {
@@ -44,6 +144,19 @@ def_package!(crate:BasicMapPackage:"Basic object map utilities.", lib, {
         },
         map,
     );
+    reg_binary_mut(lib, "merge", merge, map);
+    reg_binary_mut(
+        lib,
+        "fill_with",
+        |map1: &mut Map, map2: Map| {
+            map2.into_iter().for_each(|(key, value)| {
+                if !map1.contains_key(&key) {
+                    map1.insert(key, value);
+                }
+            });
+        },
+        map,
+    );
     reg_binary(
         lib,
         "+",
@@ -56,10 +169,69 @@ def_package!(crate:BasicMapPackage:"Basic object map utilities.", lib, {
         map,
     );
 
+    reg_binary_mut(
+        lib,
+        "get",
+        |map: &mut Map, name: String| map.get(&name).cloned().unwrap_or_else(|| ().into()),
+        map,
+    );
+    reg_tri!(lib, "get", get_or, INT, bool, char, String, Array, Map, FnPtr, ());
+    reg_tri!(lib, "set", set, INT, bool, char, String, Array, Map, FnPtr, ());
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        reg_tri!(lib, "get", get_or, i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
+        reg_tri!(lib, "set", set, i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        reg_tri!(lib, "get", get_or, f32, FLOAT);
+        reg_tri!(lib, "set", set, f32, FLOAT);
+    }
+
+    // Register callback functions
+    reg_callback(lib, "filter", filter);
+    reg_callback(lib, "map_values", map_values);
+    reg_callback(lib, "drain", drain);
+
+    // Register JSON serialization functions
+    reg_raw(lib, "to_json", &[TypeId::of::<Map>()], |context, args| {
+        let map = args[0].downcast_ref::<Map>().unwrap();
+        Ok(map_to_json(context, map, None)?.into())
+    });
+    reg_raw(
+        lib,
+        "to_json",
+        &[TypeId::of::<Map>(), TypeId::of::<INT>()],
+        |context, args| {
+            let indent = *args[1].downcast_ref::<INT>().unwrap();
+            let map = args[0].downcast_ref::<Map>().unwrap();
+            Ok(map_to_json(context, map, Some(indent))?.into())
+        },
+    );
+
     // Register map access functions
     #[cfg(not(feature = "no_index"))]
     reg_unary_mut(lib, "keys", map_get_keys, map);
 
     #[cfg(not(feature = "no_index"))]
     reg_unary_mut(lib, "values", map_get_values, map);
+
+    #[cfg(not(feature = "no_index"))]
+    reg_unary_mut(
+        lib,
+        "to_array",
+        |map: &mut Map| map_to_pairs(map.clone()).collect::<Array>(),
+        map,
+    );
+
+    // Register map iterator - each iteration yields a [key, value] pair
+    lib.type_iterators.insert(
+        TypeId::of::<Map>(),
+        Box::new(|m: Dynamic| {
+            Box::new(map_to_pairs(m.cast::<Map>())) as Box<dyn Iterator<Item = Dynamic>>
+        }),
+    );
 });
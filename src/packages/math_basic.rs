@@ -1,4 +1,4 @@
-use super::{reg_binary, reg_unary};
+use super::{reg_binary, reg_trinary, reg_unary};
 
 use crate::def_package;
 use crate::fn_register::{map_dynamic as map, map_result as result};
@@ -6,16 +6,246 @@ use crate::parser::INT;
 use crate::result::EvalAltResult;
 use crate::token::Position;
 
+#[cfg(not(feature = "unchecked"))]
+use super::arithmetic::pow_i_i;
+#[cfg(feature = "unchecked")]
+use super::arithmetic::pow_i_i_u;
+
+#[cfg(not(feature = "no_float"))]
+use super::arithmetic::pow_f_f;
+#[cfg(not(feature = "unchecked"))]
+#[cfg(not(feature = "no_float"))]
+use super::arithmetic::pow_f_i;
+#[cfg(feature = "unchecked")]
+#[cfg(not(feature = "no_float"))]
+use super::arithmetic::pow_f_i_u;
+
 #[cfg(not(feature = "no_float"))]
 use crate::parser::FLOAT;
 
-use crate::stdlib::{boxed::Box, format, i32, i64};
+use num_traits::{identities::Zero, CheckedRem, PrimInt};
+
+use crate::stdlib::{boxed::Box, fmt::Display, format, i32, i64};
 
 #[cfg(feature = "only_i32")]
 pub const MAX_INT: INT = i32::MAX;
 #[cfg(not(feature = "only_i32"))]
 pub const MAX_INT: INT = i64::MAX;
 
+// Number of bits in an integer type
+fn bits<T: PrimInt>() -> INT {
+    T::zero().count_zeros() as INT
+}
+fn min<T: PartialOrd>(x: T, y: T) -> T {
+    if y < x {
+        y
+    } else {
+        x
+    }
+}
+fn max<T: PartialOrd>(x: T, y: T) -> T {
+    if y > x {
+        y
+    } else {
+        x
+    }
+}
+fn clamp<T: Display + PartialOrd>(x: T, lo: T, hi: T) -> Result<T, Box<EvalAltResult>> {
+    if lo > hi {
+        Err(Box::new(EvalAltResult::ErrorArithmetic(
+            format!("Invalid range for clamp: {} > {}", lo, hi),
+            Position::none(),
+        )))
+    } else if x < lo {
+        Ok(lo)
+    } else if x > hi {
+        Ok(hi)
+    } else {
+        Ok(x)
+    }
+}
+fn sign<T: PartialOrd + Zero>(x: T) -> INT {
+    if x > T::zero() {
+        1
+    } else if x < T::zero() {
+        -1
+    } else {
+        0
+    }
+}
+// Euclid's algorithm - the result may be negative
+fn gcd_raw<T: PrimInt + CheckedRem>(mut x: T, mut y: T) -> T {
+    while !y.is_zero() {
+        // MIN % -1 overflows, but the remainder is always zero
+        let r = x.checked_rem(&y).unwrap_or_else(T::zero);
+        x = y;
+        y = r;
+    }
+    x
+}
+// Checked greatest common divisor
+#[cfg(not(feature = "unchecked"))]
+fn gcd<T: Display + PrimInt + CheckedRem>(x: T, y: T) -> Result<T, Box<EvalAltResult>> {
+    let r = gcd_raw(x, y);
+
+    if r >= T::zero() {
+        return Ok(r);
+    }
+
+    T::zero().checked_sub(&r).ok_or_else(|| {
+        Box::new(EvalAltResult::ErrorArithmetic(
+            format!("Integer overflow: gcd({}, {})", x, y),
+            Position::none(),
+        ))
+    })
+}
+// Checked least common multiple
+#[cfg(not(feature = "unchecked"))]
+fn lcm<T: Display + PrimInt + CheckedRem>(x: T, y: T) -> Result<T, Box<EvalAltResult>> {
+    if x.is_zero() || y.is_zero() {
+        return Ok(T::zero());
+    }
+
+    let err = || {
+        Box::new(EvalAltResult::ErrorArithmetic(
+            format!("Integer overflow: lcm({}, {})", x, y),
+            Position::none(),
+        ))
+    };
+
+    let r = (x / gcd(x, y).map_err(|_| err())?)
+        .checked_mul(&y)
+        .ok_or_else(err)?;
+
+    if r >= T::zero() {
+        Ok(r)
+    } else {
+        T::zero().checked_sub(&r).ok_or_else(err)
+    }
+}
+// Unchecked greatest common divisor - may panic on overflow
+#[cfg(feature = "unchecked")]
+fn gcd_u<T: PrimInt + CheckedRem>(x: T, y: T) -> T {
+    let r = gcd_raw(x, y);
+
+    if r < T::zero() {
+        T::zero() - r
+    } else {
+        r
+    }
+}
+// Unchecked least common multiple - may panic on overflow
+#[cfg(feature = "unchecked")]
+fn lcm_u<T: PrimInt + CheckedRem>(x: T, y: T) -> T {
+    if x.is_zero() || y.is_zero() {
+        return T::zero();
+    }
+
+    let r = x / gcd_u(x, y) * y;
+
+    if r < T::zero() {
+        T::zero() - r
+    } else {
+        r
+    }
+}
+// Integer square root, rounded down
+fn sqrt_int(x: INT) -> Result<INT, Box<EvalAltResult>> {
+    if x < 0 {
+        return Err(Box::new(EvalAltResult::ErrorArithmetic(
+            format!("Square root of a negative number: {}", x),
+            Position::none(),
+        )));
+    } else if x < 2 {
+        return Ok(x);
+    }
+
+    // Newton's method, starting from above the root
+    let mut r = x / 2 + 1;
+    let mut next = (r + x / r) / 2;
+
+    while next < r {
+        r = next;
+        next = (r + x / r) / 2;
+    }
+
+    Ok(r)
+}
+fn count_ones<T: PrimInt>(x: T) -> INT {
+    x.count_ones() as INT
+}
+fn leading_zeros<T: PrimInt>(x: T) -> INT {
+    x.leading_zeros() as INT
+}
+fn trailing_zeros<T: PrimInt>(x: T) -> INT {
+    x.trailing_zeros() as INT
+}
+// Rotations wrap around, so a negative amount rotates the other way
+fn rotate_left<T: PrimInt>(x: T, n: INT) -> T {
+    x.rotate_left(n.rem_euclid(bits::<T>()) as u32)
+}
+fn rotate_right<T: PrimInt>(x: T, n: INT) -> T {
+    x.rotate_right(n.rem_euclid(bits::<T>()) as u32)
+}
+fn check_bit<T: PrimInt>(bit: INT) -> Result<usize, Box<EvalAltResult>> {
+    if bit < 0 || bit >= bits::<T>() {
+        Err(Box::new(EvalAltResult::ErrorArithmetic(
+            format!(
+                "Bit index {} out of range for a {}-bit integer",
+                bit,
+                bits::<T>()
+            ),
+            Position::none(),
+        )))
+    } else {
+        Ok(bit as usize)
+    }
+}
+fn get_bit<T: PrimInt>(x: T, bit: INT) -> Result<bool, Box<EvalAltResult>> {
+    let bit = check_bit::<T>(bit)?;
+    Ok(!((x >> bit) & T::one()).is_zero())
+}
+fn set_bit<T: PrimInt>(x: T, bit: INT, value: bool) -> Result<T, Box<EvalAltResult>> {
+    let mask = T::one() << check_bit::<T>(bit)?;
+    Ok(if value { x | mask } else { x & !mask })
+}
+fn get_bits<T: PrimInt>(x: T, start: INT, len: INT) -> Result<T, Box<EvalAltResult>> {
+    let size = bits::<T>();
+
+    if start < 0 || len < 0 || start > size - len {
+        return Err(Box::new(EvalAltResult::ErrorArithmetic(
+            format!(
+                "Bit field of {} bits at index {} out of range for a {}-bit integer",
+                len, start, size
+            ),
+            Position::none(),
+        )));
+    } else if len == 0 {
+        return Ok(T::zero());
+    }
+
+    let mask = if len == size {
+        !T::zero()
+    } else {
+        !(!T::zero() << len as usize)
+    };
+
+    Ok((x >> start as usize) & mask)
+}
+
+macro_rules! reg_unary { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
+    $(reg_unary($lib, $op, $func::<$par>, map);)* };
+}
+macro_rules! reg_op { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
+    $(reg_binary($lib, $op, $func::<$par>, map);)* };
+}
+macro_rules! reg_op_x { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
+    $(reg_binary($lib, $op, $func::<$par>, result);)* };
+}
+macro_rules! reg_tri_x { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
+    $(reg_trinary($lib, $op, $func::<$par>, result);)* };
+}
+
 def_package!(crate:BasicMathPackage:"Basic mathematic functions.", lib, {
     #[cfg(not(feature = "no_float"))]
     {
@@ -128,4 +358,91 @@ def_package!(crate:BasicMathPackage:"Basic mathematic functions.", lib, {
             reg_unary(lib, "to_int", |x: f64| x as INT, map);
         }
     }
+
+    // Minimum, maximum and clamping
+    reg_op!(lib, "min", min, INT);
+    reg_op!(lib, "max", max, INT);
+    reg_tri_x!(lib, "clamp", clamp, INT);
+    reg_unary!(lib, "sign", sign, INT);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        reg_op!(lib, "min", min, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_op!(lib, "max", max, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_tri_x!(lib, "clamp", clamp, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_unary!(lib, "sign", sign, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        reg_op!(lib, "min", min, f32, f64);
+        reg_op!(lib, "max", max, f32, f64);
+        reg_tri_x!(lib, "clamp", clamp, f32, f64);
+        reg_unary!(lib, "sign", sign, f32, f64);
+    }
+
+    // Checked power, greatest common divisor and least common multiple
+    #[cfg(not(feature = "unchecked"))]
+    {
+        reg_binary(lib, "pow", pow_i_i, result);
+        reg_op_x!(lib, "gcd", gcd, INT);
+        reg_op_x!(lib, "lcm", lcm, INT);
+
+        #[cfg(not(feature = "no_float"))]
+        reg_binary(lib, "pow", pow_f_i, result);
+
+        #[cfg(not(feature = "only_i32"))]
+        #[cfg(not(feature = "only_i64"))]
+        {
+            reg_op_x!(lib, "gcd", gcd, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+            reg_op_x!(lib, "lcm", lcm, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        }
+    }
+
+    // Unchecked power, greatest common divisor and least common multiple
+    #[cfg(feature = "unchecked")]
+    {
+        reg_binary(lib, "pow", pow_i_i_u, map);
+        reg_op!(lib, "gcd", gcd_u, INT);
+        reg_op!(lib, "lcm", lcm_u, INT);
+
+        #[cfg(not(feature = "no_float"))]
+        reg_binary(lib, "pow", pow_f_i_u, map);
+
+        #[cfg(not(feature = "only_i32"))]
+        #[cfg(not(feature = "only_i64"))]
+        {
+            reg_op!(lib, "gcd", gcd_u, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+            reg_op!(lib, "lcm", lcm_u, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        }
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    reg_binary(lib, "pow", pow_f_f, map);
+
+    reg_unary(lib, "sqrt_int", sqrt_int, result);
+
+    // Bit manipulation
+    reg_unary!(lib, "count_ones", count_ones, INT);
+    reg_unary!(lib, "leading_zeros", leading_zeros, INT);
+    reg_unary!(lib, "trailing_zeros", trailing_zeros, INT);
+    reg_op!(lib, "rotate_left", rotate_left, INT);
+    reg_op!(lib, "rotate_right", rotate_right, INT);
+    reg_op_x!(lib, "get_bit", get_bit, INT);
+    reg_tri_x!(lib, "set_bit", set_bit, INT);
+    reg_tri_x!(lib, "get_bits", get_bits, INT);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        reg_unary!(lib, "count_ones", count_ones, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_unary!(lib, "leading_zeros", leading_zeros, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_unary!(lib, "trailing_zeros", trailing_zeros, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_op!(lib, "rotate_left", rotate_left, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_op!(lib, "rotate_right", rotate_right, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_op_x!(lib, "get_bit", get_bit, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_tri_x!(lib, "set_bit", set_bit, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+        reg_tri_x!(lib, "get_bits", get_bits, i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+    }
 });
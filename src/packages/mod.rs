@@ -2,16 +2,21 @@
 
 use crate::engine::{FnAny, IteratorFn};
 
-use crate::stdlib::{any::TypeId, boxed::Box, collections::HashMap, rc::Rc, sync::Arc};
+use crate::stdlib::{
+    any::TypeId, boxed::Box, collections::HashMap, rc::Rc, string::String, sync::Arc, vec::Vec,
+};
 
 mod arithmetic;
 mod array_basic;
+mod datetime_basic;
+mod format;
 mod iter_basic;
 mod logic;
 mod map_basic;
 mod math_basic;
 mod pkg_core;
 mod pkg_std;
+mod random;
 mod string_basic;
 mod string_more;
 mod time_basic;
@@ -20,6 +25,7 @@ mod utils;
 pub use arithmetic::ArithmeticPackage;
 #[cfg(not(feature = "no_index"))]
 pub use array_basic::BasicArrayPackage;
+pub use datetime_basic::BasicDateTimePackage;
 pub use iter_basic::BasicIteratorPackage;
 pub use logic::LogicPackage;
 #[cfg(not(feature = "no_object"))]
@@ -27,6 +33,7 @@ pub use map_basic::BasicMapPackage;
 pub use math_basic::BasicMathPackage;
 pub use pkg_core::CorePackage;
 pub use pkg_std::StandardPackage;
+pub use random::RandomPackage;
 pub use string_basic::BasicStringPackage;
 pub use string_more::MoreStringPackage;
 #[cfg(not(feature = "no_std"))]
@@ -34,6 +41,18 @@ pub use time_basic::BasicTimePackage;
 
 pub use utils::*;
 
+/// Maximum size, in bytes, of a string built by a built-in function such as `repeat` or `pad`.
+pub const MAX_STRING_SIZE: usize = 16 * 1024 * 1024;
+
+/// Maximum number of elements of an array built by a built-in function such as `pad`.
+pub const MAX_ARRAY_SIZE: usize = 1024 * 1024;
+
+/// Maximum number of values passed to `format` after the format string.
+pub const MAX_FORMAT_ARGS: usize = 16;
+
+/// Maximum width and precision in a `format` specification.
+pub const MAX_FORMAT_WIDTH: usize = 1024;
+
 /// Trait that all packages must implement.
 pub trait Package {
     /// Create a new instance of a package.
@@ -53,6 +72,10 @@ pub struct PackageStore {
 
     /// All iterator functions, keyed by the type producing the iterator.
     pub type_iterators: HashMap<TypeId, Box<IteratorFn>>,
+
+    /// Parameter types of the functions taking `Dynamic` parameters, keyed by function name and
+    /// number of parameters.
+    pub dynamic_fns: HashMap<(String, usize), Vec<Vec<TypeId>>>,
 }
 
 impl PackageStore {
@@ -61,6 +84,7 @@ impl PackageStore {
         Self {
             functions: HashMap::new(),
             type_iterators: HashMap::new(),
+            dynamic_fns: HashMap::new(),
         }
     }
 }
@@ -1,5 +1,6 @@
 #[cfg(not(feature = "no_index"))]
 use super::array_basic::BasicArrayPackage;
+use super::datetime_basic::BasicDateTimePackage;
 #[cfg(not(feature = "no_object"))]
 use super::map_basic::BasicMapPackage;
 use super::math_basic::BasicMathPackage;
@@ -19,5 +20,6 @@ def_package!(crate:StandardPackage:"_Standard_ package containing all built-in f
     BasicMapPackage::init(lib);
     #[cfg(not(feature = "no_std"))]
     BasicTimePackage::init(lib);
+    BasicDateTimePackage::init(lib);
     MoreStringPackage::init(lib);
 });
@@ -0,0 +1,234 @@
+use super::{reg_binary, reg_none, Package, PackageLibrary, PackageStore};
+
+use crate::fn_register::{map_dynamic as map, map_result as result};
+use crate::parser::INT;
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+#[cfg(not(feature = "no_index"))]
+use super::{reg_binary_mut, reg_unary_mut};
+#[cfg(not(feature = "no_index"))]
+use crate::any::Dynamic;
+#[cfg(not(feature = "no_index"))]
+use crate::engine::Array;
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
+
+use crate::stdlib::{boxed::Box, format, sync::Arc};
+
+#[cfg(not(feature = "no_index"))]
+use crate::stdlib::vec::Vec;
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(not(feature = "no_std"))]
+use crate::stdlib::time::{SystemTime, UNIX_EPOCH};
+
+/// Increment of the SplitMix64 state for each number generated.
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Seed used when no seed is specified and there is no clock to derive one from.
+const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// A SplitMix64 pseudo-random number generator, shared by all the functions of a package.
+///
+/// SplitMix64 is small and fast and passes the usual statistical test suites, but it is _not_
+/// cryptographically secure.
+#[derive(Debug, Clone)]
+struct Rng(Arc<AtomicU64>);
+
+impl Rng {
+    /// Create a new generator starting from a seed.
+    fn new(seed: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(seed)))
+    }
+
+    /// Generate the next 64 random bits.
+    fn next_u64(&self) -> u64 {
+        let mut z = self
+            .0
+            .fetch_add(GAMMA, Ordering::Relaxed)
+            .wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a uniformly-distributed number in the range `0..n`, or any number if `n` is zero.
+    fn below(&self, n: u64) -> u64 {
+        if n == 0 {
+            return self.next_u64();
+        }
+
+        // Reject the incomplete last block of `n` numbers to avoid a bias towards small numbers
+        let zone = u64::MAX - (u64::MAX - n + 1) % n;
+
+        loop {
+            let x = self.next_u64();
+
+            if x <= zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// Generate a uniformly-distributed integer in the inclusive range `lo..=hi`.
+    fn int_between(&self, lo: INT, hi: INT) -> Result<INT, Box<EvalAltResult>> {
+        if lo > hi {
+            return Err(Box::new(EvalAltResult::ErrorArithmetic(
+                format!("Invalid range for random number: {} > {}", lo, hi),
+                Position::none(),
+            )));
+        }
+
+        // The size of the range truncates to zero if it covers all 64-bit integers
+        let size = (hi as i128 - lo as i128 + 1) as u64;
+
+        Ok((lo as i128 + self.below(size) as i128) as INT)
+    }
+
+    /// Generate a uniformly-distributed floating-point number in the range `[0, 1)`.
+    #[cfg(not(feature = "no_float"))]
+    fn float(&self) -> FLOAT {
+        (self.next_u64() >> 11) as FLOAT / (1u64 << 53) as FLOAT
+    }
+
+    /// Shuffle an array in place (Fisher-Yates).
+    #[cfg(not(feature = "no_index"))]
+    fn shuffle(&self, list: &mut Array) {
+        for i in (1..list.len()).rev() {
+            list.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+
+    /// Pick `n` distinct elements of an array at random, in random order.
+    #[cfg(not(feature = "no_index"))]
+    fn sample(&self, list: &Array, n: INT) -> Array {
+        let n = if n < 0 {
+            0
+        } else {
+            (n as usize).min(list.len())
+        };
+        let mut indices: Vec<usize> = (0..list.len()).collect();
+
+        // Partial Fisher-Yates - only the first `n` positions are needed
+        for i in 0..n {
+            indices.swap(i, i + self.below((list.len() - i) as u64) as usize);
+        }
+
+        indices[..n].iter().map(|&i| list[i].clone()).collect()
+    }
+}
+
+/// Derive a seed from the system clock, if available.
+fn default_seed() -> u64 {
+    #[cfg(not(feature = "no_std"))]
+    {
+        if let Ok(time) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            return time.as_nanos() as u64 ^ DEFAULT_SEED;
+        }
+    }
+
+    DEFAULT_SEED
+}
+
+/// Register all the random-number functions, all sharing the same generator.
+fn init_with_rng(lib: &mut PackageStore, rng: Rng) {
+    let r = rng.clone();
+    reg_none(lib, "rand", move || r.next_u64() as INT, map);
+
+    let r = rng.clone();
+    reg_binary(
+        lib,
+        "rand_int",
+        move |lo: INT, hi: INT| r.int_between(lo, hi),
+        result,
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        let r = rng.clone();
+        reg_none(lib, "rand_float", move || r.float(), map);
+
+        let r = rng.clone();
+        reg_binary(
+            lib,
+            "rand_float",
+            move |lo: FLOAT, hi: FLOAT| lo + (hi - lo) * r.float(),
+            map,
+        );
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let r = rng.clone();
+        reg_unary_mut(lib, "shuffle", move |list: &mut Array| r.shuffle(list), map);
+
+        let r = rng.clone();
+        reg_binary_mut(
+            lib,
+            "sample",
+            move |list: &mut Array, n: INT| r.sample(list, n),
+            map,
+        );
+
+        reg_unary_mut(
+            lib,
+            "sample",
+            move |list: &mut Array| match list.len() {
+                0 => Dynamic::from(()),
+                len => list[rng.below(len as u64) as usize].clone(),
+            },
+            map,
+        );
+    }
+}
+
+/// Package of pseudo-random number functions: `rand`, `rand_int`, `rand_float`, `shuffle` and `sample`.
+///
+/// All the functions draw from a single generator owned by the package.  Create the package with
+/// `RandomPackage::with_seed` to get the same sequence of numbers on every run, or with
+/// `RandomPackage::new` to seed it from the system clock (or a fixed seed under `no_std`).
+///
+/// The generator is _not_ cryptographically secure.
+///
+/// # Example
+///
+/// ```
+/// use rhai::{Engine, EvalAltResult, INT};
+/// use rhai::packages::{Package, RandomPackage};
+///
+/// let mut engine = Engine::new();
+/// engine.load_package(RandomPackage::with_seed(42).get());
+///
+/// let x = engine.eval::<INT>("rand_int(1, 6)")?;
+/// assert!(x >= 1 && x <= 6);
+/// # Ok::<(), Box<EvalAltResult>>(())
+/// ```
+pub struct RandomPackage(PackageLibrary);
+
+impl RandomPackage {
+    /// Create a new `RandomPackage` whose generator starts from a particular seed.
+    ///
+    /// Packages created with the same seed produce the same sequence of random numbers.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut lib = PackageStore::new();
+        init_with_rng(&mut lib, Rng::new(seed));
+        Self(lib.into())
+    }
+}
+
+impl Package for RandomPackage {
+    fn new() -> Self {
+        Self::with_seed(default_seed())
+    }
+
+    fn get(&self) -> PackageLibrary {
+        self.0.clone()
+    }
+
+    fn init(lib: &mut PackageStore) {
+        init_with_rng(lib, Rng::new(default_seed()));
+    }
+}
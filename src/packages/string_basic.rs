@@ -2,6 +2,7 @@ use super::{reg_binary, reg_binary_mut, reg_none, reg_unary, reg_unary_mut};
 
 use crate::def_package;
 use crate::engine::{Array, Map, FUNC_TO_STRING, KEYWORD_DEBUG, KEYWORD_PRINT};
+use crate::fn_native::FnPtr;
 use crate::fn_register::map_dynamic as map;
 use crate::parser::INT;
 
@@ -27,8 +28,8 @@ macro_rules! reg_op { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
 }
 
 def_package!(crate:BasicStringPackage:"Basic string utilities, including printing.", lib, {
-    reg_op!(lib, KEYWORD_PRINT, to_string, INT, bool, char);
-    reg_op!(lib, FUNC_TO_STRING, to_string, INT, bool, char);
+    reg_op!(lib, KEYWORD_PRINT, to_string, INT, bool, char, FnPtr);
+    reg_op!(lib, FUNC_TO_STRING, to_string, INT, bool, char, FnPtr);
 
     reg_none(lib, KEYWORD_PRINT, || "".to_string(), map);
     reg_unary(lib, KEYWORD_PRINT, |_: ()| "".to_string(), map);
@@ -38,6 +39,7 @@ def_package!(crate:BasicStringPackage:"Basic string utilities, including printin
     reg_unary_mut(lib, FUNC_TO_STRING, |s: &mut String| s.clone(), map);
 
     reg_op!(lib, KEYWORD_DEBUG, to_debug, INT, bool, (), char, String);
+    reg_op!(lib, KEYWORD_DEBUG, to_string, FnPtr);
 
     #[cfg(not(feature = "only_i32"))]
     #[cfg(not(feature = "only_i64"))]
@@ -1,17 +1,42 @@
-use super::{reg_binary, reg_binary_mut, reg_trinary_mut, reg_unary_mut};
+use super::format::format_string;
+use super::{
+    reg_binary, reg_binary_mut, reg_raw, reg_trinary_mut, reg_unary, reg_unary_mut,
+    MAX_FORMAT_ARGS, MAX_STRING_SIZE,
+};
 
+use crate::any::Dynamic;
 use crate::def_package;
 use crate::engine::Array;
-use crate::fn_register::map_dynamic as map;
+use crate::fn_native::FnPtr;
+use crate::fn_register::{map_dynamic as map, map_result as result};
 use crate::parser::INT;
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+#[cfg(not(feature = "no_index"))]
+use crate::any::Union;
+#[cfg(not(feature = "no_index"))]
+use crate::engine::FUNC_TO_STRING;
+#[cfg(not(feature = "no_index"))]
+use crate::fn_native::NativeCallContext;
+#[cfg(not(feature = "no_index"))]
+use crate::fn_register::map_identity as pass;
+
+#[cfg(not(feature = "no_float"))]
+use crate::parser::FLOAT;
 
 use crate::stdlib::{
-    fmt::Display,
+    any::TypeId,
+    boxed::Box,
+    fmt::{Binary, Display, LowerHex, Octal},
     format,
     string::{String, ToString},
     vec::Vec,
 };
 
+#[cfg(not(feature = "no_index"))]
+use crate::stdlib::vec;
+
 fn prepend<T: Display>(x: T, y: String) -> String {
     format!("{}{}", x, y)
 }
@@ -67,15 +92,161 @@ fn crop_string(s: &mut String, start: INT, len: INT) {
         .for_each(|&ch| s.push(ch));
 }
 
+/// Make an error for a string that would grow beyond `MAX_STRING_SIZE`.
+fn string_too_large() -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorArithmetic(
+        format!(
+            "String exceeds the maximum size of {} bytes",
+            MAX_STRING_SIZE
+        ),
+        Position::none(),
+    ))
+}
+fn pad_string(s: &mut String, len: INT, padding: &str) -> Result<(), Box<EvalAltResult>> {
+    if len <= 0 || padding.is_empty() {
+        return Ok(());
+    }
+
+    let mut count = s.chars().count();
+
+    if count >= len as usize {
+        return Ok(());
+    }
+
+    // Make sure the padded string cannot be too large
+    let char_size = padding.chars().map(char::len_utf8).max().unwrap();
+
+    (len as usize - count)
+        .checked_mul(char_size)
+        .and_then(|size| size.checked_add(s.len()))
+        .filter(|&size| size <= MAX_STRING_SIZE)
+        .ok_or_else(string_too_large)?;
+
+    while count < len as usize {
+        for ch in padding.chars().take(len as usize - count) {
+            s.push(ch);
+            count += 1;
+        }
+    }
+
+    Ok(())
+}
+fn repeat(s: &str, count: INT) -> Result<String, Box<EvalAltResult>> {
+    let count = count.max(0) as usize;
+
+    s.len()
+        .checked_mul(count)
+        .filter(|&size| size <= MAX_STRING_SIZE)
+        .ok_or_else(string_too_large)?;
+
+    Ok(s.repeat(count))
+}
+fn single_char(mut chars: impl Iterator<Item = char>, ch: char) -> char {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ => ch,
+    }
+}
+#[cfg(not(feature = "no_index"))]
+fn to_array<T: Into<Dynamic>>(iter: impl Iterator<Item = T>) -> Array {
+    iter.map(Into::into).collect()
+}
+#[cfg(not(feature = "no_index"))]
+fn split_once(s: &str, pos: Option<(usize, usize)>) -> Dynamic {
+    match pos {
+        Some((index, len)) => {
+            let list: Array = vec![
+                s[..index].to_string().into(),
+                s[index + len..].to_string().into(),
+            ];
+            list.into()
+        }
+        None => ().into(),
+    }
+}
+#[cfg(not(feature = "no_index"))]
+fn join(
+    context: &NativeCallContext,
+    list: &mut Array,
+    sep: &str,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let mut s = String::new();
+
+    for (index, item) in list.iter_mut().enumerate() {
+        if index > 0 {
+            s.push_str(sep);
+        }
+
+        match &item.0 {
+            Union::Str(value) => s.push_str(value),
+            Union::Char(ch) => s.push(*ch),
+            _ => {
+                let value = context.call_fn(FUNC_TO_STRING, &mut [item])?;
+                let type_name = context.engine().map_type_name(value.type_name());
+
+                match value.0 {
+                    Union::Str(value) => s.push_str(&value),
+                    _ => {
+                        return Err(Box::new(EvalAltResult::ErrorMismatchDataType(
+                            "string".into(),
+                            type_name.into(),
+                            Position::none(),
+                        )))
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(s.into())
+}
+fn parse_int(s: &str, radix: INT) -> Result<INT, Box<EvalAltResult>> {
+    if !(2..=36).contains(&radix) {
+        return Err(Box::new(EvalAltResult::ErrorArithmetic(
+            format!("Invalid radix for parse_int: {}", radix),
+            Position::none(),
+        )));
+    }
+
+    INT::from_str_radix(s.trim(), radix as u32).map_err(|err| {
+        Box::new(EvalAltResult::ErrorArithmetic(
+            format!("Cannot parse '{}' as an integer: {}", s, err),
+            Position::none(),
+        ))
+    })
+}
+#[cfg(not(feature = "no_float"))]
+fn parse_float(s: &str) -> Result<FLOAT, Box<EvalAltResult>> {
+    s.trim().parse::<FLOAT>().map_err(|err| {
+        Box::new(EvalAltResult::ErrorArithmetic(
+            format!("Cannot parse '{}' as a floating-point number: {}", s, err),
+            Position::none(),
+        ))
+    })
+}
+
+fn to_hex<T: LowerHex>(x: T) -> String {
+    format!("{:x}", x)
+}
+fn to_binary<T: Binary>(x: T) -> String {
+    format!("{:b}", x)
+}
+fn to_octal<T: Octal>(x: T) -> String {
+    format!("{:o}", x)
+}
+
 macro_rules! reg_op { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
     $(reg_binary($lib, $op, $func::<$par>, map);)* };
 }
+macro_rules! reg_conv { ($lib:expr, $op:expr, $func:ident, $($par:ty),*) => {
+    $(reg_unary($lib, $op, $func::<$par>, map);)* };
+}
 
 def_package!(crate:MoreStringPackage:"Additional string utilities, including string building.", lib, {
-    reg_op!(lib, "+", append, INT, bool, char);
+    reg_op!(lib, "+", append, INT, bool, char, FnPtr);
     reg_binary_mut(lib, "+", |x: &mut String, _: ()| x.clone(), map);
 
-    reg_op!(lib, "+", prepend, INT, bool, char);
+    reg_op!(lib, "+", prepend, INT, bool, char, FnPtr);
     reg_binary(lib, "+", |_: (), y: String| y, map);
 
     #[cfg(not(feature = "only_i32"))]
@@ -97,6 +268,31 @@ def_package!(crate:MoreStringPackage:"Additional string utilities, including str
         reg_binary(lib, "+", |x: Array, y: String| format!("{:?}{}", x, y), map);
     }
 
+    // Register `format` taking a format string followed by values of any type
+    for num_values in 0..=MAX_FORMAT_ARGS {
+        let arg_types: Vec<_> = (0..=num_values)
+            .map(|i| if i == 0 { TypeId::of::<String>() } else { TypeId::of::<Dynamic>() })
+            .collect();
+
+        reg_raw(lib, "format", &arg_types, |context, args| {
+            let fmt = args[0].as_str().unwrap().to_string();
+            let mut values: Vec<_> = args[1..].iter().map(|value| (*value).clone()).collect();
+            format_string(context, &fmt, &mut values).map(Into::into)
+        });
+    }
+
+    reg_conv!(lib, "to_hex", to_hex, INT);
+    reg_conv!(lib, "to_binary", to_binary, INT);
+    reg_conv!(lib, "to_octal", to_octal, INT);
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        reg_conv!(lib, "to_hex", to_hex, i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
+        reg_conv!(lib, "to_binary", to_binary, i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
+        reg_conv!(lib, "to_octal", to_octal, i8, u8, i16, u16, i32, i64, u32, u64, i128, u128);
+    }
+
     reg_unary_mut(lib, "len", |s: &mut String| s.chars().count() as INT, map);
     reg_binary_mut(
         lib,
@@ -207,12 +403,20 @@ def_package!(crate:MoreStringPackage:"Additional string utilities, including str
     reg_trinary_mut(
         lib,
         "pad",
-        |s: &mut String, len: INT, ch: char| {
-            for _ in 0..s.chars().count() - len as usize {
-                s.push(ch);
-            }
-        },
-        map,
+        |s: &mut String, len: INT, ch: char| pad_string(s, len, ch.encode_utf8(&mut [0; 4])),
+        result,
+    );
+    reg_trinary_mut(
+        lib,
+        "pad",
+        |s: &mut String, len: INT, padding: String| pad_string(s, len, &padding),
+        result,
+    );
+    reg_binary_mut(
+        lib,
+        "repeat",
+        |s: &mut String, count: INT| repeat(s, count),
+        result,
     );
     reg_trinary_mut(
         lib,
@@ -236,4 +440,81 @@ def_package!(crate:MoreStringPackage:"Additional string utilities, including str
         },
         map,
     );
+    reg_unary_mut(
+        lib,
+        "trim_start",
+        |s: &mut String| {
+            let trimmed = s.trim_start();
+
+            if trimmed.len() < s.len() {
+                *s = trimmed.to_string();
+            }
+        },
+        map,
+    );
+    reg_unary_mut(
+        lib,
+        "trim_end",
+        |s: &mut String| {
+            let trimmed = s.trim_end();
+
+            if trimmed.len() < s.len() {
+                *s = trimmed.to_string();
+            }
+        },
+        map,
+    );
+
+    // Case conversion returns a new string - the upper/lower case of a character may be more than one character
+    reg_unary_mut(lib, "to_upper", |s: &mut String| s.to_uppercase(), map);
+    reg_unary_mut(lib, "to_lower", |s: &mut String| s.to_lowercase(), map);
+    reg_unary_mut(lib, "to_upper", |ch: &mut char| single_char(ch.to_uppercase(), *ch), map);
+    reg_unary_mut(lib, "to_lower", |ch: &mut char| single_char(ch.to_lowercase(), *ch), map);
+
+    reg_binary_mut(lib, "starts_with", |s: &mut String, find: String| s.starts_with(&find), map);
+    reg_binary_mut(lib, "starts_with", |s: &mut String, ch: char| s.starts_with(ch), map);
+    reg_binary_mut(lib, "ends_with", |s: &mut String, find: String| s.ends_with(&find), map);
+    reg_binary_mut(lib, "ends_with", |s: &mut String, ch: char| s.ends_with(ch), map);
+
+    reg_unary_mut(lib, "parse_int", |s: &mut String| parse_int(s, 10), result);
+    reg_binary_mut(lib, "parse_int", |s: &mut String, radix: INT| parse_int(s, radix), result);
+
+    #[cfg(not(feature = "no_float"))]
+    reg_unary_mut(lib, "parse_float", |s: &mut String| parse_float(s), result);
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        reg_unary_mut(lib, "chars", |s: &mut String| to_array(s.chars()), map);
+        reg_unary_mut(lib, "bytes", |s: &mut String| to_array(s.bytes().map(|b| b as INT)), map);
+        reg_unary_mut(lib, "lines", |s: &mut String| to_array(s.lines().map(ToString::to_string)), map);
+
+        reg_binary_mut(lib, "split", |s: &mut String, sep: String| to_array(s.split(sep.as_str()).map(ToString::to_string)), map);
+        reg_binary_mut(lib, "split", |s: &mut String, sep: char| to_array(s.split(sep).map(ToString::to_string)), map);
+        reg_trinary_mut(lib, "split", |s: &mut String, sep: String, segments: INT| to_array(s.splitn(segments.max(1) as usize, sep.as_str()).map(ToString::to_string)), map);
+        reg_trinary_mut(lib, "split", |s: &mut String, sep: char, segments: INT| to_array(s.splitn(segments.max(1) as usize, sep).map(ToString::to_string)), map);
+        reg_binary_mut(lib, "rsplit", |s: &mut String, sep: String| to_array(s.rsplit(sep.as_str()).map(ToString::to_string)), map);
+        reg_binary_mut(lib, "rsplit", |s: &mut String, sep: char| to_array(s.rsplit(sep).map(ToString::to_string)), map);
+        reg_trinary_mut(lib, "rsplit", |s: &mut String, sep: String, segments: INT| to_array(s.rsplitn(segments.max(1) as usize, sep.as_str()).map(ToString::to_string)), map);
+        reg_trinary_mut(lib, "rsplit", |s: &mut String, sep: char, segments: INT| to_array(s.rsplitn(segments.max(1) as usize, sep).map(ToString::to_string)), map);
+        reg_binary_mut(lib, "split_once", |s: &mut String, sep: String| split_once(s, s.find(sep.as_str()).map(|index| (index, sep.len()))), pass);
+        reg_binary_mut(lib, "split_once", |s: &mut String, sep: char| split_once(s, s.find(sep).map(|index| (index, sep.len_utf8()))), pass);
+
+        reg_raw(lib, "join", &[TypeId::of::<Array>(), TypeId::of::<String>()], |context, args| {
+            let sep = args[1].as_str().unwrap().to_string();
+            join(context, args[0].downcast_mut::<Array>().unwrap(), &sep)
+        });
+        reg_raw(lib, "join", &[TypeId::of::<Array>(), TypeId::of::<char>()], |context, args| {
+            let sep = args[1].as_char().unwrap().to_string();
+            join(context, args[0].downcast_mut::<Array>().unwrap(), &sep)
+        });
+    }
+
+    // Register string iterator
+    lib.type_iterators.insert(
+        TypeId::of::<String>(),
+        Box::new(|s: Dynamic| {
+            Box::new(s.cast::<String>().chars().collect::<Vec<_>>().into_iter().map(Into::into))
+                as Box<dyn Iterator<Item = Dynamic>>
+        }),
+    );
 });
@@ -3,6 +3,7 @@ use super::PackageStore;
 use crate::any::{Dynamic, Variant};
 use crate::calc_fn_hash;
 use crate::engine::FnCallArgs;
+use crate::fn_native::NativeCallContext;
 use crate::result::EvalAltResult;
 use crate::token::Position;
 
@@ -116,7 +117,8 @@ pub fn reg_none<R>(
 ) {
     let hash = calc_fn_hash(fn_name, ([] as [TypeId; 0]).iter().cloned());
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 0, args, pos)?;
 
         let r = func();
@@ -166,7 +168,8 @@ pub fn reg_unary<T: Variant + Clone, R>(
 
     let hash = calc_fn_hash(fn_name, [TypeId::of::<T>()].iter().cloned());
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 1, args, pos)?;
 
         let mut drain = args.iter_mut();
@@ -226,7 +229,8 @@ pub fn reg_unary_mut<T: Variant + Clone, R>(
 
     let hash = calc_fn_hash(fn_name, [TypeId::of::<T>()].iter().cloned());
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 1, args, pos)?;
 
         let mut drain = args.iter_mut();
@@ -263,7 +267,8 @@ pub(crate) fn reg_test<'a, A: Variant + Clone, B: Variant + Clone, X, R>(
         [TypeId::of::<A>(), TypeId::of::<B>()].iter().cloned(),
     );
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 2, args, pos)?;
 
         let mut drain = args.iter_mut();
@@ -320,7 +325,8 @@ pub fn reg_binary<A: Variant + Clone, B: Variant + Clone, R>(
         [TypeId::of::<A>(), TypeId::of::<B>()].iter().cloned(),
     );
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 2, args, pos)?;
 
         let mut drain = args.iter_mut();
@@ -384,7 +390,8 @@ pub fn reg_binary_mut<A: Variant + Clone, B: Variant + Clone, R>(
         [TypeId::of::<A>(), TypeId::of::<B>()].iter().cloned(),
     );
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 2, args, pos)?;
 
         let mut drain = args.iter_mut();
@@ -424,7 +431,8 @@ pub fn reg_trinary<A: Variant + Clone, B: Variant + Clone, C: Variant + Clone, R
             .cloned(),
     );
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 3, args, pos)?;
 
         let mut drain = args.iter_mut();
@@ -465,7 +473,8 @@ pub fn reg_trinary_mut<A: Variant + Clone, B: Variant + Clone, C: Variant + Clon
             .cloned(),
     );
 
-    let f = Box::new(move |args: &mut FnCallArgs, pos: Position| {
+    let f = Box::new(move |context: &NativeCallContext, args: &mut FnCallArgs| {
+        let pos = context.position();
         check_num_args(fn_name, 3, args, pos)?;
 
         let mut drain = args.iter_mut();
@@ -479,3 +488,51 @@ pub fn reg_trinary_mut<A: Variant + Clone, B: Variant + Clone, C: Variant + Clon
 
     lib.functions.insert(hash, f);
 }
+
+/// Add a function taking the `NativeCallContext` and its arguments as `Dynamic` values to the package.
+///
+/// The arguments are guaranteed to match `arg_types` in number and type.  Use this for functions
+/// which need to call back into the script, e.g. to invoke a function pointer passed in.
+///
+/// # Examples
+///
+/// ```
+/// use std::any::TypeId;
+/// use rhai::{def_package, FnPtr};
+/// use rhai::packages::reg_raw;
+///
+/// def_package!(rhai:MyPackage:"My super-duper package", lib,
+/// {
+///     // Call a function pointer twice
+///     reg_raw(lib, "twice", &[TypeId::of::<FnPtr>()], |context, args| {
+///         let f = args[0].downcast_ref::<FnPtr>().unwrap();
+///         f.call_dynamic(context, vec![])?;
+///         f.call_dynamic(context, vec![])
+///     });
+/// });
+/// ```
+///
+/// The above defines a package named 'MyPackage' with a single function named 'twice'.
+pub fn reg_raw(
+    lib: &mut PackageStore,
+    fn_name: &'static str,
+    arg_types: &[TypeId],
+
+    #[cfg(not(feature = "sync"))] func: impl Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>
+        + 'static,
+    #[cfg(feature = "sync")] func: impl Fn(&NativeCallContext, &mut FnCallArgs) -> Result<Dynamic, Box<EvalAltResult>>
+        + Send
+        + Sync
+        + 'static,
+) {
+    let hash = calc_fn_hash(fn_name, arg_types.iter().cloned());
+
+    if arg_types.contains(&TypeId::of::<Dynamic>()) && !lib.functions.contains_key(&hash) {
+        lib.dynamic_fns
+            .entry((fn_name.into(), arg_types.len()))
+            .or_default()
+            .push(arg_types.to_vec());
+    }
+
+    lib.functions.insert(hash, Box::new(func));
+}
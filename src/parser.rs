@@ -1,19 +1,22 @@
 //! Main module defining the lexer and parser.
 
 use crate::any::{Dynamic, Union};
-use crate::engine::{calc_fn_def, Engine, FunctionsLib};
+use crate::engine::{
+    calc_fn_def, Engine, FunctionsLib, Map, FN_ANONYMOUS, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CURRY,
+    KEYWORD_THIS,
+};
 use crate::error::{LexError, ParseError, ParseErrorType};
 use crate::optimize::{optimize_into_ast, OptimizationLevel};
 use crate::scope::{EntryType as ScopeEntryType, Scope};
+use crate::syntax::{MARKER_BLOCK, MARKER_EXPR, MARKER_IDENT};
 use crate::token::{Position, Token, TokenIterator};
 
 use crate::stdlib::{
     borrow::Cow,
     boxed::Box,
-    char,
-    collections::HashMap,
-    format,
-    iter::Peekable,
+    char, format,
+    iter::{once, Peekable},
+    mem,
     num::NonZeroUsize,
     ops::Add,
     rc::Rc,
@@ -169,12 +172,69 @@ pub struct FnDef {
     pub name: String,
     /// Names of function parameters.
     pub params: Vec<String>,
+    /// Type annotations of function parameters, `None` for parameters without one.
+    pub param_types: Vec<Option<String>>,
+    /// Default values of function parameters, `None` for parameters without one.
+    pub param_defaults: Vec<Option<Expr>>,
+    /// Is the last parameter a rest parameter that collects all remaining arguments into an array?
+    pub rest_param: bool,
     /// Function body.
     pub body: Box<Stmt>,
     /// Position of the function definition.
     pub pos: Position,
 }
 
+impl FnDef {
+    /// Does this function have the same name and parameter types as another function?
+    pub(crate) fn same_signature(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.param_types == other.param_types
+            && self.rest_param == other.rest_param
+    }
+    /// Number of parameters that are not the rest parameter.
+    pub fn fixed_params(&self) -> usize {
+        if self.rest_param {
+            self.params.len() - 1
+        } else {
+            self.params.len()
+        }
+    }
+    /// Minimum number of arguments required to call this function.
+    pub fn min_params(&self) -> usize {
+        self.fixed_params() - self.param_defaults.iter().filter(|v| v.is_some()).count()
+    }
+    /// Can this function be called with a certain number of arguments?
+    pub fn accepts_args(&self, num_args: usize) -> bool {
+        num_args >= self.min_params() && (self.rest_param || num_args <= self.params.len())
+    }
+    /// Score how well the type annotations of the parameters match the types of the arguments.
+    ///
+    /// `type_of` returns the type name of the argument at a certain position.
+    /// Returns the number of typed parameters that match, or `None` if any typed parameter does not
+    /// match. Parameters without type annotations, or with no argument passed, match anything.
+    pub(crate) fn match_types<'a>(
+        &self,
+        num_args: usize,
+        type_of: impl Fn(usize) -> &'a str,
+    ) -> Option<usize> {
+        self.param_types
+            .iter()
+            .take(num_args)
+            .enumerate()
+            .filter_map(|(i, typ)| typ.as_ref().map(|t| (i, t)))
+            .try_fold(0, |score, (i, typ)| {
+                let name = type_of(i);
+
+                // Custom types also match on the last segment of their full Rust path
+                if name == typ || name.rsplit("::").next() == Some(typ) {
+                    Some(score + 1)
+                } else {
+                    None
+                }
+            })
+    }
+}
+
 /// `return`/`throw` statement.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum ReturnType {
@@ -184,39 +244,158 @@ pub enum ReturnType {
     Exception,
 }
 
-/// A type that encapsulates a local stack with variable names to simulate an actual runtime scope.
-#[derive(Debug, Clone)]
-struct Stack(Vec<String>);
+/// A type that encapsulates the current state of the parser.
+#[derive(Clone)]
+struct ParseState<'e> {
+    /// Reference to the scripting `Engine`.
+    engine: &'e Engine,
+    /// A local stack with variable names to simulate an actual runtime scope.
+    stack: Vec<String>,
+    /// Names of the variables in the external `Scope`, which count as declared.
+    externals: Vec<String>,
+    /// Names of the variables visible in the scopes enclosing a closure, which it can capture.
+    outer: Vec<String>,
+    /// Names of the variables captured by a closure, in order of first use.
+    captures: Vec<String>,
+    /// Functions lifted out of closures.
+    functions: Vec<FnDef>,
+    /// Is the next primary expression a property name after `.` or `?.`, rather than a variable?
+    is_property: bool,
+}
 
-impl Stack {
-    /// Create a new `Stack`.
-    pub fn new() -> Self {
-        Self(Vec::new())
+impl<'e> ParseState<'e> {
+    /// Create a new `ParseState`.
+    pub fn new(engine: &'e Engine) -> Self {
+        Self {
+            engine,
+            stack: Vec::new(),
+            externals: Vec::new(),
+            outer: Vec::new(),
+            captures: Vec::new(),
+            functions: Vec::new(),
+            is_property: false,
+        }
+    }
+    /// Create a new `ParseState` for the global level, where the variables in the `Scope` are visible.
+    pub fn new_with_scope(engine: &'e Engine, scope: &Scope) -> Self {
+        Self {
+            externals: scope.iter().map(|entry| entry.name.to_string()).collect(),
+            ..Self::new(engine)
+        }
     }
-    /// Get the number of variables in the `Stack`.
+    /// Create a new `ParseState` for the body of a closure nested in this one.
+    pub fn new_closure(&self) -> Self {
+        let mut outer = self.outer.clone();
+        outer.extend(self.externals.iter().cloned());
+        outer.extend(self.stack.iter().cloned());
+
+        Self {
+            outer,
+            ..Self::new(self.engine)
+        }
+    }
+    /// Get the number of variables in the stack.
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.stack.len()
     }
-    /// Push (add) a new variable onto the `Stack`.
+    /// Push (add) a new variable onto the stack.
     pub fn push(&mut self, name: String) {
-        self.0.push(name);
+        self.stack.push(name);
     }
     /// Rewind the stack to a previous size.
     pub fn rewind(&mut self, len: usize) {
-        self.0.truncate(len);
+        self.stack.truncate(len);
     }
-    /// Find a variable by name in the `Stack`, searching in reverse.
-    /// The return value is the offset to be deducted from `Stack::len`,
-    /// i.e. the top element of the `Stack` is offset 1.
-    /// Return zero when the variable name is not found in the `Stack`.
+    /// Find a variable by name in the stack, searching in reverse.
+    /// The return value is the offset to be deducted from `ParseState::len`,
+    /// i.e. the top element of the stack is offset 1.
+    /// Return zero when the variable name is not found in the stack.
     pub fn find(&self, name: &str) -> Option<NonZeroUsize> {
-        self.0
+        self.stack
             .iter()
             .rev()
             .enumerate()
             .find(|(_, n)| *n == name)
             .and_then(|(i, _)| NonZeroUsize::new(i + 1))
     }
+    /// Find a variable by name like `find`.  If it is not found but belongs to a scope
+    /// enclosing a closure, the closure captures it.
+    pub fn access_var(&mut self, name: &str) -> Option<NonZeroUsize> {
+        let index = self.find(name);
+
+        if index.is_none()
+            && self.outer.iter().any(|n| n == name)
+            && !self.captures.iter().any(|n| n == name)
+        {
+            self.captures.push(name.to_string());
+            self.externals.push(name.to_string());
+        }
+
+        index
+    }
+}
+
+/// Under strict variables mode, make sure that a variable not found in the stack is declared.
+///
+/// Variables in the external `Scope` count as declared, and so do the variables declared via
+/// `Engine::declare_external_var`.
+fn ensure_variable_declared(
+    name: &str,
+    pos: Position,
+    state: &ParseState,
+) -> Result<(), Box<ParseError>> {
+    if state.engine.strict_variables
+        && name != KEYWORD_THIS
+        && !state.externals.iter().any(|n| n == name)
+        && !state.engine.external_vars.contains(name)
+    {
+        Err(PERR::VariableUndefined(name.into()).into_err(pos))
+    } else {
+        Ok(())
+    }
+}
+
+/// A loop enclosing the statements being parsed, used to check `break` and `continue` statements.
+#[derive(Debug, Clone)]
+struct LoopContext {
+    /// The label of the loop, if any.
+    label: Option<String>,
+    /// Is this a `loop` statement, which can be broken out of with a value?
+    is_loop: bool,
+}
+
+impl LoopContext {
+    /// Enter a new loop nested inside the `loops` already entered.
+    pub fn enter(loops: &[Self], label: &Option<String>, is_loop: bool) -> Vec<Self> {
+        let mut loops = loops.to_vec();
+        loops.push(Self {
+            label: label.clone(),
+            is_loop,
+        });
+        loops
+    }
+}
+
+/// A pattern binding one or more variables in a `let` statement or `for` loop.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// id
+    Variable(String),
+    /// [ id, ... , ..id ] or ( id, ... )
+    Array(Vec<String>, Option<String>),
+    /// #{ property, property: id, ... }
+    Map(Vec<(String, String)>),
+}
+
+impl Pattern {
+    /// Get the names of all the variables bound by this pattern, in order of binding.
+    pub fn variables(&self) -> Vec<&String> {
+        match self {
+            Pattern::Variable(name) => vec![name],
+            Pattern::Array(names, rest) => names.iter().chain(rest.iter()).collect(),
+            Pattern::Map(props) => props.iter().map(|(_, name)| name).collect(),
+        }
+    }
 }
 
 /// A statement.
@@ -226,24 +405,26 @@ pub enum Stmt {
     Noop(Position),
     /// if expr { stmt } else { stmt }
     IfThenElse(Box<Expr>, Box<Stmt>, Option<Box<Stmt>>),
-    /// while expr { stmt }
-    While(Box<Expr>, Box<Stmt>),
-    /// loop { stmt }
-    Loop(Box<Stmt>),
-    /// for id in expr { stmt }
-    For(String, Box<Expr>, Box<Stmt>),
-    /// let id = expr
-    Let(String, Option<Box<Expr>>, Position),
+    /// 'label: while expr { stmt }
+    While(Box<Expr>, Box<Stmt>, Option<String>),
+    /// 'label: do { stmt } while/until expr
+    Do(Box<Stmt>, Box<Expr>, bool, Option<String>),
+    /// 'label: loop { stmt }
+    Loop(Box<Stmt>, Option<String>),
+    /// 'label: for pattern in expr { stmt }
+    For(Pattern, Box<Expr>, Box<Stmt>, Option<String>),
+    /// let pattern = expr
+    Let(Pattern, Option<Box<Expr>>, Position),
     /// const id = expr
     Const(String, Box<Expr>, Position),
     /// { stmt; ... }
     Block(Vec<Stmt>, Position),
     /// { stmt }
     Expr(Box<Expr>),
-    /// continue
-    Continue(Position),
-    /// break
-    Break(Position),
+    /// continue 'label
+    Continue(Option<String>, Position),
+    /// break 'label expr
+    Break(Option<String>, Option<Box<Expr>>, Position),
     /// `return`/`throw`
     ReturnWithVal(Option<Box<Expr>>, ReturnType, Position),
 }
@@ -256,11 +437,14 @@ impl Stmt {
             | Stmt::Let(_, _, pos)
             | Stmt::Const(_, _, pos)
             | Stmt::Block(_, pos)
-            | Stmt::Continue(pos)
-            | Stmt::Break(pos)
+            | Stmt::Continue(_, pos)
+            | Stmt::Break(_, _, pos)
             | Stmt::ReturnWithVal(_, _, pos) => *pos,
             Stmt::IfThenElse(expr, _, _) | Stmt::Expr(expr) => expr.position(),
-            Stmt::While(_, stmt) | Stmt::Loop(stmt) | Stmt::For(_, _, stmt) => stmt.position(),
+            Stmt::While(_, stmt, _)
+            | Stmt::Do(stmt, _, _, _)
+            | Stmt::Loop(stmt, _)
+            | Stmt::For(_, _, stmt, _) => stmt.position(),
         }
     }
 
@@ -268,19 +452,31 @@ impl Stmt {
     pub fn is_self_terminated(&self) -> bool {
         match self {
             Stmt::IfThenElse(_, _, _)
-            | Stmt::While(_, _)
-            | Stmt::Loop(_)
-            | Stmt::For(_, _, _)
+            | Stmt::While(_, _, _)
+            | Stmt::Loop(_, _)
+            | Stmt::For(_, _, _, _)
             | Stmt::Block(_, _) => true,
 
             // A No-op requires a semicolon in order to know it is an empty statement!
             Stmt::Noop(_) => false,
 
+            // do { ... } while/until expr; - ends with an expression
+            Stmt::Do(_, _, _, _) => false,
+
+            // Custom syntax ending with a statement block
+            Stmt::Expr(expr) => {
+                if let Expr::Custom(_, inputs, _) = expr.as_ref() {
+                    if let Some(Expr::Stmt(_, _)) = inputs.last() {
+                        return true;
+                    }
+                }
+                false
+            }
+
             Stmt::Let(_, _, _)
             | Stmt::Const(_, _, _)
-            | Stmt::Expr(_)
-            | Stmt::Continue(_)
-            | Stmt::Break(_)
+            | Stmt::Continue(_, _)
+            | Stmt::Break(_, _, _)
             | Stmt::ReturnWithVal(_, _, _) => false,
         }
     }
@@ -293,14 +489,14 @@ impl Stmt {
             Stmt::IfThenElse(guard, if_block, Some(else_block)) => {
                 guard.is_pure() && if_block.is_pure() && else_block.is_pure()
             }
-            Stmt::IfThenElse(guard, block, None) | Stmt::While(guard, block) => {
-                guard.is_pure() && block.is_pure()
-            }
-            Stmt::Loop(block) => block.is_pure(),
-            Stmt::For(_, range, block) => range.is_pure() && block.is_pure(),
+            Stmt::IfThenElse(guard, block, None)
+            | Stmt::While(guard, block, _)
+            | Stmt::Do(block, guard, _, _) => guard.is_pure() && block.is_pure(),
+            Stmt::Loop(block, _) => block.is_pure(),
+            Stmt::For(_, range, block, _) => range.is_pure() && block.is_pure(),
             Stmt::Let(_, _, _) | Stmt::Const(_, _, _) => false,
             Stmt::Block(statements, _) => statements.iter().all(Stmt::is_pure),
-            Stmt::Continue(_) | Stmt::Break(_) | Stmt::ReturnWithVal(_, _, _) => false,
+            Stmt::Continue(_, _) | Stmt::Break(_, _, _) | Stmt::ReturnWithVal(_, _, _) => false,
         }
     }
 }
@@ -333,10 +529,10 @@ pub enum Expr {
     ),
     /// expr = expr
     Assignment(Box<Expr>, Box<Expr>, Position),
-    /// lhs.rhs
-    Dot(Box<Expr>, Box<Expr>, Position),
-    /// expr[expr]
-    Index(Box<Expr>, Box<Expr>, Position),
+    /// lhs.rhs, or lhs?.rhs if safe navigation
+    Dot(Box<Expr>, Box<Expr>, bool, Position),
+    /// expr[expr], or expr?[expr] if safe navigation
+    Index(Box<Expr>, Box<Expr>, bool, Position),
     /// [ expr, ... ]
     Array(Vec<Expr>, Position),
     /// #{ name:expr, ... }
@@ -347,6 +543,10 @@ pub enum Expr {
     And(Box<Expr>, Box<Expr>, Position),
     /// lhs || rhs
     Or(Box<Expr>, Box<Expr>, Position),
+    /// lhs ?? rhs
+    Coalesce(Box<Expr>, Box<Expr>, Position),
+    /// keyword input ... - custom syntax with its captured inputs
+    Custom(String, Vec<Expr>, Position),
     /// true
     True(Position),
     /// false
@@ -386,7 +586,7 @@ impl Expr {
                     items
                         .iter()
                         .map(|(k, v, _)| (k.clone(), v.get_constant_value()))
-                        .collect::<HashMap<_, _>>(),
+                        .collect::<Map>(),
                 )))
             }
 
@@ -430,14 +630,16 @@ impl Expr {
             | Self::FunctionCall(_, _, _, pos)
             | Self::And(_, _, pos)
             | Self::Or(_, _, pos)
+            | Self::Coalesce(_, _, pos)
+            | Self::Custom(_, _, pos)
             | Self::In(_, _, pos)
             | Self::True(pos)
             | Self::False(pos)
             | Self::Unit(pos) => *pos,
 
-            Self::Assignment(expr, _, _) | Self::Dot(expr, _, _) | Self::Index(expr, _, _) => {
-                expr.position()
-            }
+            Self::Assignment(expr, _, _)
+            | Self::Dot(expr, _, _, _)
+            | Self::Index(expr, _, _, _) => expr.position(),
         }
     }
 
@@ -456,13 +658,15 @@ impl Expr {
             | Self::FunctionCall(_, _, _, pos)
             | Self::And(_, _, pos)
             | Self::Or(_, _, pos)
+            | Self::Coalesce(_, _, pos)
+            | Self::Custom(_, _, pos)
             | Self::In(_, _, pos)
             | Self::True(pos)
             | Self::False(pos)
             | Self::Unit(pos)
             | Self::Assignment(_, _, pos)
-            | Self::Dot(_, _, pos)
-            | Self::Index(_, _, pos) => *pos = new_pos,
+            | Self::Dot(_, _, _, pos)
+            | Self::Index(_, _, _, pos) => *pos = new_pos,
         }
 
         self
@@ -475,9 +679,11 @@ impl Expr {
         match self {
             Self::Array(expressions, _) => expressions.iter().all(Self::is_pure),
 
-            Self::Index(x, y, _) | Self::And(x, y, _) | Self::Or(x, y, _) | Self::In(x, y, _) => {
-                x.is_pure() && y.is_pure()
-            }
+            Self::Index(x, y, _, _)
+            | Self::And(x, y, _)
+            | Self::Or(x, y, _)
+            | Self::Coalesce(x, y, _)
+            | Self::In(x, y, _) => x.is_pure() && y.is_pure(),
 
             Self::Stmt(stmt, _) => stmt.is_pure(),
 
@@ -524,6 +730,8 @@ impl Expr {
             | Self::In(_, _, _)
             | Self::And(_, _, _)
             | Self::Or(_, _, _)
+            | Self::Coalesce(_, _, _)
+            | Self::Custom(_, _, _)
             | Self::True(_)
             | Self::False(_)
             | Self::Unit(_) => false,
@@ -532,16 +740,16 @@ impl Expr {
             | Self::Stmt(_, _)
             | Self::FunctionCall(_, _, _, _)
             | Self::Assignment(_, _, _)
-            | Self::Dot(_, _, _)
-            | Self::Index(_, _, _)
+            | Self::Dot(_, _, _, _)
+            | Self::Index(_, _, _, _)
             | Self::Array(_, _)
             | Self::Map(_, _) => match token {
-                Token::LeftBracket => true,
+                Token::LeftBracket | Token::QuestionBracket => true,
                 _ => false,
             },
 
             Self::Variable(_, _, _) | Self::Property(_, _) => match token {
-                Token::LeftBracket | Token::LeftParen => true,
+                Token::LeftBracket | Token::QuestionBracket | Token::LeftParen => true,
                 _ => false,
             },
         }
@@ -585,7 +793,7 @@ fn match_token(input: &mut Peekable<TokenIterator>, token: Token) -> Result<bool
 /// Parse ( expr )
 fn parse_paren_expr<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     begin: Position,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
@@ -593,13 +801,13 @@ fn parse_paren_expr<'a>(
         return Ok(Expr::Unit(begin));
     }
 
-    let expr = parse_expr(input, stack, allow_stmt_expr)?;
+    let expr = parse_expr(input, state, allow_stmt_expr)?;
 
     match input.next().unwrap() {
         // ( xxx )
         (Token::RightParen, _) => Ok(expr),
         // ( <error>
-        (Token::LexError(err), pos) => return Err(PERR::BadInput(err.to_string()).into_err(pos)),
+        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
         // ( xxx ???
         (_, pos) => Err(PERR::MissingToken(
             ")".into(),
@@ -612,7 +820,7 @@ fn parse_paren_expr<'a>(
 /// Parse a function call.
 fn parse_call_expr<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     id: String,
     begin: Position,
     allow_stmt_expr: bool,
@@ -629,7 +837,7 @@ fn parse_call_expr<'a>(
             .into_err(*pos))
         }
         // id <error>
-        (Token::LexError(err), pos) => return Err(PERR::BadInput(err.to_string()).into_err(*pos)),
+        (Token::LexError(err), pos) => return Err(err.to_err(*pos)),
         // id()
         (Token::RightParen, _) => {
             eat_token(input, Token::RightParen);
@@ -640,7 +848,7 @@ fn parse_call_expr<'a>(
     }
 
     loop {
-        args.push(parse_expr(input, stack, allow_stmt_expr)?);
+        args.push(parse_expr(input, state, allow_stmt_expr)?);
 
         match input.peek().unwrap() {
             (Token::RightParen, _) => {
@@ -657,9 +865,7 @@ fn parse_call_expr<'a>(
                 )
                 .into_err(*pos))
             }
-            (Token::LexError(err), pos) => {
-                return Err(PERR::BadInput(err.to_string()).into_err(*pos))
-            }
+            (Token::LexError(err), pos) => return Err(err.to_err(*pos)),
             (_, pos) => {
                 return Err(PERR::MissingToken(
                     ",".into(),
@@ -675,12 +881,13 @@ fn parse_call_expr<'a>(
 /// Indexing binds to the right, so this call parses all possible levels of indexing following in the input.
 fn parse_index_chain<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     lhs: Expr,
     pos: Position,
+    is_safe: bool,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
-    let idx_expr = parse_expr(input, stack, allow_stmt_expr)?;
+    let idx_expr = parse_expr(input, state, allow_stmt_expr)?;
 
     // Check type of indexing - must be integer or string
     match &idx_expr {
@@ -792,19 +999,26 @@ fn parse_index_chain<'a>(
             // Any more indexing following?
             match input.peek().unwrap() {
                 // If another indexing level, right-bind it
-                (Token::LeftBracket, _) => {
-                    let follow_pos = eat_token(input, Token::LeftBracket);
+                (Token::LeftBracket, _) | (Token::QuestionBracket, _) => {
+                    let (follow_token, follow_pos) = input.next().unwrap();
+                    let follow_safe = follow_token == Token::QuestionBracket;
                     // Recursively parse the indexing chain, right-binding each
-                    let follow =
-                        parse_index_chain(input, stack, idx_expr, follow_pos, allow_stmt_expr)?;
+                    let follow = parse_index_chain(
+                        input,
+                        state,
+                        idx_expr,
+                        follow_pos,
+                        follow_safe,
+                        allow_stmt_expr,
+                    )?;
                     // Indexing binds to right
-                    Ok(Expr::Index(Box::new(lhs), Box::new(follow), pos))
+                    Ok(Expr::Index(Box::new(lhs), Box::new(follow), is_safe, pos))
                 }
                 // Otherwise terminate the indexing chain
-                _ => Ok(Expr::Index(Box::new(lhs), Box::new(idx_expr), pos)),
+                _ => Ok(Expr::Index(Box::new(lhs), Box::new(idx_expr), is_safe, pos)),
             }
         }
-        (Token::LexError(err), pos) => return Err(PERR::BadInput(err.to_string()).into_err(*pos)),
+        (Token::LexError(err), pos) => return Err(err.to_err(*pos)),
         (_, pos) => Err(PERR::MissingToken(
             "]".into(),
             "for a matching [ in this index expression".into(),
@@ -816,7 +1030,7 @@ fn parse_index_chain<'a>(
 /// Parse an array literal.
 fn parse_array_literal<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     begin: Position,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
@@ -824,7 +1038,7 @@ fn parse_array_literal<'a>(
 
     if !match_token(input, Token::RightBracket)? {
         while !input.peek().unwrap().0.is_eof() {
-            arr.push(parse_expr(input, stack, allow_stmt_expr)?);
+            arr.push(parse_expr(input, state, allow_stmt_expr)?);
 
             match input.peek().unwrap() {
                 (Token::Comma, _) => eat_token(input, Token::Comma),
@@ -838,9 +1052,7 @@ fn parse_array_literal<'a>(
                             .into_err(*pos),
                     )
                 }
-                (Token::LexError(err), pos) => {
-                    return Err(PERR::BadInput(err.to_string()).into_err(*pos))
-                }
+                (Token::LexError(err), pos) => return Err(err.to_err(*pos)),
                 (_, pos) => {
                     return Err(PERR::MissingToken(
                         ",".into(),
@@ -858,7 +1070,7 @@ fn parse_array_literal<'a>(
 /// Parse a map literal.
 fn parse_map_literal<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     begin: Position,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
@@ -871,9 +1083,7 @@ fn parse_map_literal<'a>(
             let (name, pos) = match input.next().unwrap() {
                 (Token::Identifier(s), pos) => (s, pos),
                 (Token::StringConst(s), pos) => (s, pos),
-                (Token::LexError(err), pos) => {
-                    return Err(PERR::BadInput(err.to_string()).into_err(pos))
-                }
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
                 (_, pos) if map.is_empty() => {
                     return Err(PERR::MissingToken("}".into(), MISSING_RBRACE.into()).into_err(pos))
                 }
@@ -885,9 +1095,7 @@ fn parse_map_literal<'a>(
 
             match input.next().unwrap() {
                 (Token::Colon, _) => (),
-                (Token::LexError(err), pos) => {
-                    return Err(PERR::BadInput(err.to_string()).into_err(pos))
-                }
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
                 (_, pos) => {
                     return Err(PERR::MissingToken(
                         ":".into(),
@@ -900,7 +1108,7 @@ fn parse_map_literal<'a>(
                 }
             };
 
-            let expr = parse_expr(input, stack, allow_stmt_expr)?;
+            let expr = parse_expr(input, state, allow_stmt_expr)?;
 
             map.push((name, expr, pos));
 
@@ -919,9 +1127,7 @@ fn parse_map_literal<'a>(
                     )
                     .into_err(*pos))
                 }
-                (Token::LexError(err), pos) => {
-                    return Err(PERR::BadInput(err.to_string()).into_err(*pos))
-                }
+                (Token::LexError(err), pos) => return Err(err.to_err(*pos)),
                 (_, pos) => {
                     return Err(PERR::MissingToken("}".into(), MISSING_RBRACE.into()).into_err(*pos))
                 }
@@ -946,14 +1152,16 @@ fn parse_map_literal<'a>(
 /// Parse a primary expression.
 fn parse_primary<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
+    let is_property = mem::replace(&mut state.is_property, false);
+
     let (token, pos) = match input.peek().unwrap() {
         // { - block statement as expression
         (Token::LeftBrace, pos) if allow_stmt_expr => {
             let pos = *pos;
-            return parse_block(input, stack, false, allow_stmt_expr)
+            return parse_block(input, state, &[], allow_stmt_expr)
                 .map(|block| Expr::Stmt(Box::new(block), pos));
         }
         (Token::EOF, pos) => return Err(PERR::UnexpectedEOF.into_err(*pos)),
@@ -966,18 +1174,35 @@ fn parse_primary<'a>(
         Token::FloatConstant(x) => Expr::FloatConstant(x, pos),
         Token::CharConstant(c) => Expr::CharConstant(c, pos),
         Token::StringConst(s) => Expr::StringConstant(s, pos),
+        Token::Identifier(s) if state.engine.custom_syntax.contains_key(&s) => {
+            parse_custom_syntax(input, state, &[], s, pos, allow_stmt_expr)?
+        }
         Token::Identifier(s) => {
-            let index = stack.find(&s);
+            // A function name is not a variable
+            let index = match input.peek().unwrap() {
+                (Token::LeftParen, _) => state.find(&s),
+                _ => {
+                    let index = state.access_var(&s);
+
+                    if index.is_none() && !is_property {
+                        ensure_variable_declared(&s, pos, state)?;
+                    }
+
+                    index
+                }
+            };
             Expr::Variable(s, index, pos)
         }
-        Token::LeftParen => parse_paren_expr(input, stack, pos, allow_stmt_expr)?,
+        Token::LeftParen => parse_paren_expr(input, state, pos, allow_stmt_expr)?,
+        #[cfg(not(feature = "no_function"))]
+        Token::Pipe | Token::Or => parse_anon_fn(input, state, token, pos)?,
         #[cfg(not(feature = "no_index"))]
-        Token::LeftBracket => parse_array_literal(input, stack, pos, allow_stmt_expr)?,
+        Token::LeftBracket => parse_array_literal(input, state, pos, allow_stmt_expr)?,
         #[cfg(not(feature = "no_object"))]
-        Token::MapStart => parse_map_literal(input, stack, pos, allow_stmt_expr)?,
+        Token::MapStart => parse_map_literal(input, state, pos, allow_stmt_expr)?,
         Token::True => Expr::True(pos),
         Token::False => Expr::False(pos),
-        Token::LexError(err) => return Err(PERR::BadInput(err.to_string()).into_err(pos)),
+        Token::LexError(err) => return Err(err.to_err(pos)),
         token => {
             return Err(PERR::BadInput(format!("Unexpected '{}'", token.syntax())).into_err(pos))
         }
@@ -997,11 +1222,15 @@ fn parse_primary<'a>(
             // Function call
             (Expr::Variable(id, _, pos), Token::LeftParen)
             | (Expr::Property(id, pos), Token::LeftParen) => {
-                parse_call_expr(input, stack, id, pos, allow_stmt_expr)?
+                parse_call_expr(input, state, id, pos, allow_stmt_expr)?
             }
             // Indexing
             (expr, Token::LeftBracket) => {
-                parse_index_chain(input, stack, expr, pos, allow_stmt_expr)?
+                parse_index_chain(input, state, expr, pos, false, allow_stmt_expr)?
+            }
+            // Safe indexing
+            (expr, Token::QuestionBracket) => {
+                parse_index_chain(input, state, expr, pos, true, allow_stmt_expr)?
             }
             // Unknown postfix operator
             (expr, token) => panic!("unknown postfix operator {:?} for {:?}", token, expr),
@@ -1011,10 +1240,60 @@ fn parse_primary<'a>(
     Ok(root_expr)
 }
 
+/// Parse a custom syntax, capturing its inputs.
+fn parse_custom_syntax<'a>(
+    input: &mut Peekable<TokenIterator<'a>>,
+    state: &mut ParseState,
+    loops: &[LoopContext],
+    key: String,
+    pos: Position,
+    allow_stmt_expr: bool,
+) -> Result<Expr, Box<ParseError>> {
+    let engine = state.engine;
+    let segments = &engine.custom_syntax[&key].segments;
+    let mut inputs = Vec::new();
+
+    for segment in &segments[1..] {
+        match segment.as_str() {
+            MARKER_EXPR => inputs.push(parse_expr(input, state, allow_stmt_expr)?),
+            MARKER_BLOCK => {
+                let pos = input.peek().unwrap().1;
+                let block = parse_block(input, state, loops, allow_stmt_expr)?;
+                inputs.push(Expr::Stmt(Box::new(block), pos));
+            }
+            MARKER_IDENT => match input.next().unwrap() {
+                (Token::Identifier(s), pos) => inputs.push(Expr::Variable(s, None, pos)),
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+            },
+            s => match input.next().unwrap() {
+                (Token::StringConst(_), pos) => {
+                    return Err(PERR::MissingToken(
+                        s.into(),
+                        format!("for the custom syntax '{}'", key),
+                    )
+                    .into_err(pos))
+                }
+                (token, _) if token.syntax() == s => (),
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                (_, pos) => {
+                    return Err(PERR::MissingToken(
+                        s.into(),
+                        format!("for the custom syntax '{}'", key),
+                    )
+                    .into_err(pos))
+                }
+            },
+        }
+    }
+
+    Ok(Expr::Custom(key, inputs, pos))
+}
+
 /// Parse a potential unary operator.
 fn parse_unary<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
     match input.peek().unwrap() {
@@ -1022,7 +1301,22 @@ fn parse_unary<'a>(
         (Token::If, pos) => {
             let pos = *pos;
             Ok(Expr::Stmt(
-                Box::new(parse_if(input, stack, false, allow_stmt_expr)?),
+                Box::new(parse_if(input, state, &[], allow_stmt_expr)?),
+                pos,
+            ))
+        }
+        // Loop statement is allowed to act as expressions, yielding the value of `break`
+        (Token::Loop, pos) => {
+            let pos = *pos;
+            Ok(Expr::Stmt(
+                Box::new(parse_loop(input, state, &[], None, allow_stmt_expr)?),
+                pos,
+            ))
+        }
+        (Token::Label(_), pos) => {
+            let pos = *pos;
+            Ok(Expr::Stmt(
+                Box::new(parse_labeled_loop(input, state, &[], allow_stmt_expr)?),
                 pos,
             ))
         }
@@ -1030,7 +1324,7 @@ fn parse_unary<'a>(
         (Token::UnaryMinus, _) => {
             let pos = eat_token(input, Token::UnaryMinus);
 
-            match parse_unary(input, stack, allow_stmt_expr)? {
+            match parse_unary(input, state, allow_stmt_expr)? {
                 // Negative integer
                 Expr::IntegerConstant(i, _) => i
                     .checked_neg()
@@ -1061,14 +1355,14 @@ fn parse_unary<'a>(
         // +expr
         (Token::UnaryPlus, _) => {
             eat_token(input, Token::UnaryPlus);
-            parse_unary(input, stack, allow_stmt_expr)
+            parse_unary(input, state, allow_stmt_expr)
         }
         // !expr
         (Token::Bang, _) => {
             let pos = eat_token(input, Token::Bang);
             Ok(Expr::FunctionCall(
                 "!".into(),
-                Box::new(vec![parse_primary(input, stack, allow_stmt_expr)?]),
+                Box::new(vec![parse_primary(input, state, allow_stmt_expr)?]),
                 Some(Box::new(false.into())), // NOT operator, when operating on invalid operand, defaults to false
                 pos,
             ))
@@ -1076,30 +1370,35 @@ fn parse_unary<'a>(
         // <EOF>
         (Token::EOF, pos) => Err(PERR::UnexpectedEOF.into_err(*pos)),
         // All other tokens
-        _ => parse_primary(input, stack, allow_stmt_expr),
+        _ => parse_primary(input, state, allow_stmt_expr),
     }
 }
 
 fn parse_assignment_stmt<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     lhs: Expr,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
+    // Cannot assign to a safe-navigation chain
+    if has_safe_navigation(&lhs) {
+        return Err(PERR::AssignmentToInvalidLHS.into_err(lhs.position()));
+    }
+
     let pos = eat_token(input, Token::Equals);
-    let rhs = parse_expr(input, stack, allow_stmt_expr)?;
+    let rhs = parse_expr(input, state, allow_stmt_expr)?;
     Ok(Expr::Assignment(Box::new(lhs), Box::new(rhs), pos))
 }
 
 /// Parse an operator-assignment expression.
 fn parse_op_assignment_stmt<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     lhs: Expr,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
     let (op, pos) = match *input.peek().unwrap() {
-        (Token::Equals, _) => return parse_assignment_stmt(input, stack, lhs, allow_stmt_expr),
+        (Token::Equals, _) => return parse_assignment_stmt(input, state, lhs, allow_stmt_expr),
         (Token::PlusAssign, pos) => ("+", pos),
         (Token::MinusAssign, pos) => ("-", pos),
         (Token::MultiplyAssign, pos) => ("*", pos),
@@ -1114,10 +1413,15 @@ fn parse_op_assignment_stmt<'a>(
         (_, _) => return Ok(lhs),
     };
 
+    // Cannot assign to a safe-navigation chain
+    if has_safe_navigation(&lhs) {
+        return Err(PERR::AssignmentToInvalidLHS.into_err(lhs.position()));
+    }
+
     input.next();
 
     let lhs_copy = lhs.clone();
-    let rhs = parse_expr(input, stack, allow_stmt_expr)?;
+    let rhs = parse_expr(input, state, allow_stmt_expr)?;
 
     // lhs op= rhs -> lhs = op(lhs, rhs)
     let rhs_expr = Expr::FunctionCall(op.into(), Box::new(vec![lhs_copy, rhs]), None, pos);
@@ -1125,42 +1429,67 @@ fn parse_op_assignment_stmt<'a>(
 }
 
 /// Make a dot expression.
-fn make_dot_expr(lhs: Expr, rhs: Expr, op_pos: Position, is_index: bool) -> Expr {
+fn make_dot_expr(lhs: Expr, rhs: Expr, op_pos: Position, is_index: bool, is_safe: bool) -> Expr {
     match (lhs, rhs) {
         // idx_lhs[idx_rhs].rhs
         // Attach dot chain to the bottom level of indexing chain
-        (Expr::Index(idx_lhs, idx_rhs, idx_pos), rhs) => Expr::Index(
+        (Expr::Index(idx_lhs, idx_rhs, idx_safe, idx_pos), rhs) => Expr::Index(
             idx_lhs,
-            Box::new(make_dot_expr(*idx_rhs, rhs, op_pos, true)),
+            Box::new(make_dot_expr(*idx_rhs, rhs, op_pos, true, is_safe)),
+            idx_safe,
             idx_pos,
         ),
         // lhs.id
         (lhs, rhs @ Expr::Variable(_, _, _)) | (lhs, rhs @ Expr::Property(_, _)) => {
             let lhs = if is_index { lhs.into_property() } else { lhs };
-            Expr::Dot(Box::new(lhs), Box::new(rhs.into_property()), op_pos)
+            Expr::Dot(
+                Box::new(lhs),
+                Box::new(rhs.into_property()),
+                is_safe,
+                op_pos,
+            )
         }
         // lhs.dot_lhs.dot_rhs
-        (lhs, Expr::Dot(dot_lhs, dot_rhs, dot_pos)) => Expr::Dot(
+        (lhs, Expr::Dot(dot_lhs, dot_rhs, dot_safe, dot_pos)) => Expr::Dot(
             Box::new(lhs),
             Box::new(Expr::Dot(
                 Box::new(dot_lhs.into_property()),
                 Box::new(dot_rhs.into_property()),
+                dot_safe,
                 dot_pos,
             )),
+            is_safe,
             op_pos,
         ),
         // lhs.idx_lhs[idx_rhs]
-        (lhs, Expr::Index(idx_lhs, idx_rhs, idx_pos)) => Expr::Dot(
+        (lhs, Expr::Index(idx_lhs, idx_rhs, idx_safe, idx_pos)) => Expr::Dot(
             Box::new(lhs),
             Box::new(Expr::Index(
                 Box::new(idx_lhs.into_property()),
                 Box::new(idx_rhs.into_property()),
+                idx_safe,
                 idx_pos,
             )),
+            is_safe,
             op_pos,
         ),
         // lhs.rhs
-        (lhs, rhs) => Expr::Dot(Box::new(lhs), Box::new(rhs.into_property()), op_pos),
+        (lhs, rhs) => Expr::Dot(
+            Box::new(lhs),
+            Box::new(rhs.into_property()),
+            is_safe,
+            op_pos,
+        ),
+    }
+}
+
+/// Does a dot/index chain contain a safe-navigation access?
+fn has_safe_navigation(expr: &Expr) -> bool {
+    match expr {
+        Expr::Dot(lhs, rhs, is_safe, _) | Expr::Index(lhs, rhs, is_safe, _) => {
+            *is_safe || has_safe_navigation(lhs) || has_safe_navigation(rhs)
+        }
+        _ => false,
     }
 }
 
@@ -1296,7 +1625,7 @@ fn make_in_expr(lhs: Expr, rhs: Expr, op_pos: Position) -> Result<Expr, Box<Pars
 /// Parse a binary expression.
 fn parse_binary_op<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     parent_precedence: u8,
     lhs: Expr,
     allow_stmt_expr: bool,
@@ -1319,7 +1648,8 @@ fn parse_binary_op<'a>(
 
         let (op_token, pos) = input.next().unwrap();
 
-        let rhs = parse_unary(input, stack, allow_stmt_expr)?;
+        state.is_property = matches!(op_token, Token::Period | Token::QuestionPeriod);
+        let rhs = parse_unary(input, state, allow_stmt_expr)?;
 
         let next_precedence = input.peek().unwrap().0.precedence();
 
@@ -1328,7 +1658,7 @@ fn parse_binary_op<'a>(
         let rhs = if (current_precedence == next_precedence && bind_right)
             || current_precedence < next_precedence
         {
-            parse_binary_op(input, stack, current_precedence, rhs, allow_stmt_expr)?
+            parse_binary_op(input, state, current_precedence, rhs, allow_stmt_expr)?
         } else {
             // Otherwise bind to left (even if next operator has the same precedence)
             rhs
@@ -1402,6 +1732,7 @@ fn parse_binary_op<'a>(
             ),
 
             Token::Or => Expr::Or(Box::new(current_lhs), Box::new(rhs), pos),
+            Token::DoubleQuestion => Expr::Coalesce(Box::new(current_lhs), Box::new(rhs), pos),
             Token::And => Expr::And(Box::new(current_lhs), Box::new(rhs), pos),
             Token::Ampersand => {
                 Expr::FunctionCall("&".into(), Box::new(vec![current_lhs, rhs]), None, pos)
@@ -1415,8 +1746,15 @@ fn parse_binary_op<'a>(
 
             Token::In => make_in_expr(current_lhs, rhs, pos)?,
 
+            // Custom operators call the function of the same name
+            Token::Custom(op, _) => {
+                Expr::FunctionCall(op.into(), Box::new(vec![current_lhs, rhs]), None, pos)
+            }
+
             #[cfg(not(feature = "no_object"))]
-            Token::Period => make_dot_expr(current_lhs, rhs, pos, false),
+            Token::Period => make_dot_expr(current_lhs, rhs, pos, false, false),
+            #[cfg(not(feature = "no_object"))]
+            Token::QuestionPeriod => make_dot_expr(current_lhs, rhs, pos, false, true),
 
             token => return Err(PERR::UnknownOperator(token.syntax().into()).into_err(pos)),
         };
@@ -1426,11 +1764,11 @@ fn parse_binary_op<'a>(
 /// Parse an expression.
 fn parse_expr<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     allow_stmt_expr: bool,
 ) -> Result<Expr, Box<ParseError>> {
-    let lhs = parse_unary(input, stack, allow_stmt_expr)?;
-    parse_binary_op(input, stack, 1, lhs, allow_stmt_expr)
+    let lhs = parse_unary(input, state, allow_stmt_expr)?;
+    parse_binary_op(input, state, 1, lhs, allow_stmt_expr)
 }
 
 /// Make sure that the expression is not a statement expression (i.e. wrapped in `{}`).
@@ -1480,8 +1818,8 @@ fn ensure_not_assignment<'a>(
 /// Parse an if statement.
 fn parse_if<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
-    breakable: bool,
+    state: &mut ParseState,
+    loops: &[LoopContext],
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
     // if ...
@@ -1489,18 +1827,18 @@ fn parse_if<'a>(
 
     // if guard { if_body }
     ensure_not_statement_expr(input, "a boolean")?;
-    let guard = parse_expr(input, stack, allow_stmt_expr)?;
+    let guard = parse_expr(input, state, allow_stmt_expr)?;
     ensure_not_assignment(input)?;
-    let if_body = parse_block(input, stack, breakable, allow_stmt_expr)?;
+    let if_body = parse_block(input, state, loops, allow_stmt_expr)?;
 
     // if guard { if_body } else ...
     let else_body = if match_token(input, Token::Else).unwrap_or(false) {
         Some(Box::new(if let (Token::If, _) = input.peek().unwrap() {
             // if guard { if_body } else if ...
-            parse_if(input, stack, breakable, allow_stmt_expr)?
+            parse_if(input, state, loops, allow_stmt_expr)?
         } else {
             // if guard { if_body } else { else-body }
-            parse_block(input, stack, breakable, allow_stmt_expr)?
+            parse_block(input, state, loops, allow_stmt_expr)?
         }))
     } else {
         None
@@ -1513,10 +1851,53 @@ fn parse_if<'a>(
     ))
 }
 
+/// Parse a labeled loop.
+fn parse_labeled_loop<'a>(
+    input: &mut Peekable<TokenIterator<'a>>,
+    state: &mut ParseState,
+    loops: &[LoopContext],
+    allow_stmt_expr: bool,
+) -> Result<Stmt, Box<ParseError>> {
+    // 'label ...
+    let label = match input.next().unwrap() {
+        (Token::Label(s), _) => s,
+        _ => panic!("expecting a label"),
+    };
+
+    // 'label: ...
+    match input.next().unwrap() {
+        (Token::Colon, _) => (),
+        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+        (_, pos) => {
+            return Err(
+                PERR::MissingToken(":".into(), format!("after the loop label '{}'", label))
+                    .into_err(pos),
+            )
+        }
+    }
+
+    // 'label: loop ...
+    match input.peek().unwrap() {
+        (Token::While, _) => parse_while(input, state, loops, Some(label), allow_stmt_expr),
+        (Token::Do, _) => parse_do(input, state, loops, Some(label), allow_stmt_expr),
+        (Token::Loop, _) => parse_loop(input, state, loops, Some(label), allow_stmt_expr),
+        (Token::For, _) => parse_for(input, state, loops, Some(label), allow_stmt_expr),
+        (Token::LexError(err), pos) => Err(err.to_err(*pos)),
+        (token, pos) => Err(PERR::BadInput(format!(
+            "Expecting a loop after the label '{}' but got '{}'",
+            label,
+            token.syntax()
+        ))
+        .into_err(*pos)),
+    }
+}
+
 /// Parse a while loop.
 fn parse_while<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
+    loops: &[LoopContext],
+    label: Option<String>,
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
     // while ...
@@ -1524,53 +1905,87 @@ fn parse_while<'a>(
 
     // while guard { body }
     ensure_not_statement_expr(input, "a boolean")?;
-    let guard = parse_expr(input, stack, allow_stmt_expr)?;
+    let guard = parse_expr(input, state, allow_stmt_expr)?;
     ensure_not_assignment(input)?;
-    let body = parse_block(input, stack, true, allow_stmt_expr)?;
+    let loops = LoopContext::enter(loops, &label, false);
+    let body = parse_block(input, state, &loops, allow_stmt_expr)?;
 
-    Ok(Stmt::While(Box::new(guard), Box::new(body)))
+    Ok(Stmt::While(Box::new(guard), Box::new(body), label))
+}
+
+/// Parse a do loop.
+fn parse_do<'a>(
+    input: &mut Peekable<TokenIterator<'a>>,
+    state: &mut ParseState,
+    loops: &[LoopContext],
+    label: Option<String>,
+    allow_stmt_expr: bool,
+) -> Result<Stmt, Box<ParseError>> {
+    // do ...
+    eat_token(input, Token::Do);
+
+    // do { body } ...
+    let loops = LoopContext::enter(loops, &label, false);
+    let body = parse_block(input, state, &loops, allow_stmt_expr)?;
+
+    // do { body } while/until ...
+    let is_until = match input.next().unwrap() {
+        (Token::While, _) => false,
+        (Token::Until, _) => true,
+        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+        (_, pos) => {
+            return Err(PERR::MissingToken(
+                "while".into(),
+                "or 'until' after the body of the do loop".into(),
+            )
+            .into_err(pos))
+        }
+    };
+
+    // do { body } while/until guard
+    ensure_not_statement_expr(input, "a boolean")?;
+    let guard = parse_expr(input, state, allow_stmt_expr)?;
+    ensure_not_assignment(input)?;
+
+    Ok(Stmt::Do(Box::new(body), Box::new(guard), is_until, label))
 }
 
 /// Parse a loop statement.
 fn parse_loop<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
+    loops: &[LoopContext],
+    label: Option<String>,
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
     // loop ...
     eat_token(input, Token::Loop);
 
     // loop { body }
-    let body = parse_block(input, stack, true, allow_stmt_expr)?;
+    let loops = LoopContext::enter(loops, &label, true);
+    let body = parse_block(input, state, &loops, allow_stmt_expr)?;
 
-    Ok(Stmt::Loop(Box::new(body)))
+    Ok(Stmt::Loop(Box::new(body), label))
 }
 
 /// Parse a for loop.
 fn parse_for<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
+    loops: &[LoopContext],
+    label: Option<String>,
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
     // for ...
     eat_token(input, Token::For);
 
-    // for name ...
-    let name = match input.next().unwrap() {
-        // Variable name
-        (Token::Identifier(s), _) => s,
-        // Bad identifier
-        (Token::LexError(err), pos) => return Err(PERR::BadInput(err.to_string()).into_err(pos)),
-        // EOF
-        (Token::EOF, pos) => return Err(PERR::VariableExpected.into_err(pos)),
-        // Not a variable name
-        (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
-    };
+    // for pattern ...
+    let pattern = parse_pattern(input)?;
 
-    // for name in ...
+    // for pattern in ...
     match input.next().unwrap() {
         (Token::In, _) => (),
-        (Token::LexError(err), pos) => return Err(PERR::BadInput(err.to_string()).into_err(pos)),
+        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
         (_, pos) => {
             return Err(
                 PERR::MissingToken("in".into(), "after the iteration variable".into())
@@ -1579,51 +1994,84 @@ fn parse_for<'a>(
         }
     }
 
-    // for name in expr { body }
+    // for pattern in expr { body }
     ensure_not_statement_expr(input, "a boolean")?;
-    let expr = parse_expr(input, stack, allow_stmt_expr)?;
+    let expr = parse_expr(input, state, allow_stmt_expr)?;
 
-    let prev_len = stack.len();
-    stack.push(name.clone());
+    let prev_len = state.len();
+    pattern
+        .variables()
+        .into_iter()
+        .for_each(|name| state.push(name.clone()));
 
-    let body = parse_block(input, stack, true, allow_stmt_expr)?;
+    let loops = LoopContext::enter(loops, &label, false);
+    let body = parse_block(input, state, &loops, allow_stmt_expr)?;
 
-    stack.rewind(prev_len);
+    state.rewind(prev_len);
 
-    Ok(Stmt::For(name, Box::new(expr), Box::new(body)))
+    Ok(Stmt::For(pattern, Box::new(expr), Box::new(body), label))
 }
 
 /// Parse a variable definition statement.
 fn parse_let<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     var_type: ScopeEntryType,
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
     // let/const... (specified in `var_type`)
     input.next();
 
-    // let name ...
-    let (name, pos) = match input.next().unwrap() {
-        (Token::Identifier(s), pos) => (s, pos),
-        (Token::LexError(err), pos) => return Err(PERR::BadInput(err.to_string()).into_err(pos)),
-        (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+    let pos = input.peek().unwrap().1;
+
+    // let pattern ...
+    let name = match (parse_pattern(input)?, var_type) {
+        (Pattern::Variable(name), _) => name,
+        // let pattern = expr
+        (pattern, ScopeEntryType::Normal) => {
+            match input.next().unwrap() {
+                (Token::Equals, _) => (),
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                (_, pos) => {
+                    return Err(PERR::MissingToken(
+                        "=".into(),
+                        "to give a value to destructure into the variables".into(),
+                    )
+                    .into_err(pos))
+                }
+            }
+
+            let init_value = parse_expr(input, state, allow_stmt_expr)?;
+
+            pattern
+                .variables()
+                .into_iter()
+                .for_each(|name| state.push(name.clone()));
+
+            return Ok(Stmt::Let(pattern, Some(Box::new(init_value)), pos));
+        }
+        // const pattern = expr - error
+        (_, ScopeEntryType::Constant) => return Err(PERR::VariableExpected.into_err(pos)),
     };
 
     // let name = ...
     if match_token(input, Token::Equals)? {
         // let name = expr
-        let init_value = parse_expr(input, stack, allow_stmt_expr)?;
+        let init_value = parse_expr(input, state, allow_stmt_expr)?;
 
         match var_type {
             // let name = expr
             ScopeEntryType::Normal => {
-                stack.push(name.clone());
-                Ok(Stmt::Let(name, Some(Box::new(init_value)), pos))
+                state.push(name.clone());
+                Ok(Stmt::Let(
+                    Pattern::Variable(name),
+                    Some(Box::new(init_value)),
+                    pos,
+                ))
             }
             // const name = { expr:constant }
             ScopeEntryType::Constant if init_value.is_constant() => {
-                stack.push(name.clone());
+                state.push(name.clone());
                 Ok(Stmt::Const(name, Box::new(init_value), pos))
             }
             // const name = expr - error
@@ -1633,21 +2081,139 @@ fn parse_let<'a>(
         }
     } else {
         // let name
-        Ok(Stmt::Let(name, None, pos))
+        Ok(Stmt::Let(Pattern::Variable(name), None, pos))
+    }
+}
+
+/// Parse a pattern binding variables.
+fn parse_pattern<'a>(input: &mut Peekable<TokenIterator<'a>>) -> Result<Pattern, Box<ParseError>> {
+    match input.next().unwrap() {
+        // id
+        (Token::Identifier(s), _) => Ok(Pattern::Variable(s)),
+
+        // [ id, ... , ..id ]
+        #[cfg(not(feature = "no_index"))]
+        (Token::LeftBracket, _) => {
+            const MISSING_RBRACKET: &str = "to end this array pattern";
+
+            let mut names = Vec::new();
+            let mut rest = None;
+
+            if !match_token(input, Token::RightBracket)? {
+                loop {
+                    let is_rest = match_token(input, Token::DoublePeriod)?;
+
+                    match input.next().unwrap() {
+                        (Token::Identifier(s), _) if is_rest => rest = Some(s),
+                        (Token::Identifier(s), _) => names.push(s),
+                        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                        (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+                    }
+
+                    match input.next().unwrap() {
+                        (Token::RightBracket, _) => break,
+                        // The rest variable must be the last one
+                        (Token::Comma, _) if rest.is_none() => (),
+                        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                        (_, pos) => {
+                            return Err(PERR::MissingToken("]".into(), MISSING_RBRACKET.into())
+                                .into_err(pos))
+                        }
+                    }
+                }
+            }
+
+            Ok(Pattern::Array(names, rest))
+        }
+
+        // ( id, ... ) - same as an array pattern, e.g. for the [key, value] pairs of an object map
+        (Token::LeftParen, _) => {
+            const MISSING_RPAREN: &str = "to end this tuple pattern";
+
+            let mut names = Vec::new();
+
+            loop {
+                match input.next().unwrap() {
+                    (Token::Identifier(s), _) => names.push(s),
+                    (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                    (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+                }
+
+                match input.next().unwrap() {
+                    (Token::RightParen, _) => break,
+                    (Token::Comma, _) => (),
+                    (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                    (_, pos) => {
+                        return Err(
+                            PERR::MissingToken(")".into(), MISSING_RPAREN.into()).into_err(pos)
+                        )
+                    }
+                }
+            }
+
+            Ok(Pattern::Array(names, None))
+        }
+
+        // #{ property, property: id, ... }
+        #[cfg(not(feature = "no_object"))]
+        (Token::MapStart, _) => {
+            const MISSING_RBRACE: &str = "to end this object map pattern";
+
+            let mut props = Vec::new();
+
+            if !match_token(input, Token::RightBrace)? {
+                loop {
+                    let prop = match input.next().unwrap() {
+                        (Token::Identifier(s), _) | (Token::StringConst(s), _) => s,
+                        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                        (_, pos) => return Err(PERR::PropertyExpected.into_err(pos)),
+                    };
+
+                    // property: id
+                    let name = if match_token(input, Token::Colon)? {
+                        match input.next().unwrap() {
+                            (Token::Identifier(s), _) => s,
+                            (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                            (_, pos) => return Err(PERR::VariableExpected.into_err(pos)),
+                        }
+                    } else {
+                        prop.clone()
+                    };
+
+                    props.push((prop, name));
+
+                    match input.next().unwrap() {
+                        (Token::RightBrace, _) => break,
+                        (Token::Comma, _) => (),
+                        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                        (_, pos) => {
+                            return Err(
+                                PERR::MissingToken("}".into(), MISSING_RBRACE.into()).into_err(pos)
+                            )
+                        }
+                    }
+                }
+            }
+
+            Ok(Pattern::Map(props))
+        }
+
+        (Token::LexError(err), pos) => Err(err.to_err(pos)),
+        (_, pos) => Err(PERR::VariableExpected.into_err(pos)),
     }
 }
 
 /// Parse a statement block.
 fn parse_block<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
-    breakable: bool,
+    state: &mut ParseState,
+    loops: &[LoopContext],
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
     // Must start with {
     let pos = match input.next().unwrap() {
         (Token::LeftBrace, pos) => pos,
-        (Token::LexError(err), pos) => return Err(PERR::BadInput(err.to_string()).into_err(pos)),
+        (Token::LexError(err), pos) => return Err(err.to_err(pos)),
         (_, pos) => {
             return Err(
                 PERR::MissingToken("{".into(), "to start a statement block".into()).into_err(pos),
@@ -1656,11 +2222,11 @@ fn parse_block<'a>(
     };
 
     let mut statements = Vec::new();
-    let prev_len = stack.len();
+    let prev_len = state.len();
 
     while !match_token(input, Token::RightBrace)? {
         // Parse statements inside the block
-        let stmt = parse_stmt(input, stack, breakable, allow_stmt_expr)?;
+        let stmt = parse_stmt(input, state, loops, allow_stmt_expr)?;
 
         // See if it needs a terminating semicolon
         let need_semicolon = !stmt.is_self_terminated();
@@ -1682,9 +2248,7 @@ fn parse_block<'a>(
             // { ... { stmt } ???
             (_, _) if !need_semicolon => (),
             // { ... stmt <error>
-            (Token::LexError(err), pos) => {
-                return Err(PERR::BadInput(err.to_string()).into_err(*pos))
-            }
+            (Token::LexError(err), pos) => return Err(err.to_err(*pos)),
             // { ... stmt ???
             (_, pos) => {
                 // Semicolons are not optional between statements
@@ -1696,7 +2260,7 @@ fn parse_block<'a>(
         }
     }
 
-    stack.rewind(prev_len);
+    state.rewind(prev_len);
 
     Ok(Stmt::Block(statements, pos))
 }
@@ -1704,19 +2268,19 @@ fn parse_block<'a>(
 /// Parse an expression as a statement.
 fn parse_expr_stmt<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
-    let expr = parse_expr(input, stack, allow_stmt_expr)?;
-    let expr = parse_op_assignment_stmt(input, stack, expr, allow_stmt_expr)?;
+    let expr = parse_expr(input, state, allow_stmt_expr)?;
+    let expr = parse_op_assignment_stmt(input, state, expr, allow_stmt_expr)?;
     Ok(Stmt::Expr(Box::new(expr)))
 }
 
 /// Parse a single statement.
 fn parse_stmt<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
-    breakable: bool,
+    state: &mut ParseState,
+    loops: &[LoopContext],
     allow_stmt_expr: bool,
 ) -> Result<Stmt, Box<ParseError>> {
     let (token, pos) = match input.peek().unwrap() {
@@ -1728,26 +2292,21 @@ fn parse_stmt<'a>(
         // Semicolon - empty statement
         Token::SemiColon => Ok(Stmt::Noop(*pos)),
 
-        Token::LeftBrace => parse_block(input, stack, breakable, allow_stmt_expr),
+        Token::LeftBrace => parse_block(input, state, loops, allow_stmt_expr),
 
         // fn ...
         #[cfg(not(feature = "no_function"))]
         Token::Fn => Err(PERR::WrongFnDefinition.into_err(*pos)),
 
-        Token::If => parse_if(input, stack, breakable, allow_stmt_expr),
-        Token::While => parse_while(input, stack, allow_stmt_expr),
-        Token::Loop => parse_loop(input, stack, allow_stmt_expr),
-        Token::For => parse_for(input, stack, allow_stmt_expr),
+        Token::If => parse_if(input, state, loops, allow_stmt_expr),
+        Token::While => parse_while(input, state, loops, None, allow_stmt_expr),
+        Token::Do => parse_do(input, state, loops, None, allow_stmt_expr),
+        Token::Loop => parse_loop(input, state, loops, None, allow_stmt_expr),
+        Token::For => parse_for(input, state, loops, None, allow_stmt_expr),
+        Token::Label(_) => parse_labeled_loop(input, state, loops, allow_stmt_expr),
 
-        Token::Continue if breakable => {
-            let pos = eat_token(input, Token::Continue);
-            Ok(Stmt::Continue(pos))
-        }
-        Token::Break if breakable => {
-            let pos = eat_token(input, Token::Break);
-            Ok(Stmt::Break(pos))
-        }
-        Token::Continue | Token::Break => Err(PERR::LoopBreak.into_err(*pos)),
+        Token::Continue | Token::Break if loops.is_empty() => Err(PERR::LoopBreak.into_err(*pos)),
+        Token::Continue | Token::Break => parse_break(input, state, loops, allow_stmt_expr),
 
         Token::Return | Token::Throw => {
             let pos = *pos;
@@ -1765,24 +2324,71 @@ fn parse_stmt<'a>(
                 (Token::SemiColon, _) => Ok(Stmt::ReturnWithVal(None, return_type, pos)),
                 // `return` or `throw` with expression
                 (_, _) => {
-                    let expr = parse_expr(input, stack, allow_stmt_expr)?;
+                    let expr = parse_expr(input, state, allow_stmt_expr)?;
                     let pos = expr.position();
                     Ok(Stmt::ReturnWithVal(Some(Box::new(expr)), return_type, pos))
                 }
             }
         }
 
-        Token::Let => parse_let(input, stack, ScopeEntryType::Normal, allow_stmt_expr),
-        Token::Const => parse_let(input, stack, ScopeEntryType::Constant, allow_stmt_expr),
+        Token::Let => parse_let(input, state, ScopeEntryType::Normal, allow_stmt_expr),
+        Token::Const => parse_let(input, state, ScopeEntryType::Constant, allow_stmt_expr),
+
+        // Custom syntax statements can break out of, or continue, the enclosing loops
+        Token::Identifier(s) if state.engine.custom_syntax.contains_key(s) => {
+            let (key, pos) = match input.next().unwrap() {
+                (Token::Identifier(s), pos) => (s, pos),
+                _ => panic!("expecting a custom syntax keyword"),
+            };
+            let expr = parse_custom_syntax(input, state, loops, key, pos, allow_stmt_expr)?;
+            Ok(Stmt::Expr(Box::new(expr)))
+        }
+
+        _ => parse_expr_stmt(input, state, allow_stmt_expr),
+    }
+}
+
+/// Parse a `break` or `continue` statement.
+fn parse_break<'a>(
+    input: &mut Peekable<TokenIterator<'a>>,
+    state: &mut ParseState,
+    loops: &[LoopContext],
+    allow_stmt_expr: bool,
+) -> Result<Stmt, Box<ParseError>> {
+    let (token, pos) = input.next().unwrap();
+
+    // break 'label / continue 'label
+    let (label, target) = match input.peek().unwrap() {
+        (Token::Label(_), _) => match input.next().unwrap() {
+            (Token::Label(s), label_pos) => {
+                match loops.iter().rev().find(|l| l.label.as_ref() == Some(&s)) {
+                    Some(target) => (Some(s), target),
+                    None => return Err(PERR::LoopLabelNotFound(s).into_err(label_pos)),
+                }
+            }
+            _ => panic!("expecting a label"),
+        },
+        _ => (None, loops.last().expect("should be inside a loop")),
+    };
 
-        _ => parse_expr_stmt(input, stack, allow_stmt_expr),
+    if token == Token::Continue {
+        return Ok(Stmt::Continue(label, pos));
     }
+
+    // break 'label expr
+    let value = match input.peek().unwrap() {
+        (Token::SemiColon, _) | (Token::RightBrace, _) | (Token::EOF, _) => None,
+        (_, pos) if !target.is_loop => return Err(PERR::LoopBreakValue.into_err(*pos)),
+        _ => Some(Box::new(parse_expr(input, state, allow_stmt_expr)?)),
+    };
+
+    Ok(Stmt::Break(label, value, pos))
 }
 
 /// Parse a function definition.
 fn parse_fn<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-    stack: &mut Stack,
+    state: &mut ParseState,
     allow_stmt_expr: bool,
 ) -> Result<FnDef, Box<ParseError>> {
     let pos = input.next().expect("should be fn").1;
@@ -1798,32 +2404,82 @@ fn parse_fn<'a>(
     };
 
     let mut params = Vec::new();
+    let mut param_types = Vec::new();
+    let mut param_defaults = Vec::new();
+    let mut rest_param = false;
 
     if !match_token(input, Token::RightParen)? {
         let end_err = format!("to close the parameters list of function '{}'", name);
         let sep_err = format!("to separate the parameters of function '{}'", name);
 
         loop {
+            // Rest parameter - ...name
+            #[cfg(not(feature = "no_index"))]
+            let is_rest = match_token(input, Token::Ellipsis)?;
+            #[cfg(feature = "no_index")]
+            let is_rest = false;
+
             match input.next().unwrap() {
-                (Token::Identifier(s), pos) => {
-                    stack.push(s.clone());
-                    params.push((s, pos))
+                (Token::Identifier(s), pos) => params.push((s, pos)),
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                (_, pos) => return Err(PERR::MissingToken(")".into(), end_err).into_err(pos)),
+            }
+
+            if is_rest {
+                state.push(params.last().unwrap().0.clone());
+                param_types.push(None);
+                param_defaults.push(None);
+                rest_param = true;
+
+                // The rest parameter must be the last one
+                match input.next().unwrap() {
+                    (Token::RightParen, _) => break,
+                    (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                    (_, pos) => return Err(PERR::MissingToken(")".into(), end_err).into_err(pos)),
                 }
-                (Token::LexError(err), pos) => {
-                    return Err(PERR::BadInput(err.to_string()).into_err(pos))
+            }
+
+            // Optional type annotation - param: Type
+            if match_token(input, Token::Colon)? {
+                match input.next().unwrap() {
+                    (Token::Identifier(s), _) => param_types.push(Some(s)),
+                    (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                    (_, pos) => {
+                        let (param, _) = params.last().unwrap();
+                        return Err(PERR::FnMissingParamType(name, param.to_string()).into_err(pos));
+                    }
                 }
-                (_, pos) => return Err(PERR::MissingToken(")".into(), end_err).into_err(pos)),
+            } else {
+                param_types.push(None);
             }
 
+            // Optional default value - param = expr
+            // The default value can refer to the parameters before it, but not itself.
+            if match_token(input, Token::Equals)? {
+                param_defaults.push(Some(parse_expr(input, state, allow_stmt_expr)?));
+            } else if param_defaults.iter().any(Option::is_some) {
+                let (param, pos) = params.last().unwrap();
+                return Err(PERR::MissingToken(
+                    "=".into(),
+                    format!(
+                        "to give a default value to parameter '{}' of function '{}'",
+                        param, name
+                    ),
+                )
+                .into_err(*pos));
+            } else {
+                param_defaults.push(None);
+            }
+
+            state.push(params.last().unwrap().0.clone());
+
             match input.next().unwrap() {
                 (Token::RightParen, _) => break,
                 (Token::Comma, _) => (),
                 (Token::Identifier(_), pos) => {
                     return Err(PERR::MissingToken(",".into(), sep_err).into_err(pos))
                 }
-                (Token::LexError(err), pos) => {
-                    return Err(PERR::BadInput(err.to_string()).into_err(pos))
-                }
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
                 (_, pos) => return Err(PERR::MissingToken(",".into(), sep_err).into_err(pos)),
             }
         }
@@ -1846,7 +2502,7 @@ fn parse_fn<'a>(
 
     // Parse function body
     let body = Box::new(match input.peek().unwrap() {
-        (Token::LeftBrace, _) => parse_block(input, stack, false, allow_stmt_expr)?,
+        (Token::LeftBrace, _) => parse_block(input, state, &[], allow_stmt_expr)?,
         (_, pos) => return Err(PERR::FnMissingBody(name).into_err(*pos)),
     });
 
@@ -1855,19 +2511,120 @@ fn parse_fn<'a>(
     Ok(FnDef {
         name,
         params,
+        param_types,
+        param_defaults,
+        rest_param,
         body,
         pos,
     })
 }
 
+/// Parse a closure, i.e. an anonymous function: `|params| expr`.
+///
+/// The closure is lifted into a script-defined function with a generated name, with the variables
+/// it captures from the enclosing scopes prepended to its parameters.  The closure itself becomes
+/// a function pointer to that function, with the current values of the captured variables curried.
+#[cfg(not(feature = "no_function"))]
+fn parse_anon_fn<'a>(
+    input: &mut Peekable<TokenIterator<'a>>,
+    state: &mut ParseState,
+    token: Token,
+    pos: Position,
+) -> Result<Expr, Box<ParseError>> {
+    // Closures define functions, so they are disabled together with `fn`
+    if state.engine.disabled_symbols.contains("fn") {
+        return Err(PERR::DisabledSymbol("fn".into()).into_err(pos));
+    }
+
+    let mut new_state = state.new_closure();
+    let mut params = Vec::new();
+
+    // || has no parameters
+    if token == Token::Pipe && !match_token(input, Token::Pipe)? {
+        let end_err = "to close the parameters list of the anonymous function".to_string();
+        let sep_err = "to separate the parameters of the anonymous function".to_string();
+
+        loop {
+            match input.next().unwrap() {
+                (Token::Identifier(s), _) => {
+                    new_state.push(s.clone());
+                    params.push(s);
+                }
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                (_, pos) => return Err(PERR::MissingToken("|".into(), end_err).into_err(pos)),
+            }
+
+            match input.next().unwrap() {
+                (Token::Pipe, _) => break,
+                (Token::Comma, _) => (),
+                (Token::Identifier(_), pos) => {
+                    return Err(PERR::MissingToken(",".into(), sep_err).into_err(pos))
+                }
+                (Token::LexError(err), pos) => return Err(err.to_err(pos)),
+                (_, pos) => return Err(PERR::MissingToken("|".into(), end_err).into_err(pos)),
+            }
+        }
+    }
+
+    // Parse the closure body
+    let body = parse_expr(input, &mut new_state, true)?;
+
+    // Captured variables are passed in before the parameters
+    let params: Vec<_> = new_state.captures.iter().cloned().chain(params).collect();
+    let body = Stmt::Expr(Box::new(body));
+    let name = format!(
+        "{}{:016x}",
+        FN_ANONYMOUS,
+        calc_fn_def(&format!("{:?}{:?}", params, body))
+    );
+
+    let captures: Vec<_> = new_state
+        .captures
+        .into_iter()
+        .map(|var| {
+            let index = state.access_var(&var);
+            Expr::Variable(var, index, pos)
+        })
+        .collect();
+
+    state.functions.extend(new_state.functions);
+    state.functions.push(FnDef {
+        name: name.clone(),
+        param_types: vec![None; params.len()],
+        param_defaults: vec![None; params.len()],
+        params,
+        rest_param: false,
+        body: Box::new(body),
+        pos,
+    });
+
+    let fn_ptr = Expr::FunctionCall(
+        KEYWORD_FN_PTR.into(),
+        Box::new(vec![Expr::StringConstant(name, pos)]),
+        None,
+        pos,
+    );
+
+    Ok(if captures.is_empty() {
+        fn_ptr
+    } else {
+        Expr::FunctionCall(
+            KEYWORD_FN_PTR_CURRY.into(),
+            Box::new(once(fn_ptr).chain(captures).collect()),
+            None,
+            pos,
+        )
+    })
+}
+
 pub fn parse_global_expr<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
     engine: &Engine,
     scope: &Scope,
     optimization_level: OptimizationLevel,
 ) -> Result<AST, Box<ParseError>> {
-    let mut stack = Stack::new();
-    let expr = parse_expr(input, &mut stack, false)?;
+    let mut state = ParseState::new_with_scope(engine, scope);
+    let expr = parse_expr(input, &mut state, false)?;
 
     match input.peek().unwrap() {
         (Token::EOF, _) => (),
@@ -1883,7 +2640,7 @@ pub fn parse_global_expr<'a>(
             engine,
             scope,
             vec![Stmt::Expr(Box::new(expr))],
-            vec![],
+            state.functions,
             optimization_level,
         ),
     )
@@ -1892,25 +2649,35 @@ pub fn parse_global_expr<'a>(
 /// Parse the global level statements.
 fn parse_global_level<'a>(
     input: &mut Peekable<TokenIterator<'a>>,
-) -> Result<(Vec<Stmt>, HashMap<u64, FnDef>), Box<ParseError>> {
+    engine: &Engine,
+    scope: &Scope,
+) -> Result<(Vec<Stmt>, Vec<FnDef>), Box<ParseError>> {
     let mut statements = Vec::<Stmt>::new();
-    let mut functions = HashMap::<u64, FnDef>::new();
-    let mut stack = Stack::new();
+    let mut functions = Vec::<FnDef>::new();
+    let mut state = ParseState::new_with_scope(engine, scope);
 
     while !input.peek().unwrap().0.is_eof() {
         // Collect all the function definitions
         #[cfg(not(feature = "no_function"))]
         {
             if let (Token::Fn, _) = input.peek().unwrap() {
-                let mut stack = Stack::new();
-                let f = parse_fn(input, &mut stack, true)?;
-                functions.insert(calc_fn_def(&f.name, f.params.len()), f);
+                let mut state = ParseState::new(engine);
+                let f = parse_fn(input, &mut state, true)?;
+
+                // A later definition with the same signature replaces the earlier one
+                match functions.iter_mut().find(|x| x.same_signature(&f)) {
+                    Some(x) => *x = f,
+                    None => functions.push(f),
+                }
+
+                // Functions lifted out of closures in the function body
+                functions.extend(state.functions);
                 continue;
             }
         }
 
         // Actual statement
-        let stmt = parse_stmt(input, &mut stack, false, true)?;
+        let stmt = parse_stmt(input, &mut state, &[], true)?;
 
         let need_semicolon = !stmt.is_self_terminated();
 
@@ -1928,9 +2695,7 @@ fn parse_global_level<'a>(
             // { stmt } ???
             (_, _) if !need_semicolon => (),
             // stmt <error>
-            (Token::LexError(err), pos) => {
-                return Err(PERR::BadInput(err.to_string()).into_err(*pos))
-            }
+            (Token::LexError(err), pos) => return Err(err.to_err(*pos)),
             // stmt ???
             (_, pos) => {
                 // Semicolons are not optional between statements
@@ -1942,6 +2707,9 @@ fn parse_global_level<'a>(
         }
     }
 
+    // Functions lifted out of closures at global level
+    functions.extend(state.functions);
+
     Ok((statements, functions))
 }
 
@@ -1952,9 +2720,8 @@ pub fn parse<'a>(
     scope: &Scope,
     optimization_level: OptimizationLevel,
 ) -> Result<AST, Box<ParseError>> {
-    let (statements, functions) = parse_global_level(input)?;
+    let (statements, fn_lib) = parse_global_level(input, engine, scope)?;
 
-    let fn_lib = functions.into_iter().map(|(_, v)| v).collect();
     Ok(
         // Optimize AST
         optimize_into_ast(engine, scope, statements, fn_lib, optimization_level),
@@ -59,6 +59,9 @@ pub enum EvalAltResult {
     ErrorLogicGuard(Position),
     /// The `for` statement encounters a type that is not an iterator.
     ErrorFor(Position),
+    /// A value cannot be destructured by a `let` or `for` pattern.
+    /// Wrapped values are the type required by the pattern and the type of the actual value.
+    ErrorPatternMismatch(String, String, Position),
     /// Usage of an unknown variable. Wrapped value is the name of the variable.
     ErrorVariableNotFound(String, Position),
     /// Assignment to an inappropriate LHS (left-hand-side) expression.
@@ -68,6 +71,9 @@ pub enum EvalAltResult {
     /// Returned type is not the same as the required output type.
     /// Wrapped value is the type of the actual result.
     ErrorMismatchOutputType(String, Position),
+    /// Data is not of the required type.
+    /// Wrapped values are the type requested and the type of the actual data.
+    ErrorMismatchDataType(String, String, Position),
     /// Inappropriate member access.
     ErrorDotExpr(String, Position),
     /// Arithmetic error encountered. Wrapped value is the error message.
@@ -80,7 +86,9 @@ pub enum EvalAltResult {
     /// Breaking out of loops - not an error if within a loop.
     /// The wrapped value, if true, means breaking clean out of the loop (i.e. a `break` statement).
     /// The wrapped value, if false, means breaking the current context (i.e. a `continue` statement).
-    ErrorLoopBreak(bool, Position),
+    /// The wrapped label, if any, names the loop to break out of.
+    /// The wrapped `Dynamic` is the value carried by a `break` statement, or `()` if none.
+    ErrorLoopBreak(bool, Option<String>, Dynamic, Position),
     /// Not an error: Value returned from a script via the `return` keyword.
     /// Wrapped value is the result value.
     Return(Dynamic, Position),
@@ -118,19 +126,21 @@ impl EvalAltResult {
             Self::ErrorStringBounds(_, _, _) => "String index out of bounds",
             Self::ErrorLogicGuard(_) => "Boolean value expected",
             Self::ErrorFor(_) => "For loop expects an array, object map, or range",
+            Self::ErrorPatternMismatch(_, _, _) => "Value does not match the destructuring pattern",
             Self::ErrorVariableNotFound(_, _) => "Variable not found",
             Self::ErrorAssignmentToUnknownLHS(_) => {
                 "Assignment to an unsupported left-hand side expression"
             }
             Self::ErrorAssignmentToConstant(_, _) => "Assignment to a constant variable",
             Self::ErrorMismatchOutputType(_, _) => "Output type is incorrect",
+            Self::ErrorMismatchDataType(_, _, _) => "Data type is incorrect",
             Self::ErrorInExpr(_) => "Malformed 'in' expression",
             Self::ErrorDotExpr(_, _) => "Malformed dot expression",
             Self::ErrorArithmetic(_, _) => "Arithmetic error",
             Self::ErrorStackOverflow(_) => "Stack overflow",
             Self::ErrorRuntime(_, _) => "Runtime error",
-            Self::ErrorLoopBreak(true, _) => "Break statement not inside a loop",
-            Self::ErrorLoopBreak(false, _) => "Continue statement not inside a loop",
+            Self::ErrorLoopBreak(true, _, _, _) => "Break statement not inside a loop",
+            Self::ErrorLoopBreak(false, _, _, _) => "Continue statement not inside a loop",
             Self::Return(_, _) => "[Not Error] Function returns value",
         }
     }
@@ -171,9 +181,21 @@ impl fmt::Display for EvalAltResult {
 
             Self::ErrorAssignmentToConstant(s, pos) => write!(f, "{}: '{}' ({})", desc, s, pos),
             Self::ErrorMismatchOutputType(s, pos) => write!(f, "{}: {} ({})", desc, s, pos),
+            Self::ErrorMismatchDataType(need, actual, pos) => {
+                write!(
+                    f,
+                    "{}: expecting {} but got {} ({})",
+                    desc, need, actual, pos
+                )
+            }
+            Self::ErrorPatternMismatch(need, actual, pos) => write!(
+                f,
+                "Destructuring pattern expects {} but got {} ({})",
+                need, actual, pos
+            ),
             Self::ErrorArithmetic(s, pos) => write!(f, "{} ({})", s, pos),
 
-            Self::ErrorLoopBreak(_, pos) => write!(f, "{} ({})", desc, pos),
+            Self::ErrorLoopBreak(_, _, _, pos) => write!(f, "{} ({})", desc, pos),
             Self::Return(_, pos) => write!(f, "{} ({})", desc, pos),
 
             Self::ErrorFunctionArgsMismatch(fn_name, 0, n, pos) => write!(
@@ -268,16 +290,18 @@ impl EvalAltResult {
             | Self::ErrorStringIndexExpr(pos)
             | Self::ErrorLogicGuard(pos)
             | Self::ErrorFor(pos)
+            | Self::ErrorPatternMismatch(_, _, pos)
             | Self::ErrorVariableNotFound(_, pos)
             | Self::ErrorAssignmentToUnknownLHS(pos)
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, pos)
+            | Self::ErrorMismatchDataType(_, _, pos)
             | Self::ErrorInExpr(pos)
             | Self::ErrorDotExpr(_, pos)
             | Self::ErrorArithmetic(_, pos)
             | Self::ErrorStackOverflow(pos)
             | Self::ErrorRuntime(_, pos)
-            | Self::ErrorLoopBreak(_, pos)
+            | Self::ErrorLoopBreak(_, _, _, pos)
             | Self::Return(_, pos) => *pos,
         }
     }
@@ -302,16 +326,18 @@ impl EvalAltResult {
             | Self::ErrorStringIndexExpr(pos)
             | Self::ErrorLogicGuard(pos)
             | Self::ErrorFor(pos)
+            | Self::ErrorPatternMismatch(_, _, pos)
             | Self::ErrorVariableNotFound(_, pos)
             | Self::ErrorAssignmentToUnknownLHS(pos)
             | Self::ErrorAssignmentToConstant(_, pos)
             | Self::ErrorMismatchOutputType(_, pos)
+            | Self::ErrorMismatchDataType(_, _, pos)
             | Self::ErrorInExpr(pos)
             | Self::ErrorDotExpr(_, pos)
             | Self::ErrorArithmetic(_, pos)
             | Self::ErrorStackOverflow(pos)
             | Self::ErrorRuntime(_, pos)
-            | Self::ErrorLoopBreak(_, pos)
+            | Self::ErrorLoopBreak(_, _, _, pos)
             | Self::Return(_, pos) => *pos = new_position,
         }
 
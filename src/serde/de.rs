@@ -0,0 +1,519 @@
+//! Implement deserialization support of `Dynamic` for [`serde`](https://crates.io/crates/serde).
+
+use crate::any::{Dynamic, Union};
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+use serde::de::{
+    value::BorrowedStrDeserializer, DeserializeSeed, Deserializer, EnumAccess, Error,
+    IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde::Deserialize;
+
+use crate::stdlib::{
+    boxed::Box,
+    fmt,
+    string::{String, ToString},
+};
+
+/// Deserializer for `Dynamic` which is kept as a reference.
+///
+/// The reference is necessary because the deserialized type may hold references
+/// (especially `&str`) to the source `Dynamic`.
+pub struct DynamicDeserializer<'a> {
+    value: &'a Dynamic,
+}
+
+impl<'de> DynamicDeserializer<'de> {
+    /// Create a `DynamicDeserializer` from a reference to a `Dynamic` value.
+    ///
+    /// The reference is necessary because the deserialized type may hold references
+    /// (especially `&str`) to the source `Dynamic`.
+    pub fn from_dynamic(value: &'de Dynamic) -> Self {
+        Self { value }
+    }
+    /// Shortcut for a type conversion error with the name of the expected type.
+    fn type_error<T>(&self, name: &str) -> Result<T, Box<EvalAltResult>> {
+        Err(Box::new(EvalAltResult::ErrorMismatchDataType(
+            name.into(),
+            self.value.type_name().into(),
+            Position::none(),
+        )))
+    }
+}
+
+/// Deserialize a `Dynamic` value into a Rust type that implements `serde::Deserialize`.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// # #[cfg(not(feature = "no_index"))]
+/// # #[cfg(not(feature = "no_object"))]
+/// # {
+/// use rhai::{Dynamic, Array, Map, INT};
+/// use rhai::from_dynamic;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct Hello {
+///     a: INT,
+///     b: bool,
+/// }
+///
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct Test {
+///     int: u32,
+///     seq: Vec<String>,
+///     obj: Hello,
+/// }
+///
+/// let mut map = Map::new();
+/// map.insert("int".into(), Dynamic::from(42_u32));
+///
+/// let mut map2 = Map::new();
+/// map2.insert("a".into(), (123 as INT).into());
+/// map2.insert("b".into(), true.into());
+///
+/// map.insert("obj".into(), map2.into());
+///
+/// let arr: Array = vec!["foo".to_string().into(), "bar".to_string().into()];
+/// map.insert("seq".into(), arr.into());
+///
+/// let value: Test = from_dynamic(&map.into())?;
+///
+/// let expected = Test {
+///     int: 42,
+///     seq: vec!["foo".into(), "bar".into()],
+///     obj: Hello { a: 123, b: true },
+/// };
+///
+/// assert_eq!(value, expected);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_dynamic<'de, T: Deserialize<'de>>(
+    value: &'de Dynamic,
+) -> Result<T, Box<EvalAltResult>> {
+    T::deserialize(&mut DynamicDeserializer::from_dynamic(value))
+}
+
+impl Error for Box<EvalAltResult> {
+    fn custom<T: fmt::Display>(err: T) -> Self {
+        Box::new(EvalAltResult::ErrorRuntime(
+            err.to_string(),
+            Position::none(),
+        ))
+    }
+}
+
+impl<'de> Deserializer<'de> for &mut DynamicDeserializer<'de> {
+    type Error = Box<EvalAltResult>;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match &self.value.0 {
+            Union::Unit(_) => self.deserialize_unit(visitor),
+            Union::Bool(_) => self.deserialize_bool(visitor),
+            Union::Str(_) => self.deserialize_str(visitor),
+            Union::Char(_) => self.deserialize_char(visitor),
+            #[cfg(not(feature = "only_i32"))]
+            Union::Int(_) => self.deserialize_i64(visitor),
+            #[cfg(feature = "only_i32")]
+            Union::Int(_) => self.deserialize_i32(visitor),
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(_) => self.deserialize_f64(visitor),
+            Union::Array(_) => self.deserialize_seq(visitor),
+            Union::Map(_) => self.deserialize_map(visitor),
+
+            Union::Variant(value) if value.is::<i8>() => self.deserialize_i8(visitor),
+            Union::Variant(value) if value.is::<i16>() => self.deserialize_i16(visitor),
+            Union::Variant(value) if value.is::<i32>() => self.deserialize_i32(visitor),
+            Union::Variant(value) if value.is::<i64>() => self.deserialize_i64(visitor),
+            Union::Variant(value) if value.is::<i128>() => self.deserialize_i128(visitor),
+            Union::Variant(value) if value.is::<u8>() => self.deserialize_u8(visitor),
+            Union::Variant(value) if value.is::<u16>() => self.deserialize_u16(visitor),
+            Union::Variant(value) if value.is::<u32>() => self.deserialize_u32(visitor),
+            Union::Variant(value) if value.is::<u64>() => self.deserialize_u64(visitor),
+            Union::Variant(value) if value.is::<u128>() => self.deserialize_u128(visitor),
+            Union::Variant(value) if value.is::<f32>() => self.deserialize_f32(visitor),
+            Union::Variant(value) if value.is::<f64>() => self.deserialize_f64(visitor),
+
+            Union::Variant(_) => self.type_error("any"),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(self.value.as_bool().or_else(|_| self.type_error("bool"))?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<i8>() {
+            Some(&x) => visitor.visit_i8(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<i16>() {
+            Some(&x) => visitor.visit_i16(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<i32>() {
+            Some(&x) => visitor.visit_i32(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<i64>() {
+            Some(&x) => visitor.visit_i64(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<i128>() {
+            Some(&x) => visitor.visit_i128(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<u8>() {
+            Some(&x) => visitor.visit_u8(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<u16>() {
+            Some(&x) => visitor.visit_u16(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<u32>() {
+            Some(&x) => visitor.visit_u32(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<u64>() {
+            Some(&x) => visitor.visit_u64(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<u128>() {
+            Some(&x) => visitor.visit_u128(x),
+            None => self.deserialize_int(visitor),
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<f32>() {
+            Some(&x) => visitor.visit_f32(x),
+            None => self.deserialize_float(visitor),
+        }
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.downcast_ref::<f64>() {
+            Some(&x) => visitor.visit_f64(x),
+            None => self.deserialize_float(visitor),
+        }
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match &self.value.0 {
+            Union::Char(ch) => visitor.visit_char(*ch),
+            // A single-character string is also accepted
+            Union::Str(s) if s.chars().count() == 1 => {
+                visitor.visit_char(s.chars().next().unwrap())
+            }
+            _ => self.type_error("char"),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match &self.value.0 {
+            Union::Str(s) => visitor.visit_borrowed_str(s.as_str()),
+            _ => self.type_error("string"),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.value.is::<()>() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value.0 {
+            Union::Unit(_) => visitor.visit_unit(),
+            _ => self.type_error("()"),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match &self.value.0 {
+            Union::Array(arr) => visitor.visit_seq(IterateArray::new(arr.iter())),
+            _ => self.type_error("array"),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match &self.value.0 {
+            Union::Map(map) => visitor.visit_map(IterateMap::new(map.iter())),
+            _ => self.type_error("map"),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match &self.value.0 {
+            // Unit variant
+            Union::Str(s) => visitor.visit_enum(s.as_str().into_deserializer()),
+            // Newtype, tuple or struct variant in the form of a single-entry map
+            Union::Map(map) if map.len() == 1 => {
+                let (tag, content) = map.iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer {
+                    tag,
+                    content: DynamicDeserializer::from_dynamic(content),
+                })
+            }
+            _ => self.type_error("enum"),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+impl<'de> DynamicDeserializer<'de> {
+    /// Deserialize the system integer type `INT`, which is converted by the visitor if necessary.
+    fn deserialize_int<V: Visitor<'de>>(&self, visitor: V) -> Result<V::Value, Box<EvalAltResult>> {
+        match self.value.0 {
+            #[cfg(not(feature = "only_i32"))]
+            Union::Int(x) => visitor.visit_i64(x),
+            #[cfg(feature = "only_i32")]
+            Union::Int(x) => visitor.visit_i32(x),
+            _ => self.type_error("integer"),
+        }
+    }
+
+    /// Deserialize the system floating-point type `FLOAT`, which is converted by the visitor if necessary.
+    /// Integers are also accepted.
+    fn deserialize_float<V: Visitor<'de>>(
+        &self,
+        visitor: V,
+    ) -> Result<V::Value, Box<EvalAltResult>> {
+        match self.value.0 {
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(x) => visitor.visit_f64(x),
+            Union::Int(_) => self.deserialize_int(visitor),
+            _ => self.type_error("float"),
+        }
+    }
+}
+
+/// `SeqAccess` implementation for arrays.
+struct IterateArray<'a, ITER: Iterator<Item = &'a Dynamic>> {
+    /// Iterator for a stream of `Dynamic` values.
+    iter: ITER,
+}
+
+impl<'a, ITER: Iterator<Item = &'a Dynamic>> IterateArray<'a, ITER> {
+    pub fn new(iter: ITER) -> Self {
+        Self { iter }
+    }
+}
+
+impl<'a: 'de, 'de, ITER: Iterator<Item = &'a Dynamic>> SeqAccess<'de> for IterateArray<'a, ITER> {
+    type Error = Box<EvalAltResult>;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Box<EvalAltResult>> {
+        // Deserialize each item coming out of the iterator.
+        match self.iter.next() {
+            None => Ok(None),
+            Some(item) => seed
+                .deserialize(&mut DynamicDeserializer::from_dynamic(item))
+                .map(Some),
+        }
+    }
+}
+
+/// `MapAccess` implementation for maps.
+struct IterateMap<'a, ITER: Iterator<Item = (&'a String, &'a Dynamic)>> {
+    /// Iterator for a stream of key-value pairs.
+    iter: ITER,
+    /// Value of the current entry, whose key has been deserialized.
+    value: Option<&'a Dynamic>,
+}
+
+impl<'a, ITER: Iterator<Item = (&'a String, &'a Dynamic)>> IterateMap<'a, ITER> {
+    pub fn new(iter: ITER) -> Self {
+        Self { iter, value: None }
+    }
+}
+
+impl<'a: 'de, 'de, ITER: Iterator<Item = (&'a String, &'a Dynamic)>> MapAccess<'de>
+    for IterateMap<'a, ITER>
+{
+    type Error = Box<EvalAltResult>;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Box<EvalAltResult>> {
+        // Deserialize each key coming out of the iterator, saving the value for later.
+        match self.iter.next() {
+            None => Ok(None),
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(BorrowedStrDeserializer::new(key.as_str()))
+                    .map(Some)
+            }
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Box<EvalAltResult>> {
+        // Deserialize the value saved by `next_key_seed`.
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+
+        seed.deserialize(&mut DynamicDeserializer::from_dynamic(value))
+    }
+}
+
+/// `EnumAccess` implementation for enum variants in the form of a single-entry map.
+struct EnumDeserializer<'t, 'de: 't> {
+    /// Name of the enum variant.
+    tag: &'t str,
+    /// Content of the enum variant.
+    content: DynamicDeserializer<'de>,
+}
+
+impl<'t, 'de> EnumAccess<'de> for EnumDeserializer<'t, 'de> {
+    type Error = Box<EvalAltResult>;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        seed.deserialize(self.tag.into_deserializer())
+            .map(|v| (v, self))
+    }
+}
+
+impl<'t, 'de> VariantAccess<'de> for EnumDeserializer<'t, 'de> {
+    type Error = Box<EvalAltResult>;
+
+    fn unit_variant(mut self) -> Result<(), Self::Error> {
+        Deserialize::deserialize(&mut self.content)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        mut self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(&mut self.content)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        mut self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.content.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        mut self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.content.deserialize_struct("", fields, visitor)
+    }
+}
@@ -0,0 +1,4 @@
+//! Helper module defining serialization/deserialization support for [`serde`](https://crates.io/crates/serde).
+
+pub mod de;
+pub mod ser;
@@ -0,0 +1,442 @@
+//! Implement serialization support of `Dynamic` for [`serde`](https://crates.io/crates/serde).
+
+use crate::any::{Dynamic, Union, Variant};
+use crate::engine::{Array, Map};
+use crate::parser::INT;
+use crate::result::EvalAltResult;
+use crate::token::Position;
+
+use serde::ser::{
+    Error, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+use serde::Serialize;
+
+use crate::stdlib::{
+    boxed::Box,
+    convert::TryFrom,
+    fmt,
+    string::{String, ToString},
+};
+
+/// Serializer for `Dynamic` which produces a `Dynamic` value.
+pub struct DynamicSerializer;
+
+/// Serialize a Rust type that implements `serde::Serialize` into a `Dynamic`.
+///
+/// Structs and maps become object maps, sequences and tuples become arrays.
+/// Unit enum variants become strings, other enum variants become object maps
+/// with the variant name as the single key.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// # #[cfg(not(feature = "no_index"))]
+/// # #[cfg(not(feature = "no_object"))]
+/// # #[cfg(not(feature = "no_float"))]
+/// # {
+/// use rhai::{Map, to_dynamic};
+///
+/// #[derive(Debug, serde::Serialize, PartialEq)]
+/// struct Point {
+///     x: f64,
+///     y: f64
+/// }
+///
+/// #[derive(Debug, serde::Serialize, PartialEq)]
+/// struct MyStruct {
+///     a: i64,
+///     b: Vec<String>,
+///     c: bool,
+///     d: Point
+/// }
+///
+/// let x = MyStruct {
+///     a: 42,
+///     b: vec![ "hello".into(), "world".into() ],
+///     c: true,
+///     d: Point { x: 123.456, y: 999.0 }
+/// };
+///
+/// // Convert the 'MyStruct' into a 'Dynamic'
+/// let value = to_dynamic(x)?;
+///
+/// assert!(value.is::<Map>());
+///
+/// let map = value.cast::<Map>();
+/// let point = map.get("d").unwrap().downcast_ref::<Map>().unwrap();
+/// assert_eq!(*point.get("x").unwrap().downcast_ref::<f64>().unwrap(), 123.456);
+/// assert_eq!(*point.get("y").unwrap().downcast_ref::<f64>().unwrap(), 999.0);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_dynamic<T: Serialize>(value: T) -> Result<Dynamic, Box<EvalAltResult>> {
+    value.serialize(DynamicSerializer)
+}
+
+impl Error for Box<EvalAltResult> {
+    fn custom<T: fmt::Display>(err: T) -> Self {
+        Box::new(EvalAltResult::ErrorRuntime(
+            err.to_string(),
+            Position::none(),
+        ))
+    }
+}
+
+/// Convert an integer into the system integer type `INT` if it fits,
+/// otherwise keep it as its own type.
+fn integer<T: Variant + Clone>(value: T) -> Dynamic
+where
+    INT: TryFrom<T>,
+{
+    match INT::try_from(value.clone()) {
+        Ok(x) => x.into(),
+        Err(_) => Dynamic::from(value),
+    }
+}
+
+/// Convert a floating-point number into the system floating-point type `FLOAT`,
+/// or keep it as its own type under `no_float`.
+#[cfg(not(feature = "no_float"))]
+fn float<T: Into<crate::parser::FLOAT>>(value: T) -> Dynamic {
+    value.into().into()
+}
+#[cfg(feature = "no_float")]
+fn float<T: Variant + Clone>(value: T) -> Dynamic {
+    Dynamic::from(value)
+}
+
+impl Serializer for DynamicSerializer {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = VariantSerializer<SeqSerializer>;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = VariantSerializer<MapSerializer>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(integer(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string().into())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(Dynamic(Union::Array(Box::new(
+            v.iter().map(|&b| integer(b)).collect(),
+        ))))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(().into())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(().into())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(make_variant(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SeqSerializer {
+            array: Array::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapSerializer {
+            map: Map::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+/// Wrap the content of an enum variant into a single-entry object map keyed by the variant name.
+fn make_variant(variant: &str, content: Dynamic) -> Dynamic {
+    let mut map = Map::with_capacity(1);
+    map.insert(variant.to_string(), content);
+    Dynamic(Union::Map(Box::new(map)))
+}
+
+/// Serializer for sequences, tuples and tuple structs, producing an array.
+pub struct SeqSerializer {
+    array: Array,
+}
+
+impl SerializeSeq for SeqSerializer {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.array.push(value.serialize(DynamicSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Dynamic(Union::Array(Box::new(self.array))))
+    }
+}
+
+impl SerializeTuple for SeqSerializer {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl SerializeTupleStruct for SeqSerializer {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+/// Serializer for maps and structs, producing an object map.
+pub struct MapSerializer {
+    map: Map,
+    /// Key of the current entry, waiting for its value.
+    key: Option<String>,
+}
+
+impl SerializeMap for MapSerializer {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        let key = key.serialize(DynamicSerializer)?;
+
+        // Object map keys must be strings
+        self.key = Some(match key.0 {
+            Union::Str(s) => *s,
+            Union::Char(ch) => ch.to_string(),
+            _ => {
+                return Err(Box::new(EvalAltResult::ErrorMismatchDataType(
+                    "string".into(),
+                    key.type_name().into(),
+                    Position::none(),
+                )))
+            }
+        });
+
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+
+        self.map.insert(key, value.serialize(DynamicSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Dynamic(Union::Map(Box::new(self.map))))
+    }
+}
+
+impl SerializeStruct for MapSerializer {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.map
+            .insert(key.to_string(), value.serialize(DynamicSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeMap::end(self)
+    }
+}
+
+/// Serializer for tuple and struct enum variants, producing a single-entry object map
+/// keyed by the variant name.
+pub struct VariantSerializer<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl SerializeTupleVariant for VariantSerializer<SeqSerializer> {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(make_variant(self.variant, SerializeSeq::end(self.inner)?))
+    }
+}
+
+impl SerializeStructVariant for VariantSerializer<MapSerializer> {
+    type Ok = Dynamic;
+    type Error = Box<EvalAltResult>;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(make_variant(self.variant, SerializeMap::end(self.inner)?))
+    }
+}
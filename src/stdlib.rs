@@ -13,7 +13,7 @@ mod inner {
     pub use core_error as error;
 
     pub mod collections {
-        pub use hashbrown::HashMap;
+        pub use hashbrown::{HashMap, HashSet};
     }
 }
 
@@ -0,0 +1,227 @@
+//! Module containing support for custom syntax extensions.
+
+use crate::any::Dynamic;
+use crate::engine::{Engine, FunctionsLib, State};
+use crate::parser::Expr;
+use crate::result::EvalAltResult;
+use crate::scope::Scope;
+use crate::token::{is_identifier, lex, Position, Token};
+
+use crate::stdlib::{
+    boxed::Box,
+    collections::{HashMap, HashSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Marker in a custom syntax pattern standing for an expression.
+pub const MARKER_EXPR: &str = "$expr$";
+/// Marker in a custom syntax pattern standing for a statement block.
+pub const MARKER_BLOCK: &str = "$block$";
+/// Marker in a custom syntax pattern standing for an identifier.
+pub const MARKER_IDENT: &str = "$ident$";
+
+// Define callback function types
+#[cfg(feature = "sync")]
+pub trait CustomSyntaxCallback:
+    Fn(&mut EvalContext, &[Expression]) -> Result<Dynamic, Box<EvalAltResult>> + Send + Sync + 'static
+{
+}
+#[cfg(feature = "sync")]
+impl<
+        F: Fn(&mut EvalContext, &[Expression]) -> Result<Dynamic, Box<EvalAltResult>>
+            + Send
+            + Sync
+            + 'static,
+    > CustomSyntaxCallback for F
+{
+}
+
+#[cfg(not(feature = "sync"))]
+pub trait CustomSyntaxCallback:
+    Fn(&mut EvalContext, &[Expression]) -> Result<Dynamic, Box<EvalAltResult>> + 'static
+{
+}
+#[cfg(not(feature = "sync"))]
+impl<F: Fn(&mut EvalContext, &[Expression]) -> Result<Dynamic, Box<EvalAltResult>> + 'static>
+    CustomSyntaxCallback for F
+{
+}
+
+/// A custom syntax registered with the `Engine`.
+pub struct CustomSyntax {
+    /// The segments of the pattern, starting with the keyword.
+    pub segments: Vec<String>,
+    /// The callback evaluating the custom syntax.
+    pub func: Box<dyn CustomSyntaxCallback>,
+}
+
+/// An input captured by a custom syntax: an expression, a statement block or an identifier.
+#[derive(Debug, Clone, Copy)]
+pub struct Expression<'a>(&'a Expr);
+
+impl<'a> From<&'a Expr> for Expression<'a> {
+    fn from(expr: &'a Expr) -> Self {
+        Self(expr)
+    }
+}
+
+impl Expression<'_> {
+    /// If this input is a variable name, return it.
+    ///
+    /// This is always the case for inputs captured by `$ident$`.
+    pub fn get_variable_name(&self) -> Option<&str> {
+        match self.0 {
+            Expr::Variable(name, _, _) => Some(name),
+            _ => None,
+        }
+    }
+    /// Get the `Position` of this input.
+    pub fn position(&self) -> Position {
+        self.0.position()
+    }
+}
+
+/// The context of a custom syntax evaluation, used to evaluate the captured inputs on demand.
+pub struct EvalContext<'a, 's> {
+    pub(crate) engine: &'a Engine,
+    pub(crate) scope: &'a mut Scope<'s>,
+    pub(crate) state: &'a mut State,
+    pub(crate) fn_lib: &'a FunctionsLib,
+    pub(crate) level: usize,
+}
+
+impl<'a, 's> EvalContext<'a, 's> {
+    /// Get the scripting `Engine`.
+    pub fn engine(&self) -> &Engine {
+        self.engine
+    }
+    /// Get the current `Scope`.
+    ///
+    /// The `Scope` cannot be modified, because variables in scripts are located by their positions
+    /// within it, which are worked out when the script is compiled.
+    pub fn scope(&self) -> &Scope<'s> {
+        self.scope
+    }
+    /// Evaluate a captured input.
+    ///
+    /// An input can be evaluated any number of times, including not at all.
+    pub fn eval_expression_tree(
+        &mut self,
+        expr: &Expression,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        self.engine
+            .eval_expr(self.scope, self.state, self.fn_lib, expr.0, self.level)
+    }
+}
+
+impl Engine {
+    /// Register a custom syntax with the `Engine`.
+    ///
+    /// The `pattern` is a list of segments, which must start with a keyword.
+    /// Each following segment is either a token to be matched (e.g. `"minutes"` or `":"`), or a marker
+    /// that captures an input:
+    ///
+    /// * `$expr$` - an expression
+    /// * `$block$` - a statement block
+    /// * `$ident$` - an identifier
+    ///
+    /// The captured inputs are passed to the callback, which evaluates them (if at all) via the `EvalContext`.
+    /// The keyword can no longer be used as a variable or function name in scripts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the keyword is not a valid identifier or is already a keyword,
+    /// or if a segment is not a single token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // 'unless condition { block }'
+    /// engine.register_custom_syntax(&["unless", "$expr$", "$block$"], |context, inputs| {
+    ///     if context.eval_expression_tree(&inputs[0])?.as_bool().unwrap_or(false) {
+    ///         Ok(().into())
+    ///     } else {
+    ///         context.eval_expression_tree(&inputs[1])
+    ///     }
+    /// }).unwrap();
+    ///
+    /// assert_eq!(engine.eval::<i64>("let x = 0; unless x > 0 { x = 42; } x")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_custom_syntax<F: CustomSyntaxCallback>(
+        &mut self,
+        pattern: &[&str],
+        func: F,
+    ) -> Result<(), String> {
+        let keyword = match pattern.first() {
+            Some(keyword) => *keyword,
+            None => return Err("Custom syntax cannot be empty".into()),
+        };
+
+        // The keyword must lex as a plain identifier
+        let scripts = [keyword];
+        let no_custom_operators = HashMap::new();
+        let no_disabled_symbols = HashSet::new();
+
+        match lex(&scripts, &no_custom_operators, &no_disabled_symbols).next() {
+            Some((Token::Identifier(s), _))
+                if s == keyword
+                    && is_identifier(keyword)
+                    && !self.custom_operators.contains_key(keyword) => {}
+            _ => {
+                return Err(format!(
+                    "'{}' is not a valid custom syntax keyword",
+                    keyword
+                ))
+            }
+        }
+
+        // Each other segment must be a marker or a single token
+        for segment in &pattern[1..] {
+            match *segment {
+                MARKER_EXPR | MARKER_BLOCK | MARKER_IDENT => (),
+                s => {
+                    let scripts = [s];
+                    let tokens: Vec<_> =
+                        lex(&scripts, &self.custom_operators, &no_disabled_symbols)
+                            .take_while(|(token, _)| *token != Token::EOF)
+                            .collect();
+
+                    match tokens.as_slice() {
+                        [(Token::LexError(_), _)]
+                        | [(Token::IntegerConstant(_), _)]
+                        | [(Token::CharConstant(_), _)]
+                        | [(Token::StringConst(_), _)]
+                        | [(Token::Label(_), _)] => {
+                            return Err(format!("'{}' is not a valid custom syntax token", s))
+                        }
+                        #[cfg(not(feature = "no_float"))]
+                        [(Token::FloatConstant(_), _)] => {
+                            return Err(format!("'{}' is not a valid custom syntax token", s))
+                        }
+                        [(token, _)] if token.syntax() == s => (),
+                        _ => return Err(format!("'{}' is not a valid custom syntax token", s)),
+                    }
+                }
+            }
+        }
+
+        self.custom_syntax.insert(
+            keyword.into(),
+            CustomSyntax {
+                segments: pattern.iter().map(|s| s.to_string()).collect(),
+                func: Box::new(func),
+            },
+        );
+
+        Ok(())
+    }
+}
@@ -9,7 +9,9 @@ use crate::parser::FLOAT;
 use crate::stdlib::{
     borrow::Cow,
     boxed::Box,
-    char, fmt,
+    char,
+    collections::{HashMap, HashSet},
+    fmt,
     iter::Peekable,
     str::{Chars, FromStr},
     string::{String, ToString},
@@ -135,6 +137,8 @@ pub enum Token {
     Identifier(String),
     CharConstant(char),
     StringConst(String),
+    Label(String),
+    Custom(String, u8),
     LeftBrace,
     RightBrace,
     LeftParen,
@@ -155,6 +159,11 @@ pub enum Token {
     Colon,
     Comma,
     Period,
+    DoublePeriod,
+    Ellipsis,
+    QuestionPeriod,
+    QuestionBracket,
+    DoubleQuestion,
     #[cfg(not(feature = "no_object"))]
     MapStart,
     Equals,
@@ -165,6 +174,8 @@ pub enum Token {
     If,
     Else,
     While,
+    Do,
+    Until,
     Loop,
     For,
     In,
@@ -212,6 +223,8 @@ impl Token {
             FloatConstant(f) => f.to_string().into(),
             Identifier(s) => s.into(),
             CharConstant(c) => c.to_string().into(),
+            Label(s) => format!("'{}", s).into(),
+            Custom(s, _) => s.into(),
             LexError(err) => err.to_string().into(),
 
             token => (match token {
@@ -232,6 +245,11 @@ impl Token {
                 Colon => ":",
                 Comma => ",",
                 Period => ".",
+                DoublePeriod => "..",
+                Ellipsis => "...",
+                QuestionPeriod => "?.",
+                QuestionBracket => "?[",
+                DoubleQuestion => "??",
                 #[cfg(not(feature = "no_object"))]
                 MapStart => "#{",
                 Equals => "=",
@@ -242,6 +260,8 @@ impl Token {
                 If => "if",
                 Else => "else",
                 While => "while",
+                Do => "do",
+                Until => "until",
                 Loop => "loop",
                 LessThan => "<",
                 GreaterThan => ">",
@@ -307,6 +327,7 @@ impl Token {
             LeftParen        | // {-expr} - is unary
             // RightParen    | (expr) - expr not unary & is closing
             LeftBracket      | // [-expr] - is unary
+            QuestionBracket  | // ?[-expr] - is unary
             // RightBracket  | [expr] - expr not unary & is closing
             Plus             |
             UnaryPlus        |
@@ -317,6 +338,8 @@ impl Token {
             Colon            |
             Comma            |
             Period           |
+            QuestionPeriod   |
+            DoubleQuestion   |
             Equals           |
             LessThan         |
             GreaterThan      |
@@ -331,6 +354,8 @@ impl Token {
             And              |
             If               |
             While            |
+            Until            |
+            Break            |
             PlusAssign       |
             MinusAssign      |
             MultiplyAssign   |
@@ -349,12 +374,29 @@ impl Token {
             Throw            |
             PowerOf          |
             In               |
+            Custom(_, _)     |
             PowerOfAssign => true,
 
             _ => false,
         }
     }
 
+    // Can a binary operator follow this token, i.e. does it end an operand?
+    // A closing brace is excluded because it usually ends a statement block.
+    pub fn ends_operand(&self) -> bool {
+        use Token::*;
+
+        match self {
+            IntegerConstant(_) | CharConstant(_) | StringConst(_) | Identifier(_) | True
+            | False | RightParen | RightBracket => true,
+
+            #[cfg(not(feature = "no_float"))]
+            FloatConstant(_) => true,
+
+            _ => false,
+        }
+    }
+
     /// Get the precedence number of the token.
     pub fn precedence(&self) -> u8 {
         use Token::*;
@@ -365,6 +407,8 @@ impl Token {
             | RightShiftAssign | AndAssign | OrAssign | XOrAssign | ModuloAssign
             | PowerOfAssign => 0,
 
+            DoubleQuestion => 30,
+
             Or | XOr | Pipe => 40,
 
             And | Ampersand => 50,
@@ -382,7 +426,9 @@ impl Token {
 
             Modulo => 110,
 
-            Period => 120,
+            Period | QuestionPeriod => 120,
+
+            Custom(_, precedence) => *precedence,
 
             _ => 0,
         }
@@ -399,7 +445,7 @@ impl Token {
             | PowerOfAssign => true,
 
             // Property access binds to the right
-            Period => true,
+            Period | QuestionPeriod => true,
 
             _ => false,
         }
@@ -410,10 +456,18 @@ impl Token {
 pub struct TokenIterator<'a> {
     /// Can the next token be a unary operator?
     can_be_unary: bool,
+    /// Can the next token be a binary operator, i.e. does the last token end an operand?
+    can_be_binary: bool,
     /// Current position.
     pos: Position,
+    /// Characters read ahead of the input streams, in reverse order.
+    lookahead: Vec<char>,
     /// The input character streams.
     streams: Vec<Peekable<Chars<'a>>>,
+    /// Custom operators and their precedence.
+    custom_operators: &'a HashMap<String, u8>,
+    /// Disabled keywords and operators.
+    disabled_symbols: &'a HashSet<String>,
 }
 
 impl<'a> TokenIterator<'a> {
@@ -424,6 +478,10 @@ impl<'a> TokenIterator<'a> {
     }
     /// Get the next character
     fn get_next(&mut self) -> Option<char> {
+        if let Some(ch) = self.lookahead.pop() {
+            return Some(ch);
+        }
+
         loop {
             if self.streams.is_empty() {
                 // No more streams
@@ -439,6 +497,10 @@ impl<'a> TokenIterator<'a> {
     }
     /// Peek the next character
     fn peek_next(&mut self) -> Option<char> {
+        if let Some(ch) = self.lookahead.last() {
+            return Some(*ch);
+        }
+
         loop {
             if self.streams.is_empty() {
                 // No more streams
@@ -469,6 +531,50 @@ impl<'a> TokenIterator<'a> {
         self.pos.new_line()
     }
 
+    /// Match the longest custom symbol operator starting with the character `first`, consuming it.
+    fn match_custom_operator(&mut self, first: char) -> Option<Token> {
+        let custom_operators = self.custom_operators;
+        let is_prefix = |text: &str| {
+            custom_operators
+                .keys()
+                .any(|op| !is_identifier(op) && op.starts_with(text))
+        };
+
+        let mut text = String::new();
+        text.push(first);
+
+        // Read ahead as long as the text can still become a longer operator
+        let mut read = Vec::new();
+        let mut matched = None;
+
+        loop {
+            if let Some(precedence) = custom_operators.get(&text) {
+                matched = Some((text.clone(), *precedence, read.len()));
+            }
+
+            match self.peek_next() {
+                Some(ch) if is_symbol(ch) => {
+                    text.push(ch);
+
+                    if !is_prefix(&text) {
+                        break;
+                    }
+
+                    self.get_next();
+                    read.push(ch);
+                }
+                _ => break,
+            }
+        }
+
+        // Put back the characters read beyond the operator
+        let len = matched.as_ref().map_or(0, |(_, _, len)| *len);
+        self.lookahead.extend(read.drain(len..).rev());
+        read.iter().for_each(|_| self.advance());
+
+        matched.map(|(op, precedence, _)| Token::Custom(op, precedence))
+    }
+
     /// Parse a string literal wrapped by `enclosing_char`.
     pub fn parse_string_literal(
         &mut self,
@@ -583,6 +689,13 @@ impl<'a> TokenIterator<'a> {
 
             let pos = self.pos;
 
+            // Custom symbol operators take precedence over built-in operators
+            if !self.custom_operators.is_empty() && is_symbol(c) {
+                if let Some(token) = self.match_custom_operator(c) {
+                    return Some((token, pos));
+                }
+            }
+
             match (c, self.peek_next().unwrap_or('\0')) {
                 // \n
                 ('\n', _) => self.new_line(),
@@ -733,6 +846,8 @@ impl<'a> TokenIterator<'a> {
                             "if" => Token::If,
                             "else" => Token::Else,
                             "while" => Token::While,
+                            "do" => Token::Do,
+                            "until" => Token::Until,
                             "loop" => Token::Loop,
                             "continue" => Token::Continue,
                             "break" => Token::Break,
@@ -744,6 +859,13 @@ impl<'a> TokenIterator<'a> {
                             #[cfg(not(feature = "no_function"))]
                             "fn" => Token::Fn,
 
+                            // Word operators only apply in binary operator position
+                            _ if self.can_be_binary => {
+                                match self.custom_operators.get(&identifier) {
+                                    Some(precedence) => Token::Custom(identifier, *precedence),
+                                    None => Token::Identifier(identifier),
+                                }
+                            }
                             _ => Token::Identifier(identifier),
                         },
                         pos,
@@ -758,6 +880,36 @@ impl<'a> TokenIterator<'a> {
                     );
                 }
 
+                // 'label - loop label
+                ('\'', ch) if ch.is_ascii_alphabetic() || ch == '_' => {
+                    let mut result = Vec::new();
+
+                    while let Some(next_char) = self.peek_next() {
+                        match next_char {
+                            x if x.is_ascii_alphanumeric() || x == '_' => {
+                                result.push(x);
+                                self.eat_next();
+                            }
+                            _ => break,
+                        }
+                    }
+
+                    let name: String = result.iter().collect();
+
+                    // 'x' - character literal that happens to look like a label
+                    if self.peek_next() == Some('\'') {
+                        self.eat_next();
+
+                        return Some(if result.len() == 1 {
+                            (Token::CharConstant(result[0]), pos)
+                        } else {
+                            (Token::LexError(Box::new(LERR::MalformedChar(name))), pos)
+                        });
+                    }
+
+                    return Some((Token::Label(name), pos));
+                }
+
                 // ' - character literal
                 ('\'', '\'') => {
                     return Some((
@@ -876,6 +1028,29 @@ impl<'a> TokenIterator<'a> {
                 (';', _) => return Some((Token::SemiColon, pos)),
                 (':', _) => return Some((Token::Colon, pos)),
                 (',', _) => return Some((Token::Comma, pos)),
+                ('?', '?') => {
+                    self.eat_next();
+                    return Some((Token::DoubleQuestion, pos));
+                }
+                ('?', '.') => {
+                    self.eat_next();
+                    return Some((Token::QuestionPeriod, pos));
+                }
+                ('?', '[') => {
+                    self.eat_next();
+                    return Some((Token::QuestionBracket, pos));
+                }
+
+                ('.', '.') => {
+                    self.eat_next();
+
+                    if self.peek_next() == Some('.') {
+                        self.eat_next();
+                        return Some((Token::Ellipsis, pos));
+                    }
+
+                    return Some((Token::DoublePeriod, pos));
+                }
                 ('.', _) => return Some((Token::Period, pos)),
 
                 ('=', '=') => {
@@ -1006,19 +1181,69 @@ impl<'a> Iterator for TokenIterator<'a> {
     type Item = (Token, Position);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner_next().map(|x| {
+        self.inner_next().map(|(token, pos)| {
             // Save the last token
-            self.can_be_unary = x.0.is_next_unary();
-            x
+            self.can_be_unary = token.is_next_unary();
+            self.can_be_binary = token.ends_operand();
+
+            // Reject disabled keywords and operators
+            let token = match token {
+                Token::IntegerConstant(_)
+                | Token::CharConstant(_)
+                | Token::StringConst(_)
+                | Token::Label(_)
+                | Token::LexError(_)
+                | Token::EOF => token,
+                #[cfg(not(feature = "no_float"))]
+                Token::FloatConstant(_) => token,
+                token if self.disabled_symbols.contains(token.syntax().as_ref()) => {
+                    Token::LexError(Box::new(LERR::DisabledSymbol(token.syntax().into())))
+                }
+                token => token,
+            };
+
+            (token, pos)
         })
     }
 }
 
-/// Tokenize an input text stream.
-pub fn lex<'a>(input: &'a [&'a str]) -> TokenIterator<'a> {
+/// Is the character part of an identifier?
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Is the text made up of identifier characters only, thus lexed like an identifier?
+pub fn is_identifier(text: &str) -> bool {
+    text.chars().all(is_identifier_char)
+}
+
+/// Can the character be part of a symbol operator?
+pub fn is_symbol(c: char) -> bool {
+    !is_identifier_char(c)
+        && !c.is_whitespace()
+        && !['"', '\'', '`', '(', ')', '[', ']', '{', '}', ',', ';', '#'].contains(&c)
+}
+
+/// Built-in operators and punctuation made up of symbol characters, including comment markers.
+pub const SYMBOL_OPERATORS: &[&str] = &[
+    "+", "-", "*", "/", "%", "~", "<<", ">>", ":", ".", "..", "...", "?.", "?[", "??", "#{", "=",
+    "<", ">", "<=", ">=", "==", "!=", "!", "|", "||", "^", "&", "&&", "+=", "-=", "*=", "/=",
+    "<<=", ">>=", "&=", "|=", "^=", "%=", "~=", "//", "/*",
+];
+
+/// Tokenize an input text stream, recognizing the custom operators and rejecting the disabled symbols.
+pub fn lex<'a>(
+    input: &'a [&'a str],
+    custom_operators: &'a HashMap<String, u8>,
+    disabled_symbols: &'a HashSet<String>,
+) -> TokenIterator<'a> {
     TokenIterator {
         can_be_unary: true,
+        can_be_binary: false,
         pos: Position::new(1, 0),
+        lookahead: Vec::new(),
         streams: input.iter().map(|s| s.chars().peekable()).collect(),
+        custom_operators,
+        disabled_symbols,
     }
 }
@@ -107,3 +107,219 @@ fn test_array_with_structs() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_function"))]
+fn test_array_higher_order() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let to_ints = |a: Array| a.into_iter().map(|v| v.cast::<INT>()).collect::<Vec<_>>();
+
+    assert_eq!(
+        to_ints(engine.eval::<Array>("[1, 2, 3].map(|x| x * 2)")?),
+        [2, 4, 6]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("[1, 2, 3].map(|x, i| x * i)")?),
+        [0, 2, 6]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>(
+            r#"
+                fn is_odd(x) { x % 2 == 1 }
+                [1, 2, 3, 4, 5].filter(Fn("is_odd"))
+            "#
+        )?),
+        [1, 3, 5]
+    );
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].reduce(|sum, x| sum + x)")?, 6);
+    assert_eq!(
+        engine.eval::<INT>("[1, 2, 3].reduce_rev(|acc, x, i| acc * 10 + x + i)")?,
+        331
+    );
+    assert!(engine.eval::<()>("[].reduce(|sum, x| sum + x)").is_ok());
+    assert_eq!(engine.eval::<INT>("[42].reduce(|sum, x| sum + x)")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("[1, 2, 3].reduce(|sum, x| sum + x, 100)")?,
+        106
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"["a", "b", "c"].reduce_rev(|s, x| s + x, "")"#)?,
+        "cba"
+    );
+    assert_eq!(
+        engine
+            .eval::<INT>("let r = [1, 2, 3].reduce(|acc, x| { acc.push(x); acc }, [0]); r.len()")?,
+        4
+    );
+    assert_eq!(
+        engine.eval::<INT>("[1, 2, 3].reduce(|acc, x| (acc ?? 10) + x, ())")?,
+        16
+    );
+
+    // Function pointers can be stored in arrays
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let fns = [];
+                fns.push(|x| x + 1);
+                fns.insert(0, |x| x * 2);
+                fns.pad(3, Fn("abs"));
+                let f = fns[0];
+                let g = fns[2];
+                fns.len() * 100 + f.call(5) + g.call(-3)
+            "#
+        )?,
+        313
+    );
+    assert!(engine.eval::<bool>("[1, 2, 3].some(|x| x > 2)")?);
+    assert!(!engine.eval::<bool>("[1, 2, 3].all(|x| x > 2)")?);
+    assert!(engine.eval::<bool>("[].all(|x| false)")?);
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].find(|x| x > 1)")?, 2);
+    assert!(engine.eval::<()>("[1, 2, 3].find(|x| x > 5)").is_ok());
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].find_index(|x| x > 1)")?, 1);
+    assert_eq!(engine.eval::<INT>("[1, 2, 3].find_index(|x| x > 5)")?, -1);
+
+    // Callbacks can capture variables
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let k = 2; [1, 2, 3].filter(|x| x >= k)")?),
+        [2, 3]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_array_manipulation() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let to_ints = |a: Array| a.into_iter().map(|v| v.cast::<INT>()).collect::<Vec<_>>();
+
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [3, 1, 2, 1]; a.sort(); a")?),
+        [1, 1, 2, 3]
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let a = ["b", "c", "a"]; a.sort(); a[0] + a[2]"#)?,
+        "ac"
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [1, 1, 2, 2, 1]; a.dedup(); a")?),
+        [1, 2, 1]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [1, 2, 3]; a.reverse(); a")?),
+        [3, 2, 1]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [1, 2, 3, 4, 5]; a.splice(1, 2, [7, 8, 9]); a")?),
+        [1, 7, 8, 9, 4, 5]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [1, 2, 3, 4, 5]; a.splice(-1, 1, []); a")?),
+        [1, 2, 3, 4]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("[1, 2, 3, 4, 5].extract(1, 2)")?),
+        [2, 3]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("[1, 2, 3, 4, 5].extract(-2)")?),
+        [4, 5]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("[1, 2, 3].extract(5, 2)")?),
+        []
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [1]; a.pad(3, 0); a")?),
+        [1, 0, 0]
+    );
+    assert!(matches!(
+        *engine
+            .eval::<Array>("let a = []; a.pad(2147483647, 0); a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [1, 2, 3, 4, 5]; let b = a.drain(1, 3); a + b")?),
+        [1, 5, 2, 3, 4]
+    );
+    assert_eq!(
+        to_ints(engine.eval::<Array>("let a = [1, 2, 3, 4, 5]; let b = a.retain(1, 3); a + b")?),
+        [2, 3, 4, 1, 5]
+    );
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(
+            to_ints(engine.eval::<Array>("let a = [3, 1, 2]; a.sort(|x, y| y - x); a")?),
+            [3, 2, 1]
+        );
+        assert_eq!(
+            to_ints(
+                engine
+                    .eval::<Array>("let a = [1, 3, 2, 4, 7]; a.dedup(|x, y| x % 2 == y % 2); a")?
+            ),
+            [1, 2, 7]
+        );
+        assert_eq!(
+            to_ints(engine.eval::<Array>(
+                "let a = [1, 2, 3, 4, 5]; let b = a.drain(|x| x % 2 == 0); a + b"
+            )?),
+            [1, 3, 5, 2, 4]
+        );
+        assert_eq!(
+            to_ints(engine.eval::<Array>(
+                "let a = [1, 2, 3, 4, 5]; let b = a.retain(|x| x % 2 == 0); a + b"
+            )?),
+            [2, 4, 1, 3, 5]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_array_callback_errors() {
+    let engine = Engine::new();
+
+    let to_ints = |a: Array| a.into_iter().map(|v| v.cast::<INT>()).collect::<Vec<_>>();
+
+    assert!(matches!(
+        *engine
+            .eval::<()>(r#"let a = [1, "x"]; a.sort()"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert!(matches!(
+            *engine.eval::<Array>("[1, 2].filter(|x| x)").expect_err("should error"),
+            EvalAltResult::ErrorMismatchDataType(need, _, _) if need == "bool"
+        ));
+        assert!(matches!(
+            *engine.eval::<()>(r#"let a = [2, 1]; a.sort(|x, y| "x")"#).expect_err("should error"),
+            EvalAltResult::ErrorMismatchDataType(need, _, _) if need == "integer"
+        ));
+
+        // The array is left untouched when sorting fails
+        let mut scope = rhai::Scope::new();
+        let a: Array = vec![(3 as INT).into(), (2 as INT).into(), (1 as INT).into()];
+        scope.push("a", a);
+
+        assert!(engine
+            .eval_with_scope::<()>(
+                &mut scope,
+                r#"a.sort(|x, y| if x == 1 { throw "oops" } else { x - y })"#
+            )
+            .expect_err("should error")
+            .to_string()
+            .contains("oops"));
+        assert_eq!(to_ints(scope.get_value::<Array>("a").unwrap()), [3, 2, 1]);
+    }
+}
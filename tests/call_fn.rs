@@ -60,6 +60,47 @@ fn test_call_fn() -> Result<(), Box<EvalAltResult>> {
     Ok(())
 }
 
+#[test]
+fn test_call_fn_default_params() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let ast = engine.compile(
+        r"
+            fn add(x, y = 40, z = 1) {
+                x + y + z
+            }
+        ",
+    )?;
+
+    let r: INT = engine.call_fn(&mut scope, &ast, "add", (1 as INT,))?;
+    assert_eq!(r, 42);
+
+    let r: INT = engine.call_fn(&mut scope, &ast, "add", (1 as INT, 2 as INT))?;
+    assert_eq!(r, 4);
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let ast = engine.compile(
+            r"
+                fn sum(...nums) {
+                    let total = 0;
+                    for n in nums { total += n; }
+                    total
+                }
+            ",
+        )?;
+
+        let r: INT = engine.call_fn(&mut scope, &ast, "sum", ())?;
+        assert_eq!(r, 0);
+
+        let r: INT = engine.call_fn(&mut scope, &ast, "sum", (40 as INT, 1 as INT, 1 as INT))?;
+        assert_eq!(r, 42);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_anonymous_fn() -> Result<(), Box<EvalAltResult>> {
     let calc_func = Func::<(INT, INT, INT), INT>::create_from_script(
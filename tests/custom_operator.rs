@@ -0,0 +1,86 @@
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+
+#[test]
+fn test_custom_operator() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_custom_operator("**", 90).unwrap();
+    engine.register_fn("**", |x: INT, y: INT| x.pow(y as u32));
+
+    assert_eq!(engine.eval::<INT>("2 ** 3")?, 8);
+    assert_eq!(engine.eval::<INT>("1 + 2 ** 3 * 5")?, 41);
+    assert_eq!(engine.eval::<INT>("let x = 3; x**2-1")?, 8);
+    assert_eq!(engine.eval::<INT>("2 * -3")?, -6);
+
+    // Higher precedence than '+'
+    engine.register_custom_operator("<>", 85).unwrap();
+    engine.register_fn("<>", |x: INT, y: INT| x * 10 + y);
+    assert_eq!(engine.eval::<INT>("1 + 2 <> 3")?, 24);
+    assert_eq!(engine.eval::<INT>("1 + 2 + 3")?, 6);
+    assert!(engine.eval::<bool>("1 < 2")?);
+
+    // The longest operator wins
+    engine.register_custom_operator("<~>", 85).unwrap();
+    engine.register_fn("<~>", |x: INT, y: INT| x * 100 + y);
+    assert_eq!(engine.eval::<INT>("1 <~> 2")?, 102);
+    assert_eq!(engine.eval::<INT>("1 <> 2")?, 12);
+    assert!(engine.eval::<bool>("1 <= 2")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_operator_word() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_custom_operator("between", 60).unwrap();
+    engine.register_fn("between", |x: INT, (lo, hi): (INT, INT)| x >= lo && x <= hi);
+    engine.register_fn("to", |lo: INT, hi: INT| (lo, hi));
+    engine.register_custom_operator("to", 80).unwrap();
+
+    assert!(engine.eval::<bool>("5 between 1 to 10")?);
+    assert!(!engine.eval::<bool>("let x = 42; x between 1 + 1 to 10")?);
+
+    // Other identifiers are not affected
+    assert_eq!(engine.eval::<INT>("let between_x = 42; between_x")?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_operator_word_as_function() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_custom_operator("contains", 60).unwrap();
+    engine.register_fn("contains", |s: &mut String, t: String| s.contains(&t));
+
+    // Word operators only apply after an operand
+    assert!(engine.eval::<bool>(r#""abc" contains "b""#)?);
+    assert!(engine.eval::<bool>(r#"let a = "abc"; let b = "c"; contains(a, b)"#)?);
+    assert!(!engine.eval::<bool>(r#"let a = "abc"; a contains "d" || false"#)?);
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.eval::<bool>(r#""abc".contains("b")"#)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_operator_invalid() {
+    let mut engine = Engine::new();
+
+    assert!(engine.register_custom_operator("", 10).is_err());
+    assert!(engine.register_custom_operator("**", 0).is_err());
+    assert!(engine.register_custom_operator("+", 10).is_err());
+    assert!(engine.register_custom_operator("==", 10).is_err());
+    assert!(engine.register_custom_operator("while", 10).is_err());
+    assert!(engine.register_custom_operator("a+", 10).is_err());
+    assert!(engine.register_custom_operator("//", 10).is_err());
+    assert!(engine.register_custom_operator("=~", 10).is_ok());
+
+    // Prefixes of built-in operators and sequences that are valid scripts are rejected
+    assert!(engine.register_custom_operator("?", 10).is_err());
+    assert!(engine.register_custom_operator("!!", 10).is_err());
+    assert!(engine.register_custom_operator("+++", 10).is_err());
+    assert!(engine.register_custom_operator("<-", 10).is_err());
+}
@@ -0,0 +1,187 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, Scope, INT};
+
+#[test]
+fn test_custom_syntax() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // when condition { block }
+    engine
+        .register_custom_syntax(&["when", "$expr$", "$block$"], |context, inputs| {
+            if context
+                .eval_expression_tree(&inputs[0])?
+                .as_bool()
+                .unwrap_or(false)
+            {
+                context.eval_expression_tree(&inputs[1])
+            } else {
+                Ok(().into())
+            }
+        })
+        .unwrap();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let temp = 42;
+                let alerts = 0;
+                when temp > 30 { alerts += 1; }
+                when temp > 50 { alerts += 10; }
+                alerts
+            "
+        )?,
+        1
+    );
+    assert_eq!(engine.eval::<INT>("let x = when true { 42 }; x")?, 42);
+
+    // every count times { block }
+    engine
+        .register_custom_syntax(
+            &["every", "$expr$", "times", "$block$"],
+            |context, inputs| {
+                let count = context
+                    .eval_expression_tree(&inputs[0])?
+                    .as_int()
+                    .map_err(|_| {
+                        Box::new(EvalAltResult::ErrorMismatchOutputType(
+                            "integer".into(),
+                            inputs[0].position(),
+                        ))
+                    })?;
+
+                for _ in 0..count {
+                    context.eval_expression_tree(&inputs[1])?;
+                }
+
+                Ok(().into())
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; every 2 + 3 times { x += 2; } x")?,
+        10
+    );
+
+    assert!(matches!(
+        engine
+            .compile("every 5 minutes { }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::MissingToken(token, _) if token == "times"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_syntax_ident() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // ifdef name { block }
+    engine
+        .register_custom_syntax(&["ifdef", "$ident$", "$block$"], |context, inputs| {
+            let name = inputs[0].get_variable_name().unwrap();
+
+            if context.scope().contains(name) {
+                context.eval_expression_tree(&inputs[1])
+            } else {
+                Ok(().into())
+            }
+        })
+        .unwrap();
+
+    assert_eq!(engine.eval::<INT>("let x = 40; ifdef x { x + 2 }")?, 42);
+    assert_eq!(engine.eval::<()>("ifdef x { x + 2 }")?, ());
+
+    let mut scope = Scope::new();
+    scope.push("x", 1 as INT);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(
+            &mut scope,
+            "let y = x + 1; ifdef y { let z = 3; x + y + z }"
+        )?,
+        6
+    );
+    assert_eq!(scope.len(), 2);
+
+    // Variables declared before and inside the custom syntax are still found correctly
+    assert_eq!(
+        engine.eval::<INT>("let a = 1; let b = 2; ifdef a { let c = 3; b + c } a * 10 + b")?,
+        12
+    );
+
+    assert!(matches!(
+        engine
+            .compile("ifdef 42 { }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableExpected
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_syntax_loop() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // run { block }
+    engine
+        .register_custom_syntax(&["run", "$block$"], |context, inputs| {
+            context.eval_expression_tree(&inputs[0])
+        })
+        .unwrap();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 0;
+                while true {
+                    x += 1;
+                    run { if x < 3 { continue; } }
+                    run { break; }
+                }
+                x
+            "
+        )?,
+        3
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = loop { run { break 42; } }; x")?,
+        42
+    );
+
+    assert!(matches!(
+        engine
+            .compile("run { break; }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::LoopBreak
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_syntax_invalid() {
+    let mut engine = Engine::new();
+
+    let f = |_: &mut rhai::EvalContext, _: &[rhai::Expression]| Ok(().into());
+
+    assert!(engine.register_custom_syntax(&[], f).is_err());
+    assert!(engine
+        .register_custom_syntax(&["while", "$expr$"], f)
+        .is_err());
+    assert!(engine.register_custom_syntax(&["+", "$expr$"], f).is_err());
+    assert!(engine.register_custom_syntax(&["foo", "a b"], f).is_err());
+    assert!(engine.register_custom_syntax(&["foo", "42"], f).is_err());
+    assert!(engine
+        .register_custom_syntax(&["foo", "$expr$", "=>", "$expr$"], f)
+        .is_err());
+    assert!(engine
+        .register_custom_syntax(&["foo", "$expr$", "->", "$block$"], f)
+        .is_err());
+    assert!(engine
+        .register_custom_syntax(&["foo", "$expr$", ":", "$block$"], f)
+        .is_ok());
+}
@@ -0,0 +1,281 @@
+#![cfg(not(feature = "no_stdlib"))]
+
+use rhai::packages::{BasicDateTimePackage, Package};
+use rhai::{DateTime, Duration, Dynamic, Engine, EvalAltResult, INT};
+
+#[test]
+fn test_datetime_rust() {
+    let date = DateTime::from_date_time(2024, 2, 29, 23, 59, 58).unwrap();
+    assert_eq!(date.year(), 2024);
+    assert_eq!(date.month(), 2);
+    assert_eq!(date.day(), 29);
+    assert_eq!(date.weekday(), 4);
+    assert_eq!(date.day_of_year(), 60);
+    assert_eq!(date.to_string(), "2024-02-29T23:59:58Z");
+
+    assert!(DateTime::from_date(2023, 2, 29).is_none());
+    assert!(DateTime::from_date(10000, 1, 1).is_none());
+    assert_eq!(
+        DateTime::from_date(0, 1, 1).unwrap().to_string(),
+        "0000-01-01T00:00:00Z"
+    );
+
+    let epoch = DateTime::from_unix_time(0, 0).unwrap();
+    assert_eq!(epoch, DateTime::UNIX_EPOCH);
+    assert_eq!(epoch.weekday(), 4);
+    assert_eq!(
+        DateTime::from_unix_time(-1, 500_000_000)
+            .unwrap()
+            .to_string(),
+        "1969-12-31T23:59:59.500Z"
+    );
+
+    let later = date.checked_add(Duration::from_seconds(2)).unwrap();
+    assert_eq!(later.to_string(), "2024-03-01T00:00:00Z");
+    assert_eq!(later.duration_since(date), Duration::from_seconds(2));
+    assert!(DateTime::from_date(9999, 12, 31)
+        .unwrap()
+        .checked_add(Duration::from_seconds(86_400))
+        .is_none());
+
+    assert_eq!(Duration::from_seconds(90).to_string(), "PT1M30S");
+    assert_eq!(Duration::ZERO.to_string(), "PT0S");
+    assert_eq!(Duration::from_seconds(86_400).to_string(), "P1D");
+    assert_eq!(
+        Duration::from_nanoseconds(-1_500_000).unwrap().to_string(),
+        "-PT0.001500S"
+    );
+
+    assert_eq!(Dynamic::from(date).to_string(), "2024-02-29T23:59:58Z");
+    assert_eq!(
+        format!("{:?}", Dynamic::from(Duration::from_seconds(90))),
+        "PT1M30S"
+    );
+}
+
+#[test]
+fn test_datetime_parse_format() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<DateTime>(r#"parse_datetime("2026-10-17T12:00:00Z")"#)?,
+        DateTime::from_date_time(2026, 10, 17, 12, 0, 0).unwrap()
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"to_string(parse_datetime("2026-10-17T14:30:00.25+02:00"))"#)?,
+        "2026-10-17T12:30:00.250Z"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"to_string(parse_datetime("2026-10-17"))"#)?,
+        "2026-10-17T00:00:00Z"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"to_string(parse_datetime("2026-01-01T01:00-0230"))"#)?,
+        "2026-01-01T03:30:00Z"
+    );
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"format(parse_datetime("2026-10-07T15:04:05.12Z"), "%a %d %b %Y %I:%M:%S.%3f %p (%j) %F %T %Z")"#
+        )?,
+        "Wed 07 Oct 2026 03:04:05.120 PM (280) 2026-10-07 15:04:05 UTC"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format(from_unix_time(86400), "%s %u %w %e %:z")"#)?,
+        "86400 5 5  2 +00:00"
+    );
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"to_string(parse_datetime("Tuesday, 17 march 26 at 9:05 pm", "%A, %d %B %y at %I:%M %p"))"#
+        )?,
+        "2026-03-17T21:05:00Z"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"to_string(parse_datetime("2026/060 12", "%Y/%j %H"))"#)?,
+        "2026-03-01T12:00:00Z"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"to_string(parse_datetime("1700000000.5", "%s.%f"))"#)?,
+        "2023-11-14T22:13:20.500Z"
+    );
+
+    assert!(matches!(
+        *engine.eval::<DateTime>(r#"parse_datetime("2026-02-30")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Cannot parse '2026-02-30' as a date/time: invalid date 2026-02-30"
+    ));
+    assert!(matches!(
+        *engine.eval::<DateTime>(r#"parse_datetime("2026-10-17T12:00:00 UTC")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.ends_with("unexpected character ' ' at position 19")
+    ));
+    assert!(matches!(
+        *engine.eval::<DateTime>(r#"parse_datetime("12:00", "%H:%M")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg.ends_with("missing year")
+    ));
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>("let a = [datetime(2026, 1, 1), hours(2)]; to_string(a)")?,
+        "[2026-01-01T00:00:00Z, PT2H]"
+    );
+
+    assert!(matches!(
+        *engine.eval::<String>(r#"format(parse_datetime("2026-10-17"), "%Q")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _) if msg == "Invalid date/time format string: unknown specifier '%Q'"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_datetime_arithmetic() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>("to_string(datetime(2026, 1, 31) + days(30) + hours(1))")?,
+        "2026-03-02T01:00:00Z"
+    );
+    assert_eq!(
+        engine.eval::<String>("to_string(minutes(1) + datetime(2026, 1, 1) - seconds(1))")?,
+        "2026-01-01T00:00:59Z"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            "let d = datetime(2026, 1, 1, 12, 0, 0); d += milliseconds(1500); to_string(d)"
+        )?,
+        "2026-01-01T12:00:01.500Z"
+    );
+    assert_eq!(
+        engine.eval::<INT>("total_hours(datetime(2026, 1, 2) - datetime(2026, 1, 1))")?,
+        24
+    );
+    assert_eq!(
+        engine.eval::<String>("to_string(datetime(2026, 1, 1) - datetime(2026, 1, 2))")?,
+        "-P1D"
+    );
+    assert_eq!(
+        engine.eval::<String>("to_string(hours(2) * 3 - minutes(30))")?,
+        "PT5H30M"
+    );
+    assert_eq!(
+        engine.eval::<String>("to_string(2 * -days(1) / 4)")?,
+        "-PT12H"
+    );
+    assert_eq!(
+        engine.eval::<String>("to_string(abs(-seconds(5)))")?,
+        "PT5S"
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"total_minutes(parse_duration("P1DT2H30M"))"#)?,
+        1590
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"total_milliseconds(parse_duration("-PT0.25S"))"#)?,
+        -250
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(
+            engine.eval::<rhai::FLOAT>("total_seconds(seconds(1.5) + seconds(1))")?,
+            2.5
+        );
+    }
+
+    assert!(engine.eval::<bool>("datetime(2026, 1, 1) < datetime(2026, 1, 2)")?);
+    assert!(engine.eval::<bool>("datetime(2026, 1, 1) == datetime(2025, 12, 31) + days(1)")?);
+    assert!(engine.eval::<bool>("hours(1) > minutes(59) && hours(1) != minutes(61)")?);
+
+    assert!(matches!(
+        *engine.eval::<DateTime>("datetime(2026, 13, 1)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg == "Cannot create date/time: invalid date 2026-13-01"
+    ));
+    assert!(matches!(
+        *engine.eval::<DateTime>("datetime(2026, 1, 1, 24, 0, 0)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg == "Cannot create date/time: invalid time 24:00:00"
+    ));
+    assert!(matches!(
+        *engine.eval::<DateTime>("datetime(9999, 12, 31) + days(1)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Date/time out of range")
+    ));
+    assert!(matches!(
+        *engine.eval::<Duration>("hours(1) / 0").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Division by zero")
+    ));
+    assert!(matches!(
+        *engine.eval::<Duration>(r#"parse_duration("P1M")"#).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(msg, _)
+            if msg == "Cannot parse 'P1M' as a duration: unexpected character 'M' at position 2"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_datetime_getters() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>("type_of(datetime(2026, 1, 1)) + type_of(days(1))")?,
+        "datetimeduration"
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let d = parse_datetime("2026-10-17T12:34:56.789Z");
+                year(d) * 10000 + month(d) * 100 + day(d)
+            "#
+        )?,
+        20261017
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>(
+                r#"
+                    let d = parse_datetime("2026-10-17T12:34:56.789Z");
+                    d.hour * 10000 + d.minute * 100 + d.second
+                "#
+            )?,
+            123456
+        );
+        assert_eq!(
+            engine.eval::<INT>(r#"let d = datetime(2026, 10, 18); d.weekday"#)?,
+            7
+        );
+        assert_eq!(
+            engine.eval::<INT>(r#"let d = datetime(2026, 12, 31); d.day_of_year()"#)?,
+            365
+        );
+        assert_eq!(
+            engine.eval::<INT>(r#"let d = from_unix_time(1234567890); d.unix_time"#)?,
+            1234567890
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_datetime_clock() -> Result<(), Box<EvalAltResult>> {
+    fn clock() -> DateTime {
+        DateTime::from_date(2026, 10, 17).unwrap()
+    }
+
+    let mut engine = Engine::new();
+    engine.load_package(BasicDateTimePackage::with_clock(clock).get());
+
+    assert_eq!(
+        engine.eval::<String>(r#"format(now(), "%F")"#)?,
+        "2026-10-17"
+    );
+
+    #[cfg(not(feature = "no_std"))]
+    {
+        let engine = Engine::new();
+        assert!(engine.eval::<INT>("year(now())")? >= 2020);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_destructure_array() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let [a, b] = [40, 2]; a + b")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("let [a, b, ..rest] = [1, 2, 3, 4, 5]; a + b + len(rest) * 10")?,
+        33
+    );
+    assert_eq!(
+        engine.eval::<INT>("let [a, ..rest] = [1]; a + len(rest)")?,
+        1
+    );
+    assert!(engine.eval::<bool>("let [a, b, c] = [1, 2]; c == ()")?);
+    assert_eq!(
+        engine.eval::<INT>("let x = 1; let [x, y] = [x + 1, x + 2]; x * 10 + y")?,
+        23
+    );
+
+    #[cfg(not(feature = "no_function"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                fn min_max(a, b) { if a < b { [a, b] } else { [b, a] } }
+                let [lo, hi] = min_max(42, 2);
+                hi - lo
+            "
+        )?,
+        40
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let [a, b] = 42; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorPatternMismatch(need, _, _) if need == "array"
+    ));
+
+    assert!(matches!(
+        engine
+            .compile("let [a, ..rest, b] = [1, 2, 3];")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::MissingToken(t, _) if t == "]"
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_destructure_map() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let person = #{ name: "Bob", age: 42 };
+                let #{ name, age: years } = person;
+                name + " " + years
+            "#
+        )?,
+        "Bob 42"
+    );
+    assert!(engine.eval::<bool>(r#"let #{ "a": x, b } = #{ a: 1 }; x == 1 && b == ()"#)?);
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let #{ a } = 42; a")
+            .expect_err("should error"),
+        EvalAltResult::ErrorPatternMismatch(need, _, _) if need == "map"
+    ));
+
+    assert!(matches!(
+        engine
+            .compile("const #{ a } = #{ a: 1 };")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableExpected
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_destructure_for() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let pairs = [[1, 2], [3, 4], [5, 6]];
+                let sum = 0;
+                for [k, v] in pairs { sum += k * v; }
+                sum
+            "
+        )?,
+        44
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let points = [#{ x: 1, y: 2 }, #{ x: 3, y: 4 }];
+                let sum = 0;
+                for #{ x, y } in points { sum += x * y; }
+                sum
+            "
+        )?,
+        14
+    );
+
+    Ok(())
+}
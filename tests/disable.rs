@@ -0,0 +1,99 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_disable_keyword() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; while x < 10 { x += 1; } x")?,
+        10
+    );
+
+    engine.disable_symbol("while");
+    engine.disable_symbol("throw");
+    engine.disable_symbol("eval");
+
+    assert_eq!(
+        *engine
+            .compile("let x = 0; while x < 10 { x += 1; } x")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::DisabledSymbol("while".into())
+    );
+    assert_eq!(
+        *engine
+            .compile("throw \"hello\"")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::DisabledSymbol("throw".into())
+    );
+    assert_eq!(
+        *engine
+            .compile(r#"eval("40 + 2")"#)
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::DisabledSymbol("eval".into())
+    );
+
+    // Other keywords are unaffected
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; loop { x += 1; if x >= 10 { break; } } x")?,
+        10
+    );
+
+    // Identifiers and strings containing the symbol are unaffected
+    assert_eq!(engine.eval::<INT>("let whiles = 42; whiles")?, 42);
+    assert_eq!(engine.eval::<String>(r#""while""#)?, "while");
+
+    Ok(())
+}
+
+#[test]
+fn test_disable_operator() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.disable_symbol("+=");
+
+    assert!(matches!(
+        *engine.compile("let x = 1; x += 2;").expect_err("should error").error_type(),
+        ParseErrorType::DisabledSymbol(ref s) if s == "+="
+    ));
+    assert_eq!(engine.eval::<INT>("let x = 1; x = x + 2; x")?, 3);
+
+    // Other engines are unaffected
+    let engine = Engine::new();
+    assert_eq!(engine.eval::<INT>("let x = 1; x += 2; x")?, 3);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_disable_closures() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.disable_symbol("fn");
+
+    // Closures define functions, so they are disabled together with 'fn'
+    assert_eq!(
+        *engine
+            .compile("let f = |x| x + 1;")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::DisabledSymbol("fn".into())
+    );
+    assert_eq!(
+        *engine
+            .compile("let f = || 42;")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::DisabledSymbol("fn".into())
+    );
+
+    // Bitwise and logical operators are unaffected
+    assert_eq!(engine.eval::<INT>("let x = 1; x | 2")?, 3);
+    assert!(engine.eval::<bool>("let x = false; x || true")?);
+    assert_eq!(engine.eval::<INT>("let x = 1; x += 2; x")?, 3);
+
+    Ok(())
+}
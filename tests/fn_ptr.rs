@@ -0,0 +1,132 @@
+#![cfg(not(feature = "no_object"))]
+
+use rhai::{Engine, EvalAltResult, FnPtr, INT};
+
+#[test]
+fn test_fn_ptr() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let f = Fn("foo"); type_of(f)"#)?,
+        "Fn"
+    );
+    assert_eq!(
+        engine
+            .eval::<FnPtr>(r#"Fn("foo").curry(1, 2)"#)?
+            .curry()
+            .len(),
+        2
+    );
+    assert_eq!(engine.eval::<INT>(r#"let f = Fn("abs"); f.call(-42)"#)?, 42);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>(
+                r#"
+                    fn add(x, y) { x + y }
+                    let f = Fn("add").curry(40);
+                    call(f, 2)
+                "#
+            )?,
+            42
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"fn foo() { 42 } let f = Fn("foo"); "" + f"#)?,
+            "Fn(foo)"
+        );
+    }
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#"Fn("nope").call(1)"#).expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(sig, _) if sig == format!("nope ({})", std::any::type_name::<INT>())
+    ));
+    assert!(matches!(
+        *engine.eval::<FnPtr>("Fn(42)").expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(need, _, _) if need == "string"
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_closures() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let f = |x| x + 1; f.call(41)")?, 42);
+    assert_eq!(engine.eval::<INT>("let f = || 42; f.call()")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("let f = |x, y| { let z = x * y; z + 2 }; f.call(5, 8)")?,
+        42
+    );
+
+    // Variables are captured by value when the closure is created
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let y = 40;
+                let f = |x| x + y;
+                y = 0;
+                f.call(2)
+            "
+        )?,
+        42
+    );
+
+    // Nested closures and closures returned from functions
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let a = 1;
+                let f = |x| |y| x + y + a;
+                let g = f.call(10);
+                g.call(31)
+            "
+        )?,
+        42
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                fn multiplier(n) { |x| x * n }
+                let triple = multiplier(3);
+                triple.call(14)
+            "
+        )?,
+        42
+    );
+
+    // Captured variables do not affect the outside
+    assert_eq!(
+        engine.eval::<INT>("let x = 42; let f = || { x += 1; x }; f.call(); x")?,
+        42
+    );
+
+    // Closures work with the strict variables mode
+    let mut engine = Engine::new();
+    engine.set_strict_variables(true);
+
+    assert_eq!(
+        engine.eval::<INT>("let y = 2; let f = |x| x * y; f.call(21)")?,
+        42
+    );
+    assert!(engine.compile("let f = |x| x * y;").is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_closures_errors() {
+    let engine = Engine::new();
+
+    assert!(engine.compile("let f = |x y| x;").is_err());
+    assert!(engine.compile("let f = |x, | x;").is_err());
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let f = |x| x.call(x); f.call(f)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorStackOverflow(_)
+    ));
+}
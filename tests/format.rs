@@ -0,0 +1,175 @@
+#![cfg(not(feature = "no_stdlib"))]
+
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+
+#[test]
+fn test_format() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"format("x = {}", 42)"#)?, "x = 42");
+    assert_eq!(engine.eval::<String>(r#"format("[{:5}]", 42)"#)?, "[   42]");
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:<5}]", 42)"#)?,
+        "[42   ]"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:*^6}]", 42)"#)?,
+        "[**42**]"
+    );
+    assert_eq!(engine.eval::<String>(r#"format("{:05}", -42)"#)?, "-0042");
+    assert_eq!(engine.eval::<String>(r#"format("{:+}", 42)"#)?, "+42");
+    assert_eq!(engine.eval::<String>(r#"format("{:x}", 255)"#)?, "ff");
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:#06X}", 255)"#)?,
+        "0x00FF"
+    );
+    assert_eq!(engine.eval::<String>(r#"format("{:#b}", 5)"#)?, "0b101");
+    assert_eq!(engine.eval::<String>(r#"format("{:o}", 8)"#)?, "10");
+    assert_eq!(engine.eval::<String>(r#"format("{{{}}}", 1)"#)?, "{1}");
+    assert_eq!(
+        engine.eval::<String>(r#"format("no args {{}}")"#)?,
+        "no args {}"
+    );
+
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:>6}]", "❤❤")"#)?,
+        "[    ❤❤]"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("[{:.2}]", "❤❤❤")"#)?,
+        "[❤❤]"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{:?}", "a\"b")"#)?,
+        r#""a\"b""#
+    );
+    assert_eq!(engine.eval::<String>(r#"format("{:3}|", 'x')"#)?, "x  |");
+    assert_eq!(engine.eval::<String>(r#"format("{}", true)"#)?, "true");
+    assert_eq!(engine.eval::<String>(r#"format("[{}]", ())"#)?, "[]");
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:>8.2}", 3.14159)"#)?,
+            "    3.14"
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:08.3}", -2.5)"#)?,
+            "-002.500"
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:.1e}", 1234.5)"#)?,
+            "1.2e3"
+        );
+        assert_eq!(engine.eval::<String>(r#"format("{:+.1}", 0.25)"#)?, "+0.2");
+    }
+
+    assert_eq!(
+        engine.eval::<String>(r#"format("{} + {} = {2:03}", 1, 2, 3)"#)?,
+        "1 + 2 = 003"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"format("{1}-{0}-{1}", "a", 'b')"#)?,
+        "b-a-b"
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"format("{}{}{}{}{}{}{}{}{}{}{}{}{}{}{}{15}", 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6)"#
+        )?,
+        "1234567890123456"
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(r#"let s = format("{:1024}", 1); len(s)"#)?,
+        1024
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(engine.eval::<String>(r#"format("{}", [1, 2])"#)?, "[1, 2]");
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:?}", [1, "a"])"#)?,
+            r#"[1, "a"]"#
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"format("{} {}", [1], 2)"#)?,
+            "[1] 2"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_format_errors() {
+    let engine = Engine::new();
+
+    for script in &[
+        r#"format("{", 1)"#,
+        r#"format("}", 1)"#,
+        r#"format("{} {}", 1)"#,
+        r#"format("{:q}", 1)"#,
+        r#"format("{:.}", 1)"#,
+        r#"format("{x}", 1)"#,
+        r#"format("{:x}", "a")"#,
+        r#"format("{:1025}", 1)"#,
+        r#"format("{:.1025}", "a")"#,
+        r#"format("{:99999999999999999999999}", 1)"#,
+    ] {
+        assert!(
+            matches!(
+                *engine.eval::<String>(script).expect_err("should error"),
+                EvalAltResult::ErrorRuntime(_, _)
+            ),
+            "{}",
+            script
+        );
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    assert!(engine.eval::<String>(r#"format("{:x}", 1.5)"#).is_err());
+}
+
+#[test]
+fn test_format_widths() -> Result<(), Box<EvalAltResult>> {
+    #[allow(unused_mut)]
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("to_hex(255)")?, "ff");
+    assert_eq!(engine.eval::<String>("to_binary(10)")?, "1010");
+    assert_eq!(engine.eval::<String>("to_octal(64)")?, "100");
+    assert_eq!(
+        engine.eval::<String>("to_hex(-1)")?,
+        format!("{:x}", -1 as INT)
+    );
+
+    #[cfg(not(feature = "only_i32"))]
+    #[cfg(not(feature = "only_i64"))]
+    {
+        engine.register_fn("byte", |x: INT| x as u8);
+        engine.register_fn("small", |x: INT| x as i16);
+        engine.register_fn("huge", |x: INT| x as u128 * 1_000_000_000_000);
+
+        assert_eq!(engine.eval::<String>("to_hex(small(-1))")?, "ffff");
+        assert_eq!(engine.eval::<String>("to_binary(byte(5))")?, "101");
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:#04x}", byte(10))"#)?,
+            "0x0a"
+        );
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:e}", huge(1000000))"#)?,
+            "1e18"
+        );
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        engine.register_fn("single", |x: rhai::FLOAT| x as f32);
+        assert_eq!(
+            engine.eval::<String>(r#"format("{:.3}", single(0.5))"#)?,
+            "0.500"
+        );
+    }
+
+    Ok(())
+}
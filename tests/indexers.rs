@@ -0,0 +1,121 @@
+#![cfg(not(feature = "no_index"))]
+#![cfg(not(feature = "no_object"))]
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+
+#[derive(Debug, Clone, Default)]
+struct Cell {
+    value: INT,
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    cells: Vec<Cell>,
+}
+
+#[derive(Debug, Clone)]
+struct Grid {
+    rows: Vec<Row>,
+}
+
+impl Grid {
+    fn new() -> Self {
+        Grid {
+            rows: vec![
+                Row {
+                    cells: vec![Default::default(); 3]
+                };
+                2
+            ],
+        }
+    }
+}
+
+#[test]
+fn test_indexer() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_type::<Grid>();
+    engine.register_type::<Row>();
+    engine.register_type::<Cell>();
+    engine.register_fn("new_grid", Grid::new);
+
+    engine.register_indexer_get_set(
+        |grid: &mut Grid, index: INT| grid.rows[index as usize].clone(),
+        |grid: &mut Grid, index: INT, row: Row| grid.rows[index as usize] = row,
+    );
+    engine.register_indexer_get_set(
+        |row: &mut Row, index: INT| row.cells[index as usize].clone(),
+        |row: &mut Row, index: INT, cell: Cell| row.cells[index as usize] = cell,
+    );
+    engine.register_indexer_get(|cell: &mut Cell, _: String| cell.value);
+
+    engine.register_get_set(
+        "value",
+        |cell: &mut Cell| cell.value,
+        |cell: &mut Cell, value: INT| cell.value = value,
+    );
+
+    assert_eq!(engine.eval::<INT>("let g = new_grid(); g[1][2][\"x\"]")?, 0);
+
+    // Chains of indexers
+    assert_eq!(
+        engine.eval::<INT>("let g = new_grid(); g[1][2].value = 3; g[1][2].value")?,
+        3
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            "let g = new_grid(); g[0][1].value += 40; g[0][1].value += 2; g[0][1].value"
+        )?,
+        42
+    );
+
+    let grid = engine.eval::<Grid>("let g = new_grid(); g[1][0].value = 42; g")?;
+    assert_eq!(grid.rows[1].cells[0].value, 42);
+    assert_eq!(grid.rows[0].cells[0].value, 0);
+
+    // Assigning through an indexer
+    let grid =
+        engine.eval::<Grid>("let g = new_grid(); let r = g[0]; r[2] = g[1][1]; g[1] = r; g")?;
+    assert_eq!(grid.rows[1].cells.len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_indexer_errors() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_type::<Grid>();
+    engine.register_fn("new_grid", Grid::new);
+
+    // No indexer registered
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let g = new_grid(); g[0]")
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+
+    // Read-only indexer
+    engine.register_indexer_get(|grid: &mut Grid, index: INT| {
+        grid.rows[index as usize].cells.len() as INT
+    });
+
+    assert_eq!(engine.eval::<INT>("let g = new_grid(); g[0]")?, 3);
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let g = new_grid(); g[0] = 1; 0")
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+
+    // Wrong index type
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"let g = new_grid(); g["x"]"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 #![cfg(not(feature = "no_function"))]
 
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[cfg(not(feature = "no_object"))]
+use rhai::RegisterFn;
 
 #[test]
 fn test_internal_fn() -> Result<(), Box<EvalAltResult>> {
@@ -55,3 +58,177 @@ fn test_internal_fn_overloading() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_internal_fn_overloading_custom_types() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Clone)]
+    struct Square(INT);
+
+    #[derive(Clone)]
+    struct Circle(INT);
+
+    let mut engine = Engine::new();
+
+    engine.register_type_with_name::<Square>("Square");
+    engine.register_type_with_name::<Circle>("Circle");
+    engine.register_fn("new_square", Square);
+    engine.register_fn("new_circle", Circle);
+    engine.register_get("size", |x: &mut Square| x.0);
+    engine.register_get("size", |x: &mut Circle| x.0);
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn area(s: Square) { s.size * s.size }
+                fn area(c: Circle) { 3 * c.size * c.size }
+
+                area(new_square(2)) + area(new_circle(10))
+            "#
+        )?,
+        304
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_internal_fn_overloading_types() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                fn describe(x: char) { "character" }
+                fn describe(x: string) { "string" }
+                fn describe(x) { type_of(x) }
+
+                describe('x') + "," + describe("hello") + "," + describe(true)
+            "#
+        )?,
+        "character,string,bool"
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn pick(x, y) { 1 }
+                fn pick(x: bool, y) { 2 }
+                fn pick(x: bool, y: char) { 3 }
+                fn pick(x: bool, y) { 4 }     // should override previous definition
+
+                pick(1, 2) * 100 + pick(true, 2) * 10 + pick(true, 'x')
+            "#
+        )?,
+        143
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"fn only(x: bool) { 1 } only("hello")"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f == "only (string)"
+    ));
+
+    assert!(matches!(
+        *engine
+            .compile("fn bad(x: ) { 1 }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::FnMissingParamType(_, _)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_internal_fn_default_params() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                fn greet(name, greeting = "hi", mark = "!") { greeting + ", " + name + mark }
+
+                greet("Bob") + " " + greet("Bob", "hello") + " " + greet("Bob", "hey", "?")
+            "#
+        )?,
+        "hi, Bob! hello, Bob! hey, Bob?"
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn scale(x, factor = x * 2) { x * factor }
+                scale(3) + scale(3, 10)
+            "#
+        )?,
+        48
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn pick(x) { 1 }
+                fn pick(x, y = 0) { 2 }
+
+                pick(42) * 10 + pick(42, 0)
+            "#
+        )?,
+        12
+    );
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#"fn foo(x, y = 1) { x + y } foo(1, 2, 3)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f.starts_with("foo (")
+    ));
+
+    assert!(matches!(
+        engine
+            .compile("fn bad(x = 1, y) { x + y }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::MissingToken(t, _) if t == "="
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_internal_fn_rest_params() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn sum(first, ...nums) {
+                    let total = first;
+                    for n in nums { total += n; }
+                    total
+                }
+
+                sum(1) + sum(1, 2, 3, 4) * 100
+            "#
+        )?,
+        1001
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn count(...items) { len(items) }
+                fn count(x) { -1 }
+
+                count() + count(1, 2, 3) * 10 + count(1) * 100
+            "#
+        )?,
+        -70
+    );
+
+    assert!(engine.compile("fn bad(...rest, x) { x }").is_err());
+
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use rhai::{Dynamic, Engine, EvalAltResult, ParseErrorType, RegisterFn, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+#[cfg(not(feature = "no_object"))]
+use rhai::Map;
+
+fn json_error(engine: &Engine, json: &str) -> (String, Option<usize>, Option<usize>) {
+    match *engine.parse_json_value(json).expect_err("should error") {
+        EvalAltResult::ErrorParsing(err) => match err.error_type() {
+            ParseErrorType::BadInput(message) => (
+                message.clone(),
+                err.position().line(),
+                err.position().position(),
+            ),
+            err => panic!("wrong parse error: {:?}", err),
+        },
+        err => panic!("wrong error: {}", err),
+    }
+}
+
+#[test]
+fn test_json_scalars() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.parse_json_value("42")?.cast::<INT>(), 42);
+    assert_eq!(engine.parse_json_value(" -7 ")?.cast::<INT>(), -7);
+    assert!(engine.parse_json_value("true")?.cast::<bool>());
+    assert!(engine.parse_json_value("null")?.is::<()>());
+    assert_eq!(
+        engine
+            .parse_json_value(r#""a\"b\\c\/d\n\tAé😀""#)?
+            .cast::<String>(),
+        "a\"b\\c/d\n\tAé😀"
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(
+            engine.parse_json_value("1.5e2")?.cast::<rhai::FLOAT>(),
+            150.0
+        );
+        assert_eq!(
+            engine.parse_json_value("-0.25")?.cast::<rhai::FLOAT>(),
+            -0.25
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_json_nested() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let array = engine
+        .parse_json_value(r#" [ 1, "two", null, { "a": [ true, {} ], "a": [] }, [] ] "#)?
+        .cast::<Array>();
+
+    assert_eq!(array.len(), 5);
+    assert_eq!(array[0].as_int().unwrap(), 1);
+    assert_eq!(array[1].as_str().unwrap(), "two");
+    assert!(array[2].is::<()>());
+
+    // Duplicate keys - the last one wins
+    let map = array[3].downcast_ref::<Map>().unwrap();
+    assert_eq!(map.len(), 1);
+    assert!(map
+        .get("a")
+        .unwrap()
+        .downcast_ref::<Array>()
+        .unwrap()
+        .is_empty());
+
+    assert!(array[4].downcast_ref::<Array>().unwrap().is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_json_parse_map() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let map = engine.parse_json(r#"{"a": null, "b": {"c": "d"}}"#, true)?;
+    assert!(map.get("a").unwrap().is::<()>());
+    assert!(map.get("b").unwrap().is::<Map>());
+
+    assert!(matches!(
+        *engine.parse_json(r#"{"a": null}"#, false).expect_err("should error"),
+        EvalAltResult::ErrorParsing(err) if err.to_string().contains("null values are not allowed")
+    ));
+    assert!(matches!(
+        *engine.parse_json("[1, 2]", true).expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(need, actual, _) if need == "map" && actual == "array"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_json_errors() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        json_error(&engine, "{\n  \"a\": 1,\n}"),
+        (
+            "Invalid JSON: unexpected character '}'".into(),
+            Some(3),
+            Some(1)
+        )
+    );
+    assert_eq!(
+        json_error(&engine, "[1, 2"),
+        (
+            "Invalid JSON: unexpected end of input".into(),
+            Some(1),
+            Some(5)
+        )
+    );
+    assert_eq!(
+        json_error(&engine, "1 2").0,
+        "Invalid JSON: unexpected character '2'"
+    );
+    assert_eq!(
+        json_error(&engine, "\"abc").0,
+        "Invalid JSON: unterminated string"
+    );
+    assert_eq!(
+        json_error(&engine, "\"a\tb\"").0,
+        "Invalid JSON: control character '\\t' must be escaped"
+    );
+    assert_eq!(
+        json_error(&engine, r#""\ud83d""#).0,
+        "Invalid JSON: invalid surrogate pair in string"
+    );
+
+    // Numbers must follow the JSON grammar
+    for json in &["01", "+1", ".5", "1.", "1e", "-", "NaN", "0x10"] {
+        json_error(&engine, json);
+    }
+
+    // Numbers too large for a floating-point value
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(
+        json_error(&engine, "[1, -1e400]"),
+        (
+            "Invalid JSON: number '-1e400' is out of range".into(),
+            Some(1),
+            Some(10)
+        )
+    );
+
+    // Comments and single quotes are not JSON
+    json_error(&engine, "[1 // one\n]");
+    json_error(&engine, "'a'");
+
+    let deep = "[".repeat(rhai::MAX_JSON_DEPTH + 1) + &"]".repeat(rhai::MAX_JSON_DEPTH + 1);
+    assert_eq!(
+        json_error(&engine, &deep).0,
+        format!(
+            "Invalid JSON: nesting deeper than {} levels",
+            rhai::MAX_JSON_DEPTH
+        )
+    );
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_json_to_json() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let value = engine.eval::<Dynamic>(r#"[1, "a\"\\\n\u0001", 'x', true, (), [], #{}]"#)?;
+    let json = engine.to_json(&value)?;
+    assert_eq!(json, r#"[1,"a\"\\\n\u0001","x",true,null,[],{}]"#);
+
+    let value = engine.eval::<Dynamic>(r#"#{ list: [1, #{ a: () }] }"#)?;
+    assert_eq!(
+        engine.to_json_pretty(&value, 2)?,
+        "{\n  \"list\": [\n    1,\n    {\n      \"a\": null\n    }\n  ]\n}"
+    );
+
+    // Round-trip
+    let json = r#"{"a":[1,true,null,"x\u001f",{"c":"é"}]}"#;
+    let value = engine.parse_json_value(json)?;
+    assert_eq!(engine.to_json(&value)?, json);
+    assert_eq!(
+        engine.to_json(&engine.parse_json_value(&engine.to_json_pretty(&value, 4)?)?)?,
+        json
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_json_to_json_errors() {
+    #[derive(Clone)]
+    struct Foo;
+
+    #[allow(unused_mut)]
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        engine.register_type_with_name::<Foo>("Foo");
+
+        assert!(matches!(
+            *engine.to_json(&Dynamic::from(Foo)).expect_err("should error"),
+            EvalAltResult::ErrorMismatchDataType(need, actual, _) if need == "JSON value" && actual == "Foo"
+        ));
+    }
+
+    assert!(engine.to_json(&Dynamic::from(Foo)).is_err());
+
+    #[cfg(not(feature = "no_float"))]
+    assert!(matches!(
+        *engine
+            .to_json(&Dynamic::from(rhai::FLOAT::NAN))
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(_, _, _)
+    ));
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let mut value = Dynamic::from(Array::new());
+        for _ in 1..rhai::MAX_JSON_DEPTH {
+            value = Dynamic::from(vec![value]);
+        }
+        assert!(engine.to_json(&value).is_ok());
+
+        value = Dynamic::from(vec![value]);
+        assert!(matches!(
+            *engine.to_json(&value).expect_err("should error"),
+            EvalAltResult::ErrorRuntime(_, _)
+        ));
+    }
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_json_script() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let x = #{ a: [1, "\"hi\""] }; x.to_json()"#)?,
+        r#"{"a":[1,"\"hi\""]}"#
+    );
+    assert_eq!(
+        engine.eval::<String>("let x = [1, [2]]; x.to_json(1)")?,
+        "[\n 1,\n [\n  2\n ]\n]"
+    );
+    assert_eq!(engine.eval::<String>("to_json([], -1)")?, "[]");
+    assert_eq!(engine.eval::<String>("to_json([1], -1)")?, "[\n1\n]");
+    assert_eq!(
+        engine.eval::<String>("to_json([1], 1000000)")?,
+        format!("[\n{}1\n]", " ".repeat(rhai::MAX_JSON_INDENT))
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_json_script_errors() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Clone)]
+    struct Foo;
+
+    let mut engine = Engine::new();
+    engine.register_type_with_name::<Foo>("Foo");
+    engine.register_fn("new_foo", || Foo);
+
+    assert!(matches!(
+        *engine.eval::<String>("let x = [new_foo()]; x.to_json()").expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(need, actual, pos)
+            if need == "JSON value" && actual == "Foo" && !pos.is_none()
+    ));
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
 
 #[test]
 fn test_loop() -> Result<(), Box<EvalAltResult>> {
@@ -28,3 +28,109 @@ fn test_loop() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_loop_labels() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 0;
+
+                'outer: for i in range(0, 10) {
+                    for j in range(0, 10) {
+                        if j > i { continue 'outer; }
+                        if i == 5 { break 'outer; }
+                        x += 1;
+                    }
+                }
+
+                x
+            "
+        )?,
+        15
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 0;
+                'a: while true {
+                    'b: loop {
+                        do { x += 1; break 'b; } until false;
+                    }
+                    if x > 2 { break 'a; }
+                }
+                x
+            "
+        )?,
+        3
+    );
+
+    assert!(matches!(
+        engine
+            .compile("loop { break 'outer; }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::LoopLabelNotFound(label) if label == "outer"
+    ));
+
+    // Labels do not reach into function bodies
+    #[cfg(not(feature = "no_function"))]
+    assert!(matches!(
+        engine
+            .compile("fn foo() { continue 'outer; } 'outer: loop { foo(); }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::LoopBreak
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_loop_break_value() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let i = 0;
+                let x = loop {
+                    i += 1;
+                    if i * i > 40 { break i; }
+                };
+                x
+            "
+        )?,
+        7
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 'outer: loop {
+                    for i in range(0, 10) {
+                        if i == 3 { break 'outer i * 14; }
+                    }
+                };
+                x
+            "
+        )?,
+        42
+    );
+
+    assert!(engine.eval::<bool>("let x = loop { break; }; x == ()")?);
+    assert_eq!(engine.eval::<INT>("loop { break 42; }")?, 42);
+
+    assert!(matches!(
+        engine
+            .compile("while true { break 42; }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::LoopBreakValue
+    ));
+
+    Ok(())
+}
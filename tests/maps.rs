@@ -242,3 +242,187 @@ fn test_map_json() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_map_functions() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let x = #{a: 1}; x.get(\"a\", 42) + x.get(\"b\", 42)")?,
+        43
+    );
+    assert!(engine.eval::<bool>(r#"let x = #{a: 1}; x.get("b") == ()"#)?);
+    assert_eq!(
+        engine.eval::<String>(r#"let x = #{}; x.set("a", "hello"); x.a"#)?,
+        "hello"
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"let x = #{a: 1, b: 2}; x.fill_with(#{b: 20, c: 30}); x.a + x.b + x.c"#
+        )?,
+        33
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let x = #{a: #{b: 1, c: 2}, d: 3};
+                x.merge(#{a: #{c: 20, e: 30}, d: #{f: 4}});
+                x.a.b + x.a.c + x.a.e + x.d.f
+            "#
+        )?,
+        55
+    );
+    assert_eq!(
+        engine.eval::<INT>(r#"let x = #{a: 1}; x.mixin(#{a: #{b: 2}}); x.a.len()"#)?,
+        1
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_map_callbacks() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let x = #{a: 1, b: 2, c: 3, d: 4};
+                let y = x.filter(|k, v| v % 2 == 0 && k != "d");
+                y.len() * 10 + y.b
+            "#
+        )?,
+        12
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let x = #{a: 1, b: 2};
+                let y = x.map_values(|k, v| if k == "a" { v * 10 } else { v });
+                y.a + y.b + x.a
+            "#
+        )?,
+        13
+    );
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let x = #{a: 1, b: 2, c: 3};
+                let y = x.drain(|k, v| v > 1);
+                x.len() * 100 + y.len() * 10 + y.c
+            "#
+        )?,
+        123
+    );
+    assert!(matches!(
+        *engine
+            .eval::<Map>("let x = #{a: 1}; x.filter(|k, v| v)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(need, _, _) if need == "bool"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_map_iteration() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                let x = #{a: 1, b: 2, c: 3};
+                let sum = 0;
+                for (k, v) in x { if k != "b" { sum += v } }
+                sum
+            "#
+        )?,
+        4
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = ""; for (k, v) in #{abc: 42} { s = k + v } s"#)?,
+        "abc42"
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(
+            engine.eval::<INT>(
+                r#"
+                    let x = #{a: 1, b: 2};
+                    let sum = 0;
+                    for [k, v] in x { sum += v }
+                    for pair in x.to_array() { sum += pair[1] * 10 }
+                    sum
+                "#
+            )?,
+            33
+        );
+        assert_eq!(engine.eval::<INT>("let (a, b) = [40, 2]; a + b")?, 42);
+    }
+
+    assert!(engine.compile("for (k, v in x {}").is_err());
+    assert!(engine.compile("for () in x {}").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_map_order() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let x = #{z: 1, a: 2, m: 3}; x.b = 4; x.a = 5; x.to_string()"#)?,
+        r#"#{"z": 1, "a": 5, "m": 3, "b": 4}"#
+    );
+    assert_eq!(
+        engine.eval::<String>(
+            r#"
+                let x = #{z: 1, a: 2, m: 3, q: 4};
+                x.remove("a");
+                x.c = 5;
+                let s = "";
+                for (k, v) in x { s += k + v }
+                s
+            "#
+        )?,
+        "z1m3q4c5"
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval::<String>(r#"let x = #{c: 1, b: 2, a: 3}; let k = x.keys(); k.join(",")"#)?,
+        "c,b,a"
+    );
+
+    let json = r#"{"z":1,"y":[true,{"b":null,"a":"x"}],"x":{}}"#;
+    let map = engine.parse_json(json, true)?;
+    assert_eq!(
+        map.keys().map(String::as_str).collect::<Vec<_>>(),
+        vec!["z", "y", "x"]
+    );
+    assert_eq!(engine.to_json(&map.into())?, json);
+
+    let mut map = Map::new();
+    map.insert("b".into(), (1 as INT).into());
+    map.insert("a".into(), (2 as INT).into());
+    map.insert("c".into(), (3 as INT).into());
+    assert_eq!(map.remove("a").unwrap().as_int().unwrap(), 2);
+    map.insert("a".into(), (4 as INT).into());
+    assert_eq!(
+        map.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+        vec!["b", "c", "a"]
+    );
+    assert_eq!(map["c"].as_int().unwrap(), 3);
+    assert!(map.get("x").is_none());
+
+    let mut scope = Scope::new();
+    scope.push("map", map);
+    assert_eq!(
+        engine.eval_with_scope::<String>(&mut scope, r#"map.d = 5; map.to_json()"#)?,
+        r#"{"b":1,"c":3,"a":4,"d":5}"#
+    );
+
+    Ok(())
+}
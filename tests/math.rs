@@ -99,3 +99,148 @@ fn test_math() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_math_integer() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("min(3, -7) + max(3, -7)")?, -4);
+    assert_eq!(engine.eval::<INT>("clamp(42, 0, 10)")?, 10);
+    assert_eq!(engine.eval::<INT>("clamp(-42, 0, 10)")?, 0);
+    assert_eq!(engine.eval::<INT>("clamp(5, 0, 10)")?, 5);
+    assert_eq!(
+        engine.eval::<INT>("sign(-9) * 100 + sign(0) * 10 + sign(9)")?,
+        -99
+    );
+    assert_eq!(engine.eval::<INT>("gcd(12, -18)")?, 6);
+    assert_eq!(engine.eval::<INT>("gcd(0, -5)")?, 5);
+    assert_eq!(engine.eval::<INT>("lcm(-4, 6)")?, 12);
+    assert_eq!(engine.eval::<INT>("lcm(0, 6)")?, 0);
+    assert_eq!(engine.eval::<INT>("pow(3, 4)")?, 81);
+    assert_eq!(
+        engine.eval::<INT>("sqrt_int(99) * 10 + sqrt_int(100)")?,
+        100
+    );
+    assert_eq!(engine.eval::<INT>("sqrt_int(0) + sqrt_int(1)")?, 1);
+    assert_eq!(
+        engine.eval::<INT>(&format!("sqrt_int({})", INT::MAX))?,
+        (INT::MAX as f64).sqrt() as INT
+    );
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(
+            engine.eval::<rhai::FLOAT>("max(1.5, 2.5) - min(1.5, 2.5)")?,
+            1.0
+        );
+        assert_eq!(engine.eval::<rhai::FLOAT>("clamp(1.75, 0.0, 1.0)")?, 1.0);
+        assert_eq!(engine.eval::<INT>("sign(-0.5)")?, -1);
+        assert_eq!(
+            engine.eval::<rhai::FLOAT>("pow(2.0, 3) + pow(4.0, 0.5)")?,
+            10.0
+        );
+    }
+
+    assert!(matches!(
+        *engine.eval::<INT>("clamp(1, 10, 0)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg == "Invalid range for clamp: 10 > 0"
+    ));
+    assert!(matches!(
+        *engine.eval::<INT>("sqrt_int(-1)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg == "Square root of a negative number: -1"
+    ));
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        assert!(matches!(
+            *engine.eval::<INT>("pow(2, 200)").expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Power overflow")
+        ));
+        assert!(matches!(
+            *engine.eval::<INT>("pow(2, -1)").expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(_, _)
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<INT>(&format!("gcd({}, 0)", INT::MIN))
+                .expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Integer overflow: gcd")
+        ));
+        assert!(matches!(
+            *engine
+                .eval::<INT>(&format!("lcm({}, {})", INT::MAX, INT::MAX - 1))
+                .expect_err("should error"),
+            EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Integer overflow: lcm")
+        ));
+        assert_eq!(engine.eval::<INT>(&format!("gcd({}, -1)", INT::MIN))?, 1);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_math_bits() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+    let bits = INT::MIN.count_zeros() as INT + 1;
+
+    assert_eq!(engine.eval::<INT>("count_ones(0b1011)")?, 3);
+    assert_eq!(engine.eval::<INT>("count_ones(-1)")?, bits);
+    assert_eq!(engine.eval::<INT>("leading_zeros(1)")?, bits - 1);
+    assert_eq!(engine.eval::<INT>("trailing_zeros(0b1000)")?, 3);
+    assert_eq!(engine.eval::<INT>("trailing_zeros(0)")?, bits);
+    assert_eq!(engine.eval::<INT>("rotate_left(0b1011, 2)")?, 0b101100);
+    assert_eq!(engine.eval::<INT>("rotate_right(0b1011, -2)")?, 0b101100);
+    assert_eq!(engine.eval::<INT>("rotate_right(1, 1)")?, INT::MIN);
+    assert_eq!(
+        engine.eval::<INT>(&format!("rotate_left(1, {})", bits + 3))?,
+        0b1000
+    );
+
+    assert!(engine.eval::<bool>("get_bit(0b100, 2)")?);
+    assert!(!engine.eval::<bool>("get_bit(0b100, 1)")?);
+    assert!(engine.eval::<bool>(&format!("get_bit(-1, {})", bits - 1))?);
+    assert_eq!(
+        engine.eval::<INT>("set_bit(set_bit(0b1010, 0, true), 3, false)")?,
+        0b0011
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!("set_bit(0, {}, true)", bits - 1))?,
+        INT::MIN
+    );
+    assert_eq!(engine.eval::<INT>("get_bits(0b110110, 1, 3)")?, 0b011);
+    assert_eq!(engine.eval::<INT>("get_bits(-1, 0, 4)")?, 0b1111);
+    assert_eq!(
+        engine.eval::<INT>(&format!("get_bits(-1, 0, {})", bits))?,
+        -1
+    );
+    assert_eq!(
+        engine.eval::<INT>(&format!("get_bits(-1, {}, 0)", bits))?,
+        0
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; let y = x.set_bit(4, true); y.get_bits(3, 2)")?,
+        0b10
+    );
+
+    assert!(matches!(
+        *engine.eval::<bool>("get_bit(1, -1)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg.starts_with("Bit index -1 out of range")
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(&format!("set_bit(0, {}, true)", bits))
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(&format!("get_bits(1, 1, {})", bits))
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _)
+            if msg == format!("Bit field of {} bits at index 1 out of range for a {}-bit integer", bits, bits)
+    ));
+
+    Ok(())
+}
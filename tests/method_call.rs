@@ -38,3 +38,65 @@ fn test_method_call() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_method_call_this() -> Result<(), Box<EvalAltResult>> {
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    struct TestStruct {
+        x: INT,
+    }
+
+    impl TestStruct {
+        fn get_x(&mut self) -> INT {
+            self.x
+        }
+        fn set_x(&mut self, new_x: INT) {
+            self.x = new_x;
+        }
+        fn new() -> Self {
+            TestStruct { x: 1 }
+        }
+    }
+
+    let mut engine = Engine::new();
+
+    engine.register_type::<TestStruct>();
+    engine.register_get_set("x", TestStruct::get_x, TestStruct::set_x);
+    engine.register_fn("new_ts", TestStruct::new);
+
+    assert_eq!(
+        engine.eval::<TestStruct>("fn inc(n) { this.x += n } let a = new_ts(); a.inc(41); a")?,
+        TestStruct { x: 42 }
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                fn inc() { this.count += 1; this.count }
+                let m = #{ count: 40 };
+                m.inc();
+                m.inc() + m.count
+            "
+        )?,
+        84
+    );
+
+    assert_eq!(
+        engine.eval::<INT>("fn reset() { this = 0; } let x = 42; x.reset(); x")?,
+        0
+    );
+
+    // Called as a method only when the number of parameters excludes the object
+    assert_eq!(
+        engine.eval::<INT>("fn change(x) { x = 0; } let x = 42; x.change(); x")?,
+        42
+    );
+
+    assert!(matches!(
+        *engine.eval::<INT>("fn foo() { this } foo()").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "this"
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+#![cfg(not(feature = "no_function"))]
+use rhai::{Engine, EvalAltResult, NativeCallContext, RegisterContextFn, INT};
+
+#[cfg(not(feature = "no_optimize"))]
+use rhai::OptimizationLevel;
+
+#[test]
+fn test_native_context_call_fn() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_context_fn(
+        "apply",
+        |context: &NativeCallContext, x: INT, callback: String| {
+            context.call_fn(&callback, &mut [&mut x.into()])
+        },
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"
+                fn double(x) { x * 2 }
+                apply(21, "double")
+            "#
+        )?,
+        42
+    );
+
+    // Registered functions can be called as well
+    assert_eq!(engine.eval::<String>(r#"apply(42, "to_string")"#)?, "42");
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#"apply(42, "triple")"#).expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(ref f, _) if f.starts_with("triple")
+    ));
+
+    // Not evaluated by the optimizer, where script-defined functions are not available
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        engine.set_optimization_level(OptimizationLevel::Full);
+
+        assert_eq!(
+            engine.eval::<INT>(
+                r#"
+                    fn double(x) { x * 2 }
+                    apply(21, "double")
+                "#
+            )?,
+            42
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_native_context_call_level() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_context_fn("call_level", |context: &NativeCallContext| {
+        Ok(context.call_level() as INT)
+    });
+
+    assert_eq!(engine.eval::<INT>("call_level()")?, 0);
+    assert_eq!(
+        engine.eval::<INT>("fn foo() { call_level() } fn bar() { foo() } bar()")?,
+        2
+    );
+
+    Ok(())
+}
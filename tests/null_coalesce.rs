@@ -0,0 +1,94 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+
+#[test]
+fn test_null_coalesce() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("() ?? 42")?, 42);
+    assert_eq!(engine.eval::<INT>("let x = 1; x ?? 42")?, 1);
+    assert_eq!(engine.eval::<INT>("let x; let y; x ?? y ?? 42")?, 42);
+    assert_eq!(engine.eval::<INT>("let x = 0; x ?? 42")?, 0);
+    assert!(!engine.eval::<bool>("let x = false; x ?? true")?);
+
+    // Lower precedence than other binary operators
+    assert_eq!(engine.eval::<INT>("let x; x ?? 40 + 2")?, 42);
+    assert!(engine.eval::<bool>("let x; x ?? 1 < 2 || false")?);
+
+    // Short-circuits the right-hand side
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; let y = 1 ?? { x = 42; 2 }; x + y")?,
+        1
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_safe_navigation_dot() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>("let m = #{ a: #{ b: 42 } }; m?.a?.b")?,
+        42
+    );
+    assert!(engine.eval::<bool>("let m = #{ a: 1 }; m?.x?.y == ()")?);
+    assert!(engine.eval::<bool>("let m; m?.a.b.c == ()")?);
+    assert_eq!(engine.eval::<INT>("let m; m?.a ?? 42")?, 42);
+    assert_eq!(
+        engine.eval::<INT>(r#"let m = #{ a: "hello" }; m?.a?.len()"#)?,
+        5
+    );
+    assert!(engine.eval::<bool>("let m; m?.len() == ()")?);
+
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let m = #{ a: 1 }; m.x.y")
+            .expect_err("should error"),
+        EvalAltResult::ErrorDotExpr(_, _) | EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    assert!(matches!(
+        engine
+            .compile("let m = #{ a: 1 }; m?.a = 42;")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::AssignmentToInvalidLHS
+    ));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_safe_navigation_index() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let a = [1, [2, 42]]; a?[1]?[1]")?, 42);
+    assert!(engine.eval::<bool>("let a; a?[1][2] == ()")?);
+    assert_eq!(engine.eval::<INT>("let a; a?[0] ?? 42")?, 42);
+
+    // Only () is short-circuited, other errors are still raised
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let m = #{ list: [] }; m?.list?[0]?.x ?? 42")
+            .expect_err("should error"),
+        EvalAltResult::ErrorArrayBounds(0, 0, _)
+    ));
+
+    assert!(matches!(
+        *engine.eval::<INT>("let a; a[1]").expect_err("should error"),
+        EvalAltResult::ErrorIndexingType(_, _)
+    ));
+
+    assert!(matches!(
+        engine
+            .compile("let a = [1]; a?[0] += 1;")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::AssignmentToInvalidLHS
+    ));
+
+    Ok(())
+}
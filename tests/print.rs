@@ -0,0 +1,93 @@
+#![cfg(not(feature = "no_object"))]
+
+use rhai::{Engine, EvalAltResult, RegisterFn, INT};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone)]
+struct Point {
+    x: INT,
+    y: INT,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[test]
+fn test_print_custom_type() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    let log = Arc::new(RwLock::new(Vec::<String>::new()));
+
+    let logger = log.clone();
+    engine.on_print(move |s| logger.write().unwrap().push(format!("print: {}", s)));
+    let logger = log.clone();
+    engine.on_debug(move |s| logger.write().unwrap().push(format!("debug: {}", s)));
+
+    engine.register_fn("point", |x: INT, y: INT| Point { x, y });
+
+    // Not formattable until registered
+    assert!(matches!(
+        *engine
+            .consume("print(point(1, 2))")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    engine.register_display::<Point>();
+    engine.register_debug::<Point>();
+
+    engine.consume("let p = point(1, 2); print(p); debug(p);")?;
+
+    assert_eq!(
+        *log.read().unwrap(),
+        vec!["print: (1, 2)", "debug: Point { x: 1, y: 2 }"]
+    );
+
+    assert_eq!(engine.eval::<String>("to_string(point(1, 2))")?, "(1, 2)");
+    assert_eq!(
+        engine.eval::<String>(r#""p = " + point(1, 2)"#)?,
+        "p = (1, 2)"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"point(1, 2) + " = p""#)?,
+        "(1, 2) = p"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let s = "p = "; s += point(3, 4); s"#)?,
+        "p = (3, 4)"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_print_type_names() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_type_with_name::<Point>("Point");
+    engine.register_fn("point", |x: INT, y: INT| Point { x, y });
+
+    assert!(matches!(
+        *engine.eval::<INT>("point(1, 2).len()").expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(f, _) if f == "len (Point)"
+    ));
+
+    // A `print` override returning the wrong type
+    engine.register_fn("print", |p: Point| p);
+
+    assert!(matches!(
+        *engine.consume("print(point(1, 2))").expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(t, _) if t == "Point"
+    ));
+
+    assert!(matches!(
+        *engine.eval::<INT>("point(1, 2)").expect_err("should error"),
+        EvalAltResult::ErrorMismatchOutputType(t, _) if t == "Point"
+    ));
+
+    Ok(())
+}
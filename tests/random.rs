@@ -0,0 +1,130 @@
+use rhai::packages::{Package, RandomPackage};
+use rhai::{Engine, EvalAltResult, INT};
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+
+fn engine_with_seed(seed: u64) -> Engine {
+    let mut engine = Engine::new();
+    engine.load_package(RandomPackage::with_seed(seed).get());
+    engine
+}
+
+#[test]
+fn test_random_seed() -> Result<(), Box<EvalAltResult>> {
+    #[cfg(not(feature = "no_index"))]
+    {
+        let script = "[rand(), rand(), rand_int(1, 100), rand_int(-5, 5)]";
+
+        let first = engine_with_seed(42).eval::<Array>(script)?;
+        let second = engine_with_seed(42).eval::<Array>(script)?;
+        let other = engine_with_seed(7).eval::<Array>(script)?;
+
+        let to_ints = |list: Array| {
+            list.into_iter()
+                .map(|v| v.cast::<INT>())
+                .collect::<Vec<_>>()
+        };
+
+        let first = to_ints(first);
+        assert_eq!(first, to_ints(second));
+        assert_ne!(first, to_ints(other));
+    }
+
+    // Functions in the same package share one generator
+    let engine = engine_with_seed(42);
+    let a = engine.eval::<INT>("rand()")?;
+    let b = engine.eval::<INT>("rand()")?;
+    assert_ne!(a, b);
+    assert_eq!(engine_with_seed(42).eval::<INT>("rand()")?, a);
+
+    // Unseeded packages work too
+    let mut engine = Engine::new();
+    engine.load_package(RandomPackage::new().get());
+    engine.eval::<INT>("rand()")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_random_int() -> Result<(), Box<EvalAltResult>> {
+    let engine = engine_with_seed(1);
+
+    assert!(engine.eval::<bool>(
+        r"
+            let ok = true;
+            for i in range(0, 1000) {
+                let x = rand_int(-3, 3);
+                if x < -3 || x > 3 { ok = false; }
+            }
+            ok
+        "
+    )?);
+
+    assert_eq!(engine.eval::<INT>("rand_int(9, 9)")?, 9);
+    engine.eval::<INT>(&format!("rand_int({}, {})", INT::MIN, INT::MAX))?;
+
+    assert!(matches!(
+        *engine.eval::<INT>("rand_int(2, 1)").expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(msg, _) if msg == "Invalid range for random number: 2 > 1"
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_float"))]
+fn test_random_float() -> Result<(), Box<EvalAltResult>> {
+    let engine = engine_with_seed(2);
+
+    assert!(engine.eval::<bool>(
+        r"
+            let ok = true;
+            for i in range(0, 1000) {
+                let x = rand_float();
+                let y = rand_float(-2.0, 3.0);
+                if x < 0.0 || x >= 1.0 || y < -2.0 || y >= 3.0 { ok = false; }
+            }
+            ok
+        "
+    )?);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_random_shuffle_sample() -> Result<(), Box<EvalAltResult>> {
+    let engine = engine_with_seed(3);
+
+    let sorted = |list: Array| {
+        let mut list: Vec<INT> = list.into_iter().map(|v| v.cast::<INT>()).collect();
+        list.sort();
+        list
+    };
+
+    let list = engine.eval::<Array>("let x = [1, 2, 3, 4, 5, 6, 7, 8]; shuffle(x); x")?;
+    assert_eq!(sorted(list), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let list = engine.eval::<Array>("sample([1, 2, 3, 4, 5, 6, 7, 8], 3)")?;
+    assert_eq!(list.len(), 3);
+    let mut list = sorted(list);
+    list.dedup();
+    assert_eq!(list.len(), 3);
+
+    let list = engine.eval::<Array>("sample([1, 2, 3], 10)")?;
+    assert_eq!(sorted(list), vec![1, 2, 3]);
+    assert!(engine.eval::<Array>("sample([1, 2, 3], -1)")?.is_empty());
+
+    let x = engine.eval::<INT>("sample([4, 5, 6])")?;
+    assert!(x >= 4 && x <= 6);
+    engine.eval::<()>("sample([])")?;
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("let x = [1, 2, 3]; x.shuffle(); let y = x.sample(2); y.len()")?,
+        2
+    );
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use rhai::{Dynamic, Engine, EvalAltResult, RegisterFn, INT};
+use std::any::TypeId;
+
+#[test]
+fn test_raw_fn() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_raw_fn(
+        "add_all",
+        &[
+            TypeId::of::<INT>(),
+            TypeId::of::<INT>(),
+            TypeId::of::<INT>(),
+        ],
+        |_, args| Ok(args.iter().map(|x| x.as_int().unwrap()).sum::<INT>().into()),
+    );
+
+    assert_eq!(engine.eval::<INT>("add_all(1, 2, 39)")?, 42);
+    assert!(matches!(
+        *engine
+            .eval::<INT>("add_all(1, 2)")
+            .expect_err("should error"),
+        EvalAltResult::ErrorFunctionNotFound(_, _)
+    ));
+
+    // Modify the first argument in place
+    engine.register_raw_fn(
+        "bump",
+        &[TypeId::of::<INT>(), TypeId::of::<INT>()],
+        |_, args| {
+            let y = args[1].clone().cast::<INT>();
+            let x = args[0].downcast_mut::<INT>().unwrap();
+            *x += y;
+            Ok(().into())
+        },
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<INT>("let x = 40; x.bump(2); x")?, 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_raw_fn_many_params() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // More parameters than `register_fn` supports
+    engine.register_raw_fn("sum", &[TypeId::of::<INT>(); 25], |_, args| {
+        Ok(args.iter().map(|x| x.as_int().unwrap()).sum::<INT>().into())
+    });
+
+    let args: Vec<_> = (1..=25).map(|x| x.to_string()).collect();
+
+    assert_eq!(
+        engine.eval::<INT>(&format!("sum({})", args.join(", ")))?,
+        325
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_raw_fn_dynamic_params() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.register_raw_fn(
+        "describe",
+        &[TypeId::of::<Dynamic>(), TypeId::of::<Dynamic>()],
+        |_, args| Ok(format!("any: {}, {}", args[0], args[1]).into()),
+    );
+    engine.register_raw_fn(
+        "describe",
+        &[TypeId::of::<INT>(), TypeId::of::<Dynamic>()],
+        |_, args| Ok(format!("int: {}, {}", args[0], args[1]).into()),
+    );
+    engine.register_fn("describe", |x: INT, y: INT| format!("ints: {}, {}", x, y));
+
+    assert_eq!(engine.eval::<String>("describe(1, 2)")?, "ints: 1, 2");
+    assert_eq!(engine.eval::<String>("describe(1, true)")?, "int: 1, true");
+    assert_eq!(engine.eval::<String>(r#"describe("a", 2)"#)?, "any: a, 2");
+    assert_eq!(engine.eval::<String>(r#"describe('x', "y")"#)?, "any: x, y");
+
+    // Many `Dynamic` parameters
+    engine.register_raw_fn("count", &[TypeId::of::<Dynamic>(); 20], |_, args| {
+        Ok((args.len() as INT).into())
+    });
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r#"count(1, "a", 'b', true, (), 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20)"#
+        )?,
+        20
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_raw_fn_context() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // Call a script-defined function by name with the remaining arguments
+    engine.register_raw_fn(
+        "call",
+        &[
+            TypeId::of::<String>(),
+            TypeId::of::<Dynamic>(),
+            TypeId::of::<Dynamic>(),
+        ],
+        |context, args| {
+            let fn_name = args[0].clone().cast::<String>();
+            context.call_fn(&fn_name, &mut args[1..])
+        },
+    );
+
+    assert_eq!(
+        engine.eval::<INT>(r#"fn add(x, y) { x + y } call("add", 40, 2)"#)?,
+        42
+    );
+
+    Ok(())
+}
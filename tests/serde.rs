@@ -0,0 +1,223 @@
+#![cfg(feature = "serde")]
+
+use rhai::{from_dynamic, to_dynamic, Dynamic, Engine, EvalAltResult, INT};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[cfg(not(feature = "no_index"))]
+use rhai::Array;
+#[cfg(not(feature = "no_object"))]
+use rhai::Map;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Numbers {
+    a: i8,
+    b: i16,
+    c: i32,
+    d: i64,
+    e: i128,
+    f: u8,
+    g: u16,
+    h: u32,
+    i: u64,
+    j: u128,
+    k: f32,
+    l: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Line(INT, INT),
+    Rect { width: INT, height: INT },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    enabled: bool,
+    initial: char,
+    retries: Option<u32>,
+    timeout: Option<u32>,
+    tags: Vec<String>,
+    limits: HashMap<String, INT>,
+    shapes: Vec<Shape>,
+    pair: (INT, String),
+}
+
+fn make_config() -> Config {
+    let mut limits = HashMap::new();
+    limits.insert("cpu".to_string(), 4);
+    limits.insert("memory".to_string(), 1024);
+
+    Config {
+        name: "test".into(),
+        enabled: true,
+        initial: 'x',
+        retries: Some(3),
+        timeout: None,
+        tags: vec!["hello".into(), "world".into()],
+        limits,
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Line(1, 2),
+            Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+        ],
+        pair: (42, "answer".into()),
+    }
+}
+
+#[test]
+fn test_serde_round_trip() -> Result<(), Box<EvalAltResult>> {
+    let config = make_config();
+    let value = to_dynamic(&config)?;
+
+    assert_eq!(value.type_name(), "map");
+    assert_eq!(from_dynamic::<Config>(&value)?, config);
+
+    let numbers = Numbers {
+        a: -1,
+        b: -2,
+        c: -3,
+        d: -4,
+        e: i128::MAX,
+        f: 1,
+        g: 2,
+        h: 3,
+        i: u64::MAX,
+        j: u128::MAX,
+        k: 1.5,
+        l: -2.25,
+    };
+    let value = to_dynamic(&numbers)?;
+
+    assert_eq!(from_dynamic::<Numbers>(&value)?, numbers);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_serde_to_dynamic() -> Result<(), Box<EvalAltResult>> {
+    let value = to_dynamic(make_config())?;
+    let map = value.cast::<Map>();
+
+    assert_eq!(map.get("name").unwrap().as_str().unwrap(), "test");
+    assert_eq!(map.get("retries").unwrap().as_int().unwrap(), 3);
+    assert!(map.get("timeout").unwrap().is::<()>());
+    assert_eq!(
+        map.get("limits")
+            .unwrap()
+            .downcast_ref::<Map>()
+            .unwrap()
+            .get("memory")
+            .unwrap()
+            .as_int()
+            .unwrap(),
+        1024
+    );
+
+    let shapes = map.get("shapes").unwrap().downcast_ref::<Array>().unwrap();
+
+    // Unit variants are strings, other variants are single-entry maps
+    assert_eq!(shapes[0].as_str().unwrap(), "Empty");
+    assert!(shapes[2]
+        .downcast_ref::<Map>()
+        .unwrap()
+        .get("Line")
+        .unwrap()
+        .is::<Array>());
+    assert!(shapes[3]
+        .downcast_ref::<Map>()
+        .unwrap()
+        .get("Rect")
+        .unwrap()
+        .is::<Map>());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_serde_script() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    let value = engine.eval::<Dynamic>(
+        r#"
+            #{
+                name: "script",
+                enabled: false,
+                initial: 's',
+                retries: 5,
+                timeout: (),
+                tags: [],
+                limits: #{ cpu: 1 },
+                shapes: ["Empty", #{ Line: [1, 2] }, #{ Rect: #{ width: 3, height: 4 } }],
+                pair: [1, "one"]
+            }
+        "#,
+    )?;
+
+    let config: Config = from_dynamic(&value)?;
+
+    assert_eq!(config.name, "script");
+    assert!(!config.enabled);
+    assert_eq!(config.retries, Some(5));
+    assert_eq!(config.timeout, None);
+    assert_eq!(config.limits.get("cpu"), Some(&1));
+    assert_eq!(
+        config.shapes,
+        vec![
+            Shape::Empty,
+            Shape::Line(1, 2),
+            Shape::Rect {
+                width: 3,
+                height: 4
+            }
+        ]
+    );
+    assert_eq!(config.pair, (1, "one".into()));
+
+    // Borrowed strings
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        name: &'a str,
+    }
+
+    let borrowed: Borrowed = from_dynamic(&value)?;
+    assert_eq!(borrowed.name, "script");
+
+    Ok(())
+}
+
+#[test]
+fn test_serde_errors() -> Result<(), Box<EvalAltResult>> {
+    assert!(matches!(
+        *from_dynamic::<String>(&(42 as INT).into()).expect_err("should error"),
+        EvalAltResult::ErrorMismatchDataType(need, actual, _) if need == "string" && actual == std::any::type_name::<INT>()
+    ));
+    assert!(matches!(
+        *from_dynamic::<u8>(&(-1 as INT).into()).expect_err("should error"),
+        EvalAltResult::ErrorRuntime(_, _)
+    ));
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        let engine = Engine::new();
+        let value = engine.eval::<Dynamic>("#{ a: 1 }")?;
+
+        assert!(matches!(
+            *from_dynamic::<Numbers>(&value).expect_err("should error"),
+            EvalAltResult::ErrorRuntime(err, _) if err.contains("missing field")
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use rhai::{Engine, EvalAltResult, ParseErrorType, Scope, INT};
+
+#[test]
+fn test_strict_variables() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    // Undeclared variables only fail at runtime by default
+    assert!(engine.compile("if false { x }").is_ok());
+
+    engine.set_strict_variables(true);
+
+    assert_eq!(
+        *engine
+            .compile("if false { x }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("x".into())
+    );
+    assert_eq!(
+        *engine
+            .compile("let x = 42; { let y = x; } y")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("y".into())
+    );
+    assert_eq!(
+        *engine
+            .compile("z = 1;")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("z".into())
+    );
+    assert!(matches!(
+        *engine
+            .eval::<INT>("let x = 40; x + y")
+            .expect_err("should error"),
+        EvalAltResult::ErrorParsing(_)
+    ));
+
+    assert_eq!(engine.eval::<INT>("let x = 40; { let y = 2; x + y }")?, 42);
+    assert_eq!(
+        engine.eval::<INT>("let sum = 0; for x in range(0, 4) { sum += x; } sum")?,
+        6
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; let y = if x > 0 { x } else { 42 }; y")?,
+        42
+    );
+
+    // Function names and properties are not variables
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("let x = #{a: 40}; x.a + type_of(x).len() - 1")?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_strict_variables_scope() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    engine.set_strict_variables(true);
+
+    scope.push("x", 40 as INT);
+
+    assert_eq!(
+        *engine
+            .compile("x + 2")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("x".into())
+    );
+
+    let ast = engine.compile_with_scope(&scope, "x + 2")?;
+    assert_eq!(engine.eval_ast_with_scope::<INT>(&mut scope, &ast)?, 42);
+
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x += 1; x")?, 41);
+    assert_eq!(
+        engine.eval_expression_with_scope::<INT>(&mut scope, "x + 1")?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_strict_variables_functions() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    engine.set_strict_variables(true);
+
+    scope.push("x", 40 as INT);
+
+    assert_eq!(
+        engine.eval::<INT>("fn add(a, b) { let c = a + b; c } add(40, 2)")?,
+        42
+    );
+
+    // Functions cannot access the global scope
+    assert_eq!(
+        *engine
+            .compile_with_scope(&scope, "fn foo() { x } foo()")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("x".into())
+    );
+    assert_eq!(
+        *engine
+            .compile("let y = 1; fn foo() { y } foo()")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("y".into())
+    );
+
+    // 'this' is always available to methods
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval::<INT>("fn inc() { this += 1; } let x = 41; x.inc(); x")?,
+        42
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_strict_variables_resolver_and_syntax() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+
+    engine.set_strict_variables(true);
+
+    // Identifiers captured by custom syntax are not variables
+    engine
+        .register_custom_syntax(&["ifdef", "$ident$", "$block$"], |context, inputs| {
+            if context
+                .scope()
+                .contains(inputs[0].get_variable_name().unwrap())
+            {
+                context.eval_expression_tree(&inputs[1])
+            } else {
+                Ok(().into())
+            }
+        })
+        .unwrap();
+
+    assert_eq!(engine.eval::<INT>("let x = 0; ifdef y { x = 1; } x")?, 0);
+    assert_eq!(
+        *engine
+            .compile("ifdef y { y }")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("y".into())
+    );
+
+    // Deeply nested expressions are checked once
+    let depth = 64;
+    let script = format!("let x = 1; {}x{}", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(engine.eval::<INT>(&script)?, 1);
+    let script = format!("{}y{}", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(
+        *engine
+            .compile(&script)
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("y".into())
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        *engine
+            .compile("let x = #{a: [1]}; x.a[y]")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("y".into())
+    );
+
+    // Variables provided by a variable resolver must be declared
+    engine.on_var(|name, _, _| match name {
+        "answer" => Ok(Some((42 as INT).into())),
+        _ => Ok(None),
+    });
+
+    assert_eq!(
+        *engine
+            .compile("answer")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("answer".into())
+    );
+
+    engine.declare_external_var("answer");
+    engine.declare_external_var("question");
+
+    assert_eq!(engine.eval::<INT>("answer")?, 42);
+    assert!(matches!(
+        *engine.eval::<INT>("question").expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(name, _) if name == "question"
+    ));
+    assert_eq!(
+        *engine
+            .compile("answr")
+            .expect_err("should error")
+            .error_type(),
+        ParseErrorType::VariableUndefined("answr".into())
+    );
+
+    Ok(())
+}
@@ -140,3 +140,140 @@ fn test_string_substring() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[cfg(not(feature = "no_stdlib"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_more() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "Héllo ß"; x.to_upper() + x.to_lower()"#)?,
+        "HÉLLO SShéllo ß"
+    );
+    assert_eq!(engine.eval::<char>("'é'.to_upper()")?, 'É');
+    assert_eq!(engine.eval::<char>("'ß'.to_upper()")?, 'ß');
+
+    assert!(engine.eval::<bool>(r#""❤ hello".starts_with("❤ h")"#)?);
+    assert!(engine.eval::<bool>(r#""❤ hello".starts_with('❤')"#)?);
+    assert!(engine.eval::<bool>(r#""hello ❤".ends_with('❤')"#)?);
+    assert!(!engine.eval::<bool>(r#""hello ❤".ends_with("hello")"#)?);
+
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "  hi  "; x.trim_start(); x + "|""#)?,
+        "hi  |"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "  hi  "; x.trim_end(); x + "|""#)?,
+        "  hi|"
+    );
+
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "❤❤"; x.pad(5, '!'); x"#)?,
+        "❤❤!!!"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "❤❤"; x.pad(7, "ab"); x"#)?,
+        "❤❤ababa"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let x = "hello"; x.pad(2, '!'); x"#)?,
+        "hello"
+    );
+    assert_eq!(engine.eval::<String>(r#""❤-".repeat(3)"#)?, "❤-❤-❤-");
+    assert_eq!(engine.eval::<String>(r#""abc".repeat(-1)"#)?, "");
+
+    // Strings built by 'repeat' and 'pad' are limited in size
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#""abc".repeat(2147483647)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<String>(r#"let x = "a"; x.pad(2147483647, "❤"); x"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    assert_eq!(engine.eval::<INT>(r#"" 42 ".parse_int()"#)?, 42);
+    assert_eq!(engine.eval::<INT>(r#""-ff".parse_int(16)"#)?, -255);
+    assert_eq!(engine.eval::<INT>(r#""1010".parse_int(2)"#)?, 10);
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(
+        engine.eval::<rhai::FLOAT>(r#""-1.5e2".parse_float()"#)?,
+        -150.0
+    );
+
+    assert!(matches!(
+        *engine.eval::<INT>(r#""12x".parse_int()"#).expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(err, _) if err.starts_with("Cannot parse '12x' as an integer")
+    ));
+    assert!(matches!(
+        *engine
+            .eval::<INT>(r#""12".parse_int(37)"#)
+            .expect_err("should error"),
+        EvalAltResult::ErrorArithmetic(_, _)
+    ));
+
+    assert_eq!(
+        engine.eval::<String>(r#"let s = ""; for ch in "a❤b" { s += ch + "," } s"#)?,
+        "a,❤,b,"
+    );
+
+    Ok(())
+}
+
+#[cfg(not(feature = "no_stdlib"))]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_split_join() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<String>(r#"let a = "a,❤,,b".split(','); a.join("|")"#)?,
+        "a|❤||b"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let a = "a::b::c".split("::", 2); a.join('|')"#)?,
+        "a|b::c"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let a = "a::b::c".rsplit("::"); a.join("|")"#)?,
+        "c|b|a"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let a = "a::b::c".rsplit("::", 2); a.join("|")"#)?,
+        "c|a::b"
+    );
+    assert_eq!(
+        engine.eval::<String>(r#"let a = "key = ❤ = x".split_once(" = "); a.join("|")"#)?,
+        "key|❤ = x"
+    );
+    assert!(engine.eval::<()>(r#""key".split_once('=')"#).is_ok());
+    assert_eq!(
+        engine.eval::<String>(r#"let a = [1, 'a', "b", true, ()]; a.join(", ")"#)?,
+        "1, a, b, true, "
+    );
+    assert_eq!(engine.eval::<String>(r#"let a = []; a.join(",")"#)?, "");
+
+    assert_eq!(
+        engine.eval::<INT>(r#"let a = "h❤llo".chars(); a.len()"#)?,
+        5
+    );
+    assert_eq!(
+        engine.eval::<char>(r#"let a = "h❤llo".chars(); a[1]"#)?,
+        '❤'
+    );
+    assert_eq!(engine.eval::<INT>(r#"let a = "h❤".bytes(); a.len()"#)?, 4);
+    assert_eq!(engine.eval::<INT>(r#"let a = "h❤".bytes(); a[0]"#)?, 104);
+    assert_eq!(
+        engine.eval::<String>(r#"let a = "one\r\ntwo\n\nthree".lines(); a.join("|")"#)?,
+        "one|two||three"
+    );
+
+    Ok(())
+}
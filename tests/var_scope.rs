@@ -52,3 +52,88 @@ fn test_scope_eval() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_var_resolver() -> Result<(), Box<EvalAltResult>> {
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    scope.push("innocent", 1 as INT);
+    scope.push("chameleon", 123 as INT);
+    scope.push("DO_NOT_USE", 999 as INT);
+
+    engine.on_var(|name, index, context| match name {
+        "MYSTIC_NUMBER" => Ok(Some((42 as INT).into())),
+        "GREETING" => Ok(Some("hello".to_string().into())),
+        // Never consulted, because the variable is in the scope
+        "chameleon" | "DO_NOT_USE" => Ok(Some((0 as INT).into())),
+        "FORBIDDEN" => Err(Box::new(EvalAltResult::ErrorVariableNotFound(
+            name.to_string(),
+            Default::default(),
+        ))),
+        // Silly example - derive a variable from one in the scope, but only if not declared in the script
+        "double_chameleon" if index == 0 => Ok(context
+            .scope()
+            .get_value::<INT>("chameleon")
+            .map(|x| (x * 2).into())),
+        _ => Ok(None),
+    });
+
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "MYSTIC_NUMBER")?,
+        42
+    );
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "innocent")?, 1);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "chameleon")?, 123);
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "DO_NOT_USE")?,
+        999
+    );
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "double_chameleon")?,
+        246
+    );
+    assert_eq!(
+        engine.eval::<INT>("let MYSTIC_NUMBER = 1; MYSTIC_NUMBER")?,
+        1
+    );
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<INT>(&mut scope, "FORBIDDEN")
+            .expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(n, p) if n == "FORBIDDEN" && p.line() == Some(1)
+    ));
+    assert!(matches!(
+        *engine
+            .eval_with_scope::<INT>(&mut scope, "UNKNOWN")
+            .expect_err("should error"),
+        EvalAltResult::ErrorVariableNotFound(n, _) if n == "UNKNOWN"
+    ));
+
+    // Resolved variables are read-only
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "let x = MYSTIC_NUMBER; x += 1; x")?,
+        43
+    );
+    assert!(engine
+        .eval_with_scope::<INT>(&mut scope, "MYSTIC_NUMBER = 1; MYSTIC_NUMBER")
+        .is_err());
+
+    // Resolved variables can be used in indexing and property chains
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(
+        engine.eval_with_scope::<char>(&mut scope, "GREETING[1]")?,
+        'e'
+    );
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine.eval_with_scope::<INT>(&mut scope, "GREETING.len()")?,
+        5
+    );
+    #[cfg(not(feature = "no_index"))]
+    assert!(engine
+        .eval_with_scope::<INT>(&mut scope, "GREETING[0] = 'J';")
+        .is_err());
+
+    Ok(())
+}
@@ -24,3 +24,41 @@ fn test_while() -> Result<(), Box<EvalAltResult>> {
 
     Ok(())
 }
+
+#[test]
+fn test_do_while() -> Result<(), Box<EvalAltResult>> {
+    let engine = Engine::new();
+
+    assert_eq!(
+        engine.eval::<INT>(
+            r"
+                let x = 0;
+
+                do {
+                    x = x + 1;
+                    if x == 12 { continue; }
+                    x = x + 10;
+                } while x < 30;
+
+                x
+            ",
+        )?,
+        34
+    );
+
+    // The body always runs at least once
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; do { x += 1; } while false; x")?,
+        1
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; do { x += 1; } until true; x")?,
+        1
+    );
+    assert_eq!(
+        engine.eval::<INT>("let x = 0; do { x += 1; if x > 5 { break; } } until x == 10; x")?,
+        6
+    );
+
+    Ok(())
+}